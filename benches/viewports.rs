@@ -0,0 +1,68 @@
+//! Frame time of the headless plugin with 8 orbit cameras in 8 viewports,
+//! idle and while orbiting the active camera. Run with
+//! `cargo bench --features testing`.
+
+use std::time::{Duration, Instant};
+
+use bevy::{prelude::*, render::camera::Viewport};
+
+use bevy_blendy_cameras::{
+    testing::{set_active_camera, test_app, SimulatedInput},
+    OrbitCameraController,
+};
+
+const FRAMES: u32 = 2000;
+
+/// Spawn 8 orbit cameras in 8 viewports of 200x300 pixels, the first one
+/// being the active camera
+fn spawn_8_viewports(app: &mut App) {
+    let cameras = (0..8)
+        .map(|index| {
+            app.world_mut()
+                .spawn((
+                    Camera {
+                        viewport: Some(Viewport {
+                            physical_position: UVec2::new(
+                                index % 4 * 200,
+                                index / 4 * 300,
+                            ),
+                            physical_size: UVec2::new(200, 300),
+                            ..default()
+                        }),
+                        ..default()
+                    },
+                    Projection::default(),
+                    Transform::from_xyz(index as f32, 2.0, 5.0)
+                        .looking_at(Vec3::ZERO, Vec3::Y),
+                    OrbitCameraController::default(),
+                ))
+                .id()
+        })
+        .collect::<Vec<_>>();
+    set_active_camera(app.world_mut(), cameras[0], Vec2::new(200.0, 300.0));
+    app.update();
+}
+
+/// Mean duration of an update of `app`
+fn mean_update_time(app: &mut App, input: Option<Vec2>) -> Duration {
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        if let Some(delta) = input {
+            app.world_mut()
+                .resource_mut::<SimulatedInput>()
+                .orbit(delta);
+        }
+        app.update();
+    }
+    start.elapsed() / FRAMES
+}
+
+fn main() {
+    let mut app = test_app();
+    spawn_8_viewports(&mut app);
+    // Warm up
+    mean_update_time(&mut app, None);
+    let idle = mean_update_time(&mut app, None);
+    let orbiting = mean_update_time(&mut app, Some(Vec2::new(1.0, 0.5)));
+    println!("8 viewports: idle {idle:?} per frame, orbiting {orbiting:?}");
+}
@@ -10,9 +10,9 @@ use bevy::{
 };
 
 use bevy_blendy_cameras::{
-    BlendyCamerasPlugin, FlyCameraController, FrameEvent,
-    OrbitCameraController, SwitchProjection, SwitchToFlyController,
-    SwitchToOrbitController, Viewpoint, ViewpointEvent,
+    BlendyCamerasPlugin, ControllerMode, FlyCameraController, FrameEvent,
+    FrameMode, OrbitCameraController, SetCameraControllerEvent,
+    SwitchProjection, Viewpoint, ViewpointEvent,
 };
 
 // FIXME: Make fly mode work in ortho projection
@@ -36,6 +36,7 @@ const ORBIT_HELP_TEXT: &str = "\
     Press Middle Mouse button and drag to orbit camera\n\
     Press Shift + Middle Mouse button and drag to pan camera\n\
     Scoll the mouse wheel to zoom\n\
+    Press Ctrl + Middle Mouse button and drag to zoom\n\
     ";
 
 const FLY_HELP_TEXT: &str = "\
@@ -68,7 +69,7 @@ fn main() {
         ..default()
     });
     app.add_plugins(DefaultPlugins)
-        .add_plugins(BlendyCamerasPlugin)
+        .add_plugins(BlendyCamerasPlugin::default())
         .add_systems(Startup, setup_system)
         .add_systems(
             Update,
@@ -146,18 +147,17 @@ fn setup_system(
     commands.insert_resource(HelpText { help_text_entity });
 }
 
-// FIXME: Use the same event with parameter to switch
 fn switch_camera_controler_system(
     mut commands: Commands,
     key_input: Res<ButtonInput<KeyCode>>,
-    mut orbit_ev_writer: EventWriter<SwitchToOrbitController>,
-    mut fly_ev_writer: EventWriter<SwitchToFlyController>,
+    mut ev_writer: EventWriter<SetCameraControllerEvent>,
     mut help_text: ResMut<HelpText>,
     scene: Res<Scene>,
 ) {
     if key_input.just_pressed(KeyCode::KeyF) {
-        fly_ev_writer.send(SwitchToFlyController {
+        ev_writer.send(SetCameraControllerEvent {
             camera_entity: scene.camera_entity,
+            mode: ControllerMode::Fly,
         });
         change_help_text(
             format!("{GENERAL_HELP_TEXT}\n{FLY_HELP_TEXT}"),
@@ -166,8 +166,9 @@ fn switch_camera_controler_system(
         );
     }
     if key_input.just_pressed(KeyCode::KeyO) {
-        orbit_ev_writer.send(SwitchToOrbitController {
+        ev_writer.send(SetCameraControllerEvent {
             camera_entity: scene.camera_entity,
+            mode: ControllerMode::Orbit,
         });
         change_help_text(
             format!("{GENERAL_HELP_TEXT}\n{ORBIT_HELP_TEXT}"),
@@ -199,8 +200,10 @@ fn switch_camera_viewpoint_system(
         && key_input.pressed(KeyCode::Numpad1)
     {
         ev_writer.send(ViewpointEvent {
-            camera_entity: scene.camera_entity,
+            camera_entity: Some(scene.camera_entity),
             viewpoint: Viewpoint::Front,
+            duration: None,
+            relative_to: None,
         });
     }
     if (key_input.pressed(KeyCode::ShiftLeft)
@@ -208,8 +211,10 @@ fn switch_camera_viewpoint_system(
         && key_input.pressed(KeyCode::Numpad1)
     {
         ev_writer.send(ViewpointEvent {
-            camera_entity: scene.camera_entity,
+            camera_entity: Some(scene.camera_entity),
             viewpoint: Viewpoint::Back,
+            duration: None,
+            relative_to: None,
         });
     }
     if !key_input.pressed(KeyCode::ShiftLeft)
@@ -217,8 +222,10 @@ fn switch_camera_viewpoint_system(
         && key_input.pressed(KeyCode::Numpad3)
     {
         ev_writer.send(ViewpointEvent {
-            camera_entity: scene.camera_entity,
+            camera_entity: Some(scene.camera_entity),
             viewpoint: Viewpoint::Right,
+            duration: None,
+            relative_to: None,
         });
     }
     if (key_input.pressed(KeyCode::ShiftLeft)
@@ -226,8 +233,10 @@ fn switch_camera_viewpoint_system(
         && key_input.pressed(KeyCode::Numpad3)
     {
         ev_writer.send(ViewpointEvent {
-            camera_entity: scene.camera_entity,
+            camera_entity: Some(scene.camera_entity),
             viewpoint: Viewpoint::Left,
+            duration: None,
+            relative_to: None,
         });
     }
     if !key_input.pressed(KeyCode::ShiftLeft)
@@ -235,8 +244,10 @@ fn switch_camera_viewpoint_system(
         && key_input.pressed(KeyCode::Numpad7)
     {
         ev_writer.send(ViewpointEvent {
-            camera_entity: scene.camera_entity,
+            camera_entity: Some(scene.camera_entity),
             viewpoint: Viewpoint::Top,
+            duration: None,
+            relative_to: None,
         });
     }
     if (key_input.pressed(KeyCode::ShiftLeft)
@@ -244,8 +255,10 @@ fn switch_camera_viewpoint_system(
         && key_input.pressed(KeyCode::Numpad7)
     {
         ev_writer.send(ViewpointEvent {
-            camera_entity: scene.camera_entity,
+            camera_entity: Some(scene.camera_entity),
             viewpoint: Viewpoint::Bottom,
+            duration: None,
+            relative_to: None,
         });
     }
 }
@@ -263,16 +276,22 @@ fn frame_camera_system(
                         camera_entity: scene.camera_entity,
                         entities_to_be_framed: vec![scene.scene_entity],
                         include_children: true,
+                        precise: false,
+                        ignore_hidden: true,
+                        mode: FrameMode::KeepDirection,
+                        duration: None,
                     });
                 }
-                Key::Character(str) => {
-                    if str == "c" {
-                        ev_writer.send(FrameEvent {
-                            camera_entity: scene.camera_entity,
-                            entities_to_be_framed: vec![scene.cube_entity],
-                            include_children: false,
-                        });
-                    }
+                Key::Character(str) if str == "c" => {
+                    ev_writer.send(FrameEvent {
+                        camera_entity: scene.camera_entity,
+                        entities_to_be_framed: vec![scene.cube_entity],
+                        include_children: false,
+                        precise: false,
+                        ignore_hidden: true,
+                        mode: FrameMode::KeepDirection,
+                        duration: None,
+                    });
                 }
                 _ => {}
             }
@@ -1,6 +1,6 @@
 //! Full egui example with egui_dock
 
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use bevy::{
     ecs::{
@@ -13,9 +13,12 @@ use bevy::{
     winit::WinitSettings,
 };
 use bevy_blendy_cameras::{
-    BlendyCamerasPlugin, FlyCameraController, FrameEvent,
-    OrbitCameraController, SwitchProjection, SwitchToFlyController,
-    SwitchToOrbitController, Viewpoint, ViewpointEvent,
+    controller_toolbar_ui, navigation_gizmo_ui, BlendyCamerasPlugin,
+    FlyCameraController, FrameEvent, FrameMode, IsoCorner,
+    OrbitCameraController, SetCameraControllerEvent,
+    SnapToNearestViewpointEvent, SwitchProjection, SwitchToFlyController,
+    SwitchToOrbitController, Viewpoint, ViewpointDetectionConfig,
+    ViewpointEvent,
 };
 use bevy_egui::{egui, EguiContext, EguiPlugin};
 use egui_dock::{DockArea, DockState, NodeIndex, Style, SurfaceIndex};
@@ -28,7 +31,7 @@ fn main() {
     });
     app.add_plugins(DefaultPlugins)
         .add_plugins(EguiPlugin)
-        .add_plugins(BlendyCamerasPlugin)
+        .add_plugins(BlendyCamerasPlugin::default())
         .insert_resource(WinitSettings::desktop_app())
         .add_systems(Startup, setup_system)
         .add_systems(
@@ -160,6 +163,10 @@ impl TabViewer<'_> {
                                 camera_entity,
                                 entities_to_be_framed: vec![scene.scene_entity],
                                 include_children: true,
+                                precise: false,
+                                ignore_hidden: true,
+                                mode: FrameMode::KeepDirection,
+                                duration: Some(Duration::from_millis(300)),
                             });
                             ui.close_menu();
                         }
@@ -169,6 +176,10 @@ impl TabViewer<'_> {
                                 camera_entity,
                                 entities_to_be_framed: vec![scene.cube_entity],
                                 include_children: false,
+                                precise: false,
+                                ignore_hidden: true,
+                                mode: FrameMode::KeepDirection,
+                                duration: Some(Duration::from_millis(300)),
                             });
                             ui.close_menu();
                         }
@@ -181,46 +192,77 @@ impl TabViewer<'_> {
                         ui.menu_button("Viewpoint", |ui| {
                             if ui.button("Top").clicked() {
                                 self.world.send_event(ViewpointEvent {
-                                    camera_entity,
+                                    camera_entity: Some(camera_entity),
                                     viewpoint: Viewpoint::Top,
+                                    duration: Some(Duration::from_millis(300)),
+                                    relative_to: None,
                                 });
                                 ui.close_menu();
                             }
                             if ui.button("Bottom").clicked() {
                                 self.world.send_event(ViewpointEvent {
-                                    camera_entity,
+                                    camera_entity: Some(camera_entity),
                                     viewpoint: Viewpoint::Bottom,
+                                    duration: Some(Duration::from_millis(300)),
+                                    relative_to: None,
                                 });
                                 ui.close_menu();
                             }
                             if ui.button("Front").clicked() {
                                 self.world.send_event(ViewpointEvent {
-                                    camera_entity,
+                                    camera_entity: Some(camera_entity),
                                     viewpoint: Viewpoint::Front,
+                                    duration: Some(Duration::from_millis(300)),
+                                    relative_to: None,
                                 });
                                 ui.close_menu();
                             }
                             if ui.button("Back").clicked() {
                                 self.world.send_event(ViewpointEvent {
-                                    camera_entity,
+                                    camera_entity: Some(camera_entity),
                                     viewpoint: Viewpoint::Back,
+                                    duration: Some(Duration::from_millis(300)),
+                                    relative_to: None,
                                 });
                                 ui.close_menu();
                             }
                             if ui.button("Left").clicked() {
                                 self.world.send_event(ViewpointEvent {
-                                    camera_entity,
+                                    camera_entity: Some(camera_entity),
                                     viewpoint: Viewpoint::Left,
+                                    duration: Some(Duration::from_millis(300)),
+                                    relative_to: None,
                                 });
                                 ui.close_menu();
                             }
                             if ui.button("Right").clicked() {
                                 self.world.send_event(ViewpointEvent {
-                                    camera_entity,
+                                    camera_entity: Some(camera_entity),
                                     viewpoint: Viewpoint::Right,
+                                    duration: Some(Duration::from_millis(300)),
+                                    relative_to: None,
                                 });
                                 ui.close_menu();
                             }
+                            if ui.button("Isometric").clicked() {
+                                self.world.send_event(ViewpointEvent {
+                                    camera_entity: Some(camera_entity),
+                                    viewpoint: Viewpoint::Isometric {
+                                        corner: IsoCorner::FrontRightTop,
+                                    },
+                                    duration: Some(Duration::from_millis(300)),
+                                    relative_to: None,
+                                });
+                                ui.close_menu();
+                            }
+                            if ui.button("Snap to nearest").clicked() {
+                                self.world.send_event(
+                                    SnapToNearestViewpointEvent {
+                                        camera_entity: Some(camera_entity),
+                                    },
+                                );
+                                ui.close_menu();
+                            }
                         });
                         ui.menu_button("Navigation", |ui| {
                             if ui.button("Orbit").clicked() {
@@ -250,8 +292,7 @@ impl TabViewer<'_> {
             Query<(&OrbitCameraController, &FlyCameraController)>,
         > = SystemState::new(self.world);
         let cameras_query = system_state.get_mut(self.world);
-        let mut switch_to_orbit = false;
-        let mut switch_to_fly = false;
+        let mut controller_mode = None;
         let margin = ui.style().spacing.window_margin.left;
         let item_spacing = ui.style().spacing.item_spacing;
         let viewport_rect =
@@ -270,25 +311,18 @@ impl TabViewer<'_> {
                 .show(ui, |ui| {
                     let (orbit_controller, fly_controller) =
                         cameras_query.get(camera_entity).unwrap();
-                    let button = egui::Button::new("Orbit")
-                        .selected(orbit_controller.is_enabled);
-                    if ui.add(button).clicked() {
-                        switch_to_orbit = true;
-                    }
-                    let button = egui::Button::new("Fly")
-                        .selected(fly_controller.is_enabled);
-                    if ui.add(button).clicked() {
-                        switch_to_fly = true;
-                    }
+                    controller_mode = controller_toolbar_ui(
+                        ui,
+                        orbit_controller.is_enabled,
+                        fly_controller.is_enabled,
+                    );
                 });
         });
-        if switch_to_orbit {
-            self.world
-                .send_event(SwitchToOrbitController { camera_entity });
-        }
-        if switch_to_fly {
-            self.world
-                .send_event(SwitchToFlyController { camera_entity });
+        if let Some(mode) = controller_mode {
+            self.world.send_event(SetCameraControllerEvent {
+                camera_entity,
+                mode,
+            });
         }
         response.response.rect
     }
@@ -298,13 +332,20 @@ impl TabViewer<'_> {
         ui: &mut egui_dock::egui::Ui,
         camera_entity: Entity,
     ) {
+        let epsilon = self.world.resource::<ViewpointDetectionConfig>().epsilon;
         let mut system_state: SystemState<
             Query<(&Transform, &Projection), With<Camera3d>>,
         > = SystemState::new(self.world);
         let camera_query = system_state.get(self.world);
         let (transform, projection) = camera_query.get(camera_entity).unwrap();
-        let viewpoint_text = match Viewpoint::from_transform(transform) {
-            Viewpoint::User { yaw: _, pitch: _ } => "User".to_string(),
+        let viewpoint_text = match Viewpoint::from_transform(transform, epsilon)
+        {
+            Viewpoint::User { yaw, pitch } => format!(
+                "User (yaw {:.0}°, pitch {:.0}°)",
+                yaw.to_degrees(),
+                pitch.to_degrees()
+            ),
+            Viewpoint::Isometric { corner } => format!("Isometric {corner:?}"),
             vp => format!("{vp:?}"),
         };
         let projection_text = match *projection {
@@ -322,40 +363,17 @@ impl TabViewer<'_> {
         ui: &mut egui_dock::egui::Ui,
         camera_entity: Entity,
     ) {
-        // 64 lines, 12 text, 24 margin
-        let desired_size = egui::Vec2::splat(64.0 + 12.0 + 24.0);
-        let (_id, rect) = ui.allocate_space(desired_size);
-        if ui.is_rect_visible(rect) {
-            let mut system_state: SystemState<
-                Query<&Transform, With<Camera3d>>,
-            > = SystemState::new(self.world);
-            let camera_query = system_state.get(self.world);
-            let rotation = camera_query.get(camera_entity).unwrap().rotation;
-            let rotation = rotation.inverse();
-            let start_point = rect.center();
-            for (label, direction, color) in [
-                ("x", Vec3::X, (0.0, 0.77, 0.67, 1.0)),
-                ("y", Vec3::Y, (0.25, 0.90, 0.68, 1.0)),
-                ("z", Vec3::Z, (0.60, 0.90, 0.80, 1.0)),
-            ] {
-                let axis = rotation.mul_vec3(direction * 32.0);
-                let axis = egui::Vec2::new(axis.x, -axis.y);
-                let end_point = start_point + axis;
-                let color =
-                    egui::ecolor::Hsva::new(color.0, color.1, color.2, 1.0)
-                        .into();
-                ui.painter().line_segment(
-                    [start_point, end_point],
-                    egui::Stroke { width: 1.0, color },
-                );
-                ui.painter().text(
-                    end_point,
-                    egui::Align2::LEFT_BOTTOM,
-                    label,
-                    egui::FontId::monospace(12.0),
-                    color,
-                );
-            }
+        let mut system_state: SystemState<Query<&Transform, With<Camera3d>>> =
+            SystemState::new(self.world);
+        let camera_query = system_state.get(self.world);
+        let transform = camera_query.get(camera_entity).unwrap();
+        if let Some(viewpoint) = navigation_gizmo_ui(ui, transform, 100.0) {
+            self.world.send_event(ViewpointEvent {
+                camera_entity: Some(camera_entity),
+                viewpoint,
+                duration: Some(Duration::from_millis(300)),
+                relative_to: None,
+            });
         }
     }
 }
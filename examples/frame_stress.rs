@@ -0,0 +1,145 @@
+//! A stress test of the framing with a large and deep hierarchy: 500 chains
+//! of 100 nested cubes, 50k entities. Press Home to frame the whole scene or
+//! P to frame it precisely using the vertices of the meshes, the time spent
+//! handling the events is logged. The bounds are cached in the `SceneBounds`
+//! resource, so framing again is nearly free, unless C is held to clear the
+//! cache first.
+
+use std::time::Instant;
+
+use bevy::prelude::*;
+
+use bevy_blendy_cameras::{
+    BlendyCamerasPlugin, BlendyCamerasSystemSet, FrameEvent, FrameMode,
+    FramingCompleted, OrbitCameraController, SceneBounds,
+};
+
+const CHAIN_COUNT: usize = 500;
+const CHAIN_DEPTH: usize = 100;
+
+#[derive(Resource)]
+struct Scene {
+    pub camera_entity: Entity,
+    pub scene_entity: Entity,
+}
+
+/// When the handling of the events started, if framing
+#[derive(Resource, Default)]
+struct FramingStart(Option<Instant>);
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(BlendyCamerasPlugin::default())
+        .init_resource::<SceneBounds>()
+        .init_resource::<FramingStart>()
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, frame_camera_system)
+        .add_systems(
+            PostUpdate,
+            (
+                start_timer_system.before(BlendyCamerasSystemSet::HandleEvents),
+                log_timer_system.after(BlendyCamerasSystemSet::HandleEvents),
+            ),
+        )
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Cuboid::new(0.05, 0.05, 0.05));
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+    let scene_entity = commands
+        .spawn((Transform::default(), Visibility::default()))
+        .id();
+    let side = (CHAIN_COUNT as f32).sqrt().ceil() as usize;
+    for chain in 0..CHAIN_COUNT {
+        let mut parent = scene_entity;
+        // The first link places the chain on a grid, the others go up
+        let mut translation = Vec3::new(
+            (chain % side) as f32 * 0.2,
+            0.0,
+            (chain / side) as f32 * 0.2,
+        );
+        for _ in 0..CHAIN_DEPTH {
+            let link = commands
+                .spawn((
+                    Mesh3d(mesh.clone()),
+                    MeshMaterial3d(material.clone()),
+                    Transform::from_translation(translation),
+                ))
+                .id();
+            commands.entity(parent).add_child(link);
+            parent = link;
+            translation = Vec3::new(0.0, 0.05, 0.0);
+        }
+    }
+    commands.spawn((
+        PointLight {
+            shadows_enabled: false,
+            ..default()
+        },
+        Transform::from_xyz(4.0, 8.0, 4.0),
+    ));
+    let camera_entity = commands
+        .spawn((
+            Camera3d::default(),
+            Transform::from_translation(Vec3::new(0.0, 3.0, 10.0)),
+            OrbitCameraController::default(),
+        ))
+        .id();
+    commands.insert_resource(Scene {
+        camera_entity,
+        scene_entity,
+    });
+}
+
+fn frame_camera_system(
+    key_input: Res<ButtonInput<KeyCode>>,
+    scene: Res<Scene>,
+    mut scene_bounds: ResMut<SceneBounds>,
+    mut ev_writer: EventWriter<FrameEvent>,
+) {
+    let precise = key_input.just_pressed(KeyCode::KeyP);
+    if key_input.just_pressed(KeyCode::Home) || precise {
+        if key_input.pressed(KeyCode::KeyC) {
+            scene_bounds.clear();
+        }
+        ev_writer.send(FrameEvent {
+            camera_entity: scene.camera_entity,
+            entities_to_be_framed: vec![scene.scene_entity],
+            include_children: true,
+            precise,
+            ignore_hidden: true,
+            mode: FrameMode::KeepDirection,
+            duration: None,
+        });
+    }
+}
+
+fn start_timer_system(
+    ev_read: EventReader<FrameEvent>,
+    mut start: ResMut<FramingStart>,
+) {
+    if !ev_read.is_empty() {
+        start.0 = Some(Instant::now());
+    }
+}
+
+fn log_timer_system(
+    mut ev_read: EventReader<FramingCompleted>,
+    mut start: ResMut<FramingStart>,
+) {
+    for _ in ev_read.read() {
+        if let Some(start) = start.0.take() {
+            info!(
+                "Framed {} entities in {:?}",
+                CHAIN_COUNT * CHAIN_DEPTH,
+                start.elapsed()
+            );
+        }
+    }
+}
@@ -15,8 +15,9 @@ use bevy::{
 
 use bevy_blendy_cameras::{
     get_camera_entity_from_cursor_position, BlendyCamerasPlugin,
-    FlyCameraController, FrameEvent, OrbitCameraController, SwitchProjection,
-    SwitchToFlyController, SwitchToOrbitController, Viewpoint, ViewpointEvent,
+    ControllerMode, FlyCameraController, FrameEvent, FrameMode,
+    OrbitCameraController, SetCameraControllerEvent, SwitchProjection,
+    Viewpoint, ViewpointEvent,
 };
 
 // FIXME: Make fly mode work in ortho projection
@@ -40,6 +41,7 @@ const ORBIT_HELP_TEXT: &str = "\
     Press Middle Mouse button and drag to orbit camera\n\
     Press Shift + Middle Mouse button and drag to pan camera\n\
     Scoll the mouse wheel to zoom\n\
+    Press Ctrl + Middle Mouse button and drag to zoom\n\
     ";
 
 const FLY_HELP_TEXT: &str = "\
@@ -76,7 +78,7 @@ fn main() {
         ..default()
     });
     app.add_plugins(DefaultPlugins)
-        .add_plugins(BlendyCamerasPlugin)
+        .add_plugins(BlendyCamerasPlugin::default())
         .add_systems(Startup, setup_system)
         .add_systems(
             Update,
@@ -196,12 +198,10 @@ fn set_camera_viewports_system(
     }
 }
 
-// FIXME: Use the same event with parameter to switch
 fn switch_camera_controler_system(
     mut commands: Commands,
     key_input: Res<ButtonInput<KeyCode>>,
-    mut orbit_ev_writer: EventWriter<SwitchToOrbitController>,
-    mut fly_ev_writer: EventWriter<SwitchToFlyController>,
+    mut ev_writer: EventWriter<SetCameraControllerEvent>,
     mut help_text: ResMut<HelpText>,
     mut cameras_query: Query<(Entity, &Camera), With<Camera3d>>,
     primary_window: Query<(Entity, &Window), With<PrimaryWindow>>,
@@ -214,7 +214,10 @@ fn switch_camera_controler_system(
             &other_windows,
         );
         if let Some(camera_entity) = camera_entity {
-            fly_ev_writer.send(SwitchToFlyController { camera_entity });
+            ev_writer.send(SetCameraControllerEvent {
+                camera_entity,
+                mode: ControllerMode::Fly,
+            });
             change_help_text(
                 format!("{GENERAL_HELP_TEXT}\n{FLY_HELP_TEXT}"),
                 &mut commands,
@@ -230,7 +233,10 @@ fn switch_camera_controler_system(
             &other_windows,
         );
         if let Some(camera_entity) = camera_entity {
-            orbit_ev_writer.send(SwitchToOrbitController { camera_entity });
+            ev_writer.send(SetCameraControllerEvent {
+                camera_entity,
+                mode: ControllerMode::Orbit,
+            });
             change_help_text(
                 format!("{GENERAL_HELP_TEXT}\n{ORBIT_HELP_TEXT}"),
                 &mut commands,
@@ -278,8 +284,10 @@ fn switch_camera_viewpoint_system(
         );
         if let Some(camera_entity) = camera_entity {
             ev_writer.send(ViewpointEvent {
-                camera_entity,
+                camera_entity: Some(camera_entity),
                 viewpoint: Viewpoint::Front,
+                duration: None,
+                relative_to: None,
             });
         }
     }
@@ -294,8 +302,10 @@ fn switch_camera_viewpoint_system(
         );
         if let Some(camera_entity) = camera_entity {
             ev_writer.send(ViewpointEvent {
-                camera_entity,
+                camera_entity: Some(camera_entity),
                 viewpoint: Viewpoint::Back,
+                duration: None,
+                relative_to: None,
             });
         }
     }
@@ -310,8 +320,10 @@ fn switch_camera_viewpoint_system(
         );
         if let Some(camera_entity) = camera_entity {
             ev_writer.send(ViewpointEvent {
-                camera_entity,
+                camera_entity: Some(camera_entity),
                 viewpoint: Viewpoint::Right,
+                duration: None,
+                relative_to: None,
             });
         }
     }
@@ -326,8 +338,10 @@ fn switch_camera_viewpoint_system(
         );
         if let Some(camera_entity) = camera_entity {
             ev_writer.send(ViewpointEvent {
-                camera_entity,
+                camera_entity: Some(camera_entity),
                 viewpoint: Viewpoint::Left,
+                duration: None,
+                relative_to: None,
             });
         }
     }
@@ -342,8 +356,10 @@ fn switch_camera_viewpoint_system(
         );
         if let Some(camera_entity) = camera_entity {
             ev_writer.send(ViewpointEvent {
-                camera_entity,
+                camera_entity: Some(camera_entity),
                 viewpoint: Viewpoint::Top,
+                duration: None,
+                relative_to: None,
             });
         }
     }
@@ -358,8 +374,10 @@ fn switch_camera_viewpoint_system(
         );
         if let Some(camera_entity) = camera_entity {
             ev_writer.send(ViewpointEvent {
-                camera_entity,
+                camera_entity: Some(camera_entity),
                 viewpoint: Viewpoint::Bottom,
+                duration: None,
+                relative_to: None,
             });
         }
     }
@@ -389,26 +407,31 @@ fn frame_camera_system(
                             camera_entity,
                             entities_to_be_framed: vec![scene.scene_entity],
                             include_children: true,
+                            precise: false,
+                            ignore_hidden: true,
+                            mode: FrameMode::KeepDirection,
+                            duration: None,
                         });
                     }
                 }
-                Key::Character(str) => {
-                    if str == "c" {
-                        let camera_entity =
-                            get_camera_entity_from_cursor_position(
-                                &cameras_query
-                                    .transmute_lens::<(Entity, &Camera)>()
-                                    .query(),
-                                &primary_window,
-                                &other_windows,
-                            );
-                        if let Some(camera_entity) = camera_entity {
-                            ev_writer.send(FrameEvent {
-                                camera_entity,
-                                entities_to_be_framed: vec![scene.cube_entity],
-                                include_children: false,
-                            });
-                        }
+                Key::Character(str) if str == "c" => {
+                    let camera_entity = get_camera_entity_from_cursor_position(
+                        &cameras_query
+                            .transmute_lens::<(Entity, &Camera)>()
+                            .query(),
+                        &primary_window,
+                        &other_windows,
+                    );
+                    if let Some(camera_entity) = camera_entity {
+                        ev_writer.send(FrameEvent {
+                            camera_entity,
+                            entities_to_be_framed: vec![scene.cube_entity],
+                            include_children: false,
+                            precise: false,
+                            ignore_hidden: true,
+                            mode: FrameMode::KeepDirection,
+                            duration: None,
+                        });
                     }
                 }
                 _ => {}
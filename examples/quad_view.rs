@@ -0,0 +1,161 @@
+//! A Blender like quad view: the top, front and right orthographic views pan
+//! and zoom together and cannot be orbited, the perspective view is free.
+//! Press Home to frame the whole scene in all the views.
+
+use bevy::{
+    prelude::*,
+    render::camera::{ScalingMode, Viewport},
+    window::WindowResized,
+};
+
+use bevy_blendy_cameras::{
+    BlendyCamerasPlugin, FrameAllCamerasEvent, FrameMode,
+    OrbitCameraController, SynchronizedViews, Viewpoint,
+};
+
+const HELP_TEXT: &str = "\
+    Press Middle Mouse button and drag to orbit the perspective view\n\
+    Press Shift + Middle Mouse button and drag to pan camera\n\
+    Scroll the mouse wheel to zoom\n\
+    Press Home to frame the whole scene in all the views\n\
+    ";
+
+#[derive(Resource)]
+struct Scene {
+    pub scene_entity: Entity,
+}
+
+#[derive(Component)]
+struct CameraPosition {
+    pos: UVec2,
+}
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(BlendyCamerasPlugin::default())
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, (set_camera_viewports_system, frame_camera_system))
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Scene
+    let scene_entity = commands
+        .spawn((Transform::default(), Visibility::default()))
+        .with_children(|parent| {
+            // Ground
+            parent.spawn((
+                Mesh3d(meshes.add(Plane3d::default().mesh().size(5.0, 5.0))),
+                MeshMaterial3d(materials.add(Color::srgb(0.3, 0.5, 0.3))),
+            ));
+            // Cube
+            parent.spawn((
+                Mesh3d(meshes.add(Cuboid::new(1.0, 1.0, 1.0))),
+                MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+                Transform::from_xyz(0.0, 0.5, 0.0),
+            ));
+        })
+        .id();
+    // Light
+    commands.spawn((
+        PointLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(4.0, 8.0, 4.0),
+    ));
+    // Orthographic cameras, locked to their viewpoint
+    let orthographic_views = [
+        (UVec2::new(0, 0), Viewpoint::Top),
+        (UVec2::new(0, 1), Viewpoint::Front),
+        (UVec2::new(1, 1), Viewpoint::Right),
+    ];
+    for (n, (pos, viewpoint)) in orthographic_views.into_iter().enumerate() {
+        commands.spawn((
+            CameraPosition { pos },
+            Camera3d::default(),
+            Camera {
+                order: n as isize + 1,
+                clear_color: ClearColorConfig::None,
+                ..default()
+            },
+            Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::FixedVertical {
+                    viewport_height: 1.0,
+                },
+                ..OrthographicProjection::default_3d()
+            }),
+            viewpoint.to_transform(Vec3::ZERO, 6.0),
+            OrbitCameraController::default(),
+            SynchronizedViews {
+                group: 0,
+                viewpoint: Some(viewpoint),
+            },
+        ));
+    }
+    // Perspective camera, free
+    commands.spawn((
+        CameraPosition {
+            pos: UVec2::new(1, 0),
+        },
+        Camera3d::default(),
+        Transform::from_translation(Vec3::new(4.0, 3.0, 5.0)),
+        OrbitCameraController::default(),
+        SynchronizedViews {
+            group: 0,
+            viewpoint: None,
+        },
+    ));
+    // Help text
+    commands.spawn((
+        Text::new(HELP_TEXT),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+    ));
+    // Resources
+    commands.insert_resource(Scene { scene_entity });
+}
+
+fn set_camera_viewports_system(
+    windows: Query<&Window>,
+    mut resize_events: EventReader<WindowResized>,
+    mut query: Query<(&CameraPosition, &mut Camera)>,
+) {
+    for resize_event in resize_events.read() {
+        let window = windows.get(resize_event.window).unwrap();
+        let size = window.physical_size() / 2;
+
+        for (camera_position, mut camera) in &mut query {
+            camera.viewport = Some(Viewport {
+                physical_position: camera_position.pos * size,
+                physical_size: size,
+                ..default()
+            });
+        }
+    }
+}
+
+fn frame_camera_system(
+    key_input: Res<ButtonInput<KeyCode>>,
+    scene: Res<Scene>,
+    mut ev_writer: EventWriter<FrameAllCamerasEvent>,
+) {
+    if key_input.just_pressed(KeyCode::Home) {
+        ev_writer.send(FrameAllCamerasEvent {
+            only_active: false,
+            entities_to_be_framed: vec![scene.scene_entity],
+            include_children: true,
+            precise: false,
+            ignore_hidden: true,
+            mode: FrameMode::KeepDirection,
+            duration: None,
+        });
+    }
+}
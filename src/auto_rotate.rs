@@ -0,0 +1,309 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    input::{self, CameraInputState},
+    orbit::OrbitCameraController,
+    transition::ViewTransition,
+    utils, ActiveCameraData, BlendyCamerasDisabled, CameraViewChanged,
+    ViewChangeCause,
+};
+
+/// Slow rotation of an orbit camera around its focus while the user does
+/// not interact with it, e.g. to showcase a product, see
+/// [`OrbitCameraController::auto_rotate`]. Only the yaw changes, the pitch
+/// and the radius are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AutoRotate {
+    /// Speed of the rotation, in degrees per second
+    pub speed_deg_per_sec: f32,
+    /// Time without interaction before the rotation starts. Orbiting,
+    /// panning, zooming, holding a button or a key with the camera active,
+    /// the viewpoint events, the framing and the animated transitions stop
+    /// the rotation and restart this delay.
+    pub idle_delay: Duration,
+    /// Direction of the rotation
+    pub direction: AutoRotateDirection,
+    /// Also rotate when another camera is the active one, e.g. in another
+    /// viewport. Otherwise the camera only rotates when it is the active
+    /// camera or when there is none.
+    pub while_inactive: bool,
+}
+
+impl Default for AutoRotate {
+    fn default() -> Self {
+        Self {
+            speed_deg_per_sec: 10.0,
+            idle_delay: Duration::from_secs(3),
+            direction: AutoRotateDirection::CounterClockwise,
+            while_inactive: false,
+        }
+    }
+}
+
+/// Direction of an [`AutoRotate`], seen from above
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum AutoRotateDirection {
+    /// The yaw decreases
+    Clockwise,
+    /// The yaw increases
+    #[default]
+    CounterClockwise,
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn auto_rotate_system(
+    time: Res<Time>,
+    active_cam: Res<ActiveCameraData>,
+    key_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut changed_ev_read: EventReader<CameraViewChanged>,
+    mut cameras_query: Query<
+        (
+            Entity,
+            &mut OrbitCameraController,
+            &CameraInputState,
+            Has<ViewTransition>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+    // Elapsed time, in seconds, of the last interaction with each camera
+    mut last_interactions: Local<HashMap<Entity, f32>>,
+) {
+    let now = time.elapsed_secs();
+    // The rotation itself changes the view with `force_update`
+    let changed_cameras = changed_ev_read
+        .read()
+        .filter(|event| event.cause != ViewChangeCause::Forced)
+        .map(|event| event.camera_entity)
+        .collect::<Vec<_>>();
+    last_interactions.retain(|entity, _| cameras_query.contains(*entity));
+    for (entity, mut controller, input_state, is_transitioning) in
+        cameras_query.iter_mut()
+    {
+        let Some(auto_rotate) = controller.auto_rotate else {
+            last_interactions.remove(&entity);
+            continue;
+        };
+        let is_active = active_cam.entity == Some(entity);
+        let has_interaction = changed_cameras.contains(&entity)
+            || is_transitioning
+            || (is_active
+                && input::has_pending_input(
+                    input_state,
+                    &mouse_input,
+                    &key_input,
+                ));
+        let last_interaction = last_interactions.entry(entity).or_insert(now);
+        if has_interaction {
+            *last_interaction = now;
+            continue;
+        }
+        let can_rotate = controller.is_enabled
+            && controller.locked_viewpoint.is_none()
+            && (is_active
+                || active_cam.entity.is_none()
+                || auto_rotate.while_inactive);
+        if !can_rotate {
+            *last_interaction = now;
+            continue;
+        }
+        if now - *last_interaction < auto_rotate.idle_delay.as_secs_f32() {
+            continue;
+        }
+        let (Some(yaw), Some(pitch)) = (controller.yaw, controller.pitch)
+        else {
+            continue;
+        };
+        let delta =
+            auto_rotate.speed_deg_per_sec.to_radians() * time.delta_secs();
+        // Keep the yaw in range, the camera may rotate for hours
+        let yaw = utils::wrap_angle(match auto_rotate.direction {
+            AutoRotateDirection::Clockwise => yaw - delta,
+            AutoRotateDirection::CounterClockwise => yaw + delta,
+        });
+        controller.set_yaw_pitch(yaw, pitch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::{
+        testing::{set_active_camera, test_app, SimulatedInput},
+        Viewpoint, ViewpointEvent,
+    };
+
+    /// Duration of each update of the tests
+    const FRAME_TIME: Duration = Duration::from_millis(100);
+
+    fn spawn_camera(app: &mut App, auto_rotate: AutoRotate) -> Entity {
+        app.world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 2.0, 5.0),
+                OrbitCameraController {
+                    auto_rotate: Some(auto_rotate),
+                    ..default()
+                },
+            ))
+            .id()
+    }
+
+    /// Rotation at 90 degrees per second after 1 second without interaction
+    fn test_auto_rotate() -> AutoRotate {
+        AutoRotate {
+            speed_deg_per_sec: 90.0,
+            idle_delay: Duration::from_secs(1),
+            ..default()
+        }
+    }
+
+    /// App with updates of [`FRAME_TIME`] and an active rotating camera
+    fn app_with_camera(auto_rotate: AutoRotate) -> (App, Entity) {
+        let mut app = test_app();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_TIME));
+        let camera = spawn_camera(&mut app, auto_rotate);
+        set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+        app.update();
+        (app, camera)
+    }
+
+    fn yaw(app: &App, camera: Entity) -> f32 {
+        app.world()
+            .get::<OrbitCameraController>(camera)
+            .unwrap()
+            .yaw
+            .unwrap()
+    }
+
+    /// Number of updates until the yaw of `camera` changes, at most `max`
+    fn updates_until_rotating(app: &mut App, camera: Entity, max: u32) -> u32 {
+        let start = yaw(app, camera);
+        for count in 1..=max {
+            app.update();
+            if yaw(app, camera) != start {
+                return count;
+            }
+        }
+        max + 1
+    }
+
+    /// Number of updates in the idle delay of [`test_auto_rotate`]
+    const IDLE_UPDATES: u32 = 10;
+
+    #[test]
+    fn rotation_starts_after_the_idle_delay() {
+        let (mut app, camera) = app_with_camera(test_auto_rotate());
+        let count = updates_until_rotating(&mut app, camera, 20);
+        assert!((IDLE_UPDATES..=IDLE_UPDATES + 1).contains(&count));
+        // Then at the configured speed, counterclockwise
+        let start = yaw(&app, camera);
+        app.update();
+        let step = yaw(&app, camera) - start;
+        assert!((step - 90f32.to_radians() * 0.1).abs() < 1e-4);
+        // The transform follows
+        let transform = app.world().get::<Transform>(camera).unwrap();
+        let controller =
+            app.world().get::<OrbitCameraController>(camera).unwrap();
+        assert!(
+            (transform.translation.x
+                - controller.radius.unwrap()
+                    * controller.yaw.unwrap().sin()
+                    * controller.pitch.unwrap().cos())
+            .abs()
+                < 1e-4
+        );
+    }
+
+    #[test]
+    fn interactions_pause_the_rotation() {
+        let (mut app, camera) = app_with_camera(test_auto_rotate());
+        updates_until_rotating(&mut app, camera, 20);
+        // User input
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .orbit(Vec2::new(10.0, 0.0));
+        app.update();
+        let count = updates_until_rotating(&mut app, camera, 20);
+        assert!((IDLE_UPDATES - 1..=IDLE_UPDATES + 1).contains(&count));
+        // A view change reported by `CameraViewChanged`
+        app.world_mut().send_event(ViewpointEvent {
+            camera_entity: Some(camera),
+            viewpoint: Viewpoint::Front,
+            duration: None,
+            relative_to: None,
+        });
+        app.update();
+        assert_eq!(yaw(&app, camera), 0.0);
+        let count = updates_until_rotating(&mut app, camera, 20);
+        assert!((IDLE_UPDATES - 1..=IDLE_UPDATES + 1).contains(&count));
+    }
+
+    #[test]
+    fn inactive_cameras_only_rotate_if_configured() {
+        for while_inactive in [false, true] {
+            let (mut app, active) = app_with_camera(AutoRotate {
+                while_inactive,
+                ..test_auto_rotate()
+            });
+            let inactive = spawn_camera(
+                &mut app,
+                AutoRotate {
+                    while_inactive,
+                    ..test_auto_rotate()
+                },
+            );
+            app.update();
+            let start = yaw(&app, inactive);
+            for _ in 0..2 * IDLE_UPDATES {
+                app.update();
+            }
+            assert_ne!(yaw(&app, active), 0.0);
+            assert_eq!(yaw(&app, inactive) != start, while_inactive);
+        }
+    }
+
+    #[test]
+    fn yaw_stays_wrapped() {
+        let (mut app, camera) = app_with_camera(AutoRotate {
+            // More than a turn per second
+            speed_deg_per_sec: 1000.0,
+            ..test_auto_rotate()
+        });
+        for direction in [
+            AutoRotateDirection::CounterClockwise,
+            AutoRotateDirection::Clockwise,
+        ] {
+            app.world_mut()
+                .get_mut::<OrbitCameraController>(camera)
+                .unwrap()
+                .auto_rotate
+                .as_mut()
+                .unwrap()
+                .direction = direction;
+            for _ in 0..100 {
+                app.update();
+                assert!(yaw(&app, camera).abs() <= PI);
+            }
+        }
+    }
+}
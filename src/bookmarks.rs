@@ -0,0 +1,479 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    fly::FlyCameraController,
+    math,
+    orbit::OrbitCameraController,
+    switch_camera_projection,
+    transition::{OrbitState, ViewTransition},
+    utils, AutoOrthographic, BlendyCamerasDisabled, OtherProjection,
+    ProjectionSwitched, DEFAULT_ORBIT_RADIUS,
+};
+
+/// Saved view of a camera, see [`CameraBookmarks`]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ViewBookmark {
+    /// The point the camera looks at
+    pub focus: Vec3,
+    /// Rotation in radian around the global Y axis
+    pub yaw: f32,
+    /// Rotation in radian around a global horizontal axis perpendicular to
+    /// the view direction
+    pub pitch: f32,
+    /// The distance between the camera and the `focus`, or the scale of the
+    /// projection if orthographic
+    pub radius: f32,
+    /// Whether the camera projection is orthographic
+    pub is_orthographic: bool,
+}
+
+/// Component storing the named views of a camera, saved with
+/// [`SaveViewBookmark`] and restored with [`RestoreViewBookmark`]. It is
+/// added to the camera when the first view is saved.
+#[derive(Component, Debug, Default, Clone, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct CameraBookmarks {
+    /// The saved views, by name
+    pub bookmarks: BTreeMap<String, ViewBookmark>,
+}
+
+impl CameraBookmarks {
+    /// The view saved as `name`
+    pub fn get(&self, name: &str) -> Option<&ViewBookmark> {
+        self.bookmarks.get(name)
+    }
+
+    /// Save `bookmark` as `name`, replacing the view previously saved with
+    /// the same name
+    pub fn insert(&mut self, name: impl Into<String>, bookmark: ViewBookmark) {
+        self.bookmarks.insert(name.into(), bookmark);
+    }
+
+    /// Remove the view saved as `name`
+    pub fn remove(&mut self, name: &str) -> Option<ViewBookmark> {
+        self.bookmarks.remove(name)
+    }
+
+    /// The names of the saved views, in alphabetical order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.bookmarks.keys().map(String::as_str)
+    }
+}
+
+/// Event to save the current view of a camera in its [`CameraBookmarks`]
+#[derive(Event, Reflect)]
+pub struct SaveViewBookmark {
+    /// The camera whose view is saved
+    pub camera_entity: Entity,
+    /// Name of the view, replacing the view previously saved with this name
+    pub name: String,
+}
+
+/// Event to restore a view saved with [`SaveViewBookmark`]. The enabled
+/// controller of the camera is updated. The projection is only restored
+/// with the [`OrbitCameraController`].
+#[derive(Event, Reflect)]
+pub struct RestoreViewBookmark {
+    /// The camera whose view is restored
+    pub camera_entity: Entity,
+    /// Name of the view to restore
+    pub name: String,
+    /// Duration of the animated transition to the saved view. The camera
+    /// is moved immediately if `None`
+    pub duration: Option<Duration>,
+}
+
+impl ViewBookmark {
+    /// Current view of a camera, from its enabled controller. The orbit
+    /// controller must be initialized.
+    pub(crate) fn from_camera(
+        transform: &Transform,
+        orbit_controller_opt: Option<&OrbitCameraController>,
+        fly_controller_opt: Option<&FlyCameraController>,
+        projection: &Projection,
+    ) -> Option<Self> {
+        let is_orthographic = matches!(projection, Projection::Orthographic(_));
+        match orbit_controller_opt {
+            Some(controller) if controller.is_enabled => {
+                OrbitState::from_controller(controller).map(|state| Self {
+                    focus: state.focus,
+                    yaw: state.yaw,
+                    pitch: state.pitch,
+                    radius: state.radius,
+                    is_orthographic,
+                })
+            }
+            _ if fly_controller_opt
+                .is_some_and(|controller| controller.is_enabled) =>
+            {
+                let radius = orbit_controller_opt
+                    .and_then(|controller| controller.radius)
+                    .unwrap_or(DEFAULT_ORBIT_RADIUS);
+                let (yaw, pitch, _roll) =
+                    transform.rotation.to_euler(EulerRot::YXZ);
+                Some(Self {
+                    focus: transform.translation + transform.forward() * radius,
+                    yaw,
+                    pitch: -pitch,
+                    radius,
+                    is_orthographic,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether both views are the same, within `epsilon`
+    pub(crate) fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        self.focus.abs_diff_eq(other.focus, epsilon)
+            && utils::approx_equal_angle(self.yaw, other.yaw, epsilon)
+            && utils::approx_equal_angle(self.pitch, other.pitch, epsilon)
+            && utils::approx_equal(self.radius, other.radius, epsilon)
+            && self.is_orthographic == other.is_orthographic
+    }
+
+    /// Move the camera to this view, updating its enabled controller,
+    /// immediately or through an animated transition
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn restore(
+        &self,
+        commands: &mut Commands,
+        entity: Entity,
+        duration: Option<Duration>,
+        transform: &mut Transform,
+        orbit_controller_opt: Option<&mut OrbitCameraController>,
+        fly_controller_opt: Option<&FlyCameraController>,
+        viewport_size: Option<Vec2>,
+        projection: &mut Projection,
+        other_projection_opt: Option<&mut OtherProjection>,
+    ) {
+        let end = OrbitState {
+            focus: self.focus,
+            yaw: self.yaw,
+            pitch: self.pitch,
+            radius: self.radius,
+        };
+        if let Some(controller) = orbit_controller_opt {
+            if controller.is_enabled {
+                controller.initialize_if_necessary(transform, projection);
+                let is_orthographic =
+                    matches!(*projection, Projection::Orthographic(_));
+                if let Some(other_projection) = other_projection_opt {
+                    if controller.is_initialized
+                        && is_orthographic != self.is_orthographic
+                    {
+                        switch_camera_projection(
+                            controller,
+                            viewport_size,
+                            transform,
+                            &mut other_projection.0,
+                            projection,
+                        );
+                        commands.entity(entity).remove::<AutoOrthographic>();
+                        commands.send_event(ProjectionSwitched::new(
+                            entity, projection,
+                        ));
+                    }
+                }
+                match (duration, OrbitState::from_controller(controller)) {
+                    (Some(duration), Some(start)) => {
+                        commands.entity(entity).insert(ViewTransition::orbit(
+                            start, end, duration,
+                        ));
+                    }
+                    _ => {
+                        commands.entity(entity).remove::<ViewTransition>();
+                        end.apply(controller);
+                    }
+                }
+            }
+        }
+        if let Some(controller) = fly_controller_opt {
+            if controller.is_enabled {
+                let end = math::camera_transform_from_orbit(
+                    self.yaw,
+                    self.pitch,
+                    self.radius,
+                    self.focus,
+                );
+                if let Some(duration) = duration {
+                    commands
+                        .entity(entity)
+                        .insert(ViewTransition::fly(*transform, end, duration));
+                } else {
+                    commands.entity(entity).remove::<ViewTransition>();
+                    *transform = end;
+                }
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn bookmark_system(
+    mut commands: Commands,
+    mut save_ev_read: EventReader<SaveViewBookmark>,
+    mut restore_ev_read: EventReader<RestoreViewBookmark>,
+    mut cameras_query: Query<
+        (
+            &mut Transform,
+            Option<&mut OrbitCameraController>,
+            Option<&FlyCameraController>,
+            Option<&Camera>,
+            &mut Projection,
+            Option<&mut OtherProjection>,
+            Option<&mut CameraBookmarks>,
+        ),
+        (
+            Or<(With<OrbitCameraController>, With<FlyCameraController>)>,
+            Without<BlendyCamerasDisabled>,
+        ),
+    >,
+) {
+    // Bookmarks of the cameras without a `CameraBookmarks` component yet
+    let mut new_bookmarks = HashMap::<Entity, CameraBookmarks>::new();
+    for SaveViewBookmark {
+        camera_entity,
+        name,
+    } in save_ev_read.read()
+    {
+        let Ok((
+            mut transform,
+            mut orbit_controller_opt,
+            fly_controller_opt,
+            _,
+            mut projection,
+            _,
+            bookmarks_opt,
+        )) = cameras_query.get_mut(*camera_entity)
+        else {
+            warn!("Camera not found while trying to save view bookmark");
+            continue;
+        };
+        if let Some(ref mut controller) = orbit_controller_opt {
+            if controller.is_enabled {
+                controller
+                    .initialize_if_necessary(&mut transform, &mut projection);
+            }
+        }
+        let Some(bookmark) = ViewBookmark::from_camera(
+            &transform,
+            orbit_controller_opt.as_deref(),
+            fly_controller_opt,
+            &projection,
+        ) else {
+            warn!("No enabled controller while trying to save view bookmark");
+            continue;
+        };
+        match bookmarks_opt {
+            Some(mut bookmarks) => bookmarks.insert(name.clone(), bookmark),
+            None => new_bookmarks
+                .entry(*camera_entity)
+                .or_default()
+                .insert(name.clone(), bookmark),
+        }
+    }
+    for (entity, bookmarks) in new_bookmarks {
+        commands.entity(entity).insert(bookmarks);
+    }
+    for RestoreViewBookmark {
+        camera_entity,
+        name,
+        duration,
+    } in restore_ev_read.read()
+    {
+        let Ok((
+            mut transform,
+            orbit_controller_opt,
+            fly_controller_opt,
+            camera_opt,
+            mut projection,
+            other_projection_opt,
+            bookmarks_opt,
+        )) = cameras_query.get_mut(*camera_entity)
+        else {
+            warn!("Camera not found while trying to restore view bookmark");
+            continue;
+        };
+        let Some(bookmark) =
+            bookmarks_opt.and_then(|bookmarks| bookmarks.get(name).copied())
+        else {
+            warn!("View bookmark \"{name}\" not found");
+            continue;
+        };
+        bookmark.restore(
+            &mut commands,
+            *camera_entity,
+            *duration,
+            &mut transform,
+            orbit_controller_opt.map(Mut::into_inner),
+            fly_controller_opt,
+            camera_opt.and_then(Camera::logical_viewport_size),
+            &mut projection,
+            other_projection_opt.map(Mut::into_inner),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::{
+        testing::{
+            assert_transform_approx_eq, set_active_camera, test_app,
+            transforms_approx_eq, SimulatedInput,
+        },
+        SwitchToFlyController, Viewpoint, ViewpointEvent,
+    };
+
+    fn spawn_camera(app: &mut App) -> Entity {
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(1.0, 2.0, 5.0),
+                OrbitCameraController {
+                    focus: Vec3::new(0.0, 1.0, 0.0),
+                    ..default()
+                },
+                FlyCameraController {
+                    is_enabled: false,
+                    ..default()
+                },
+            ))
+            .id();
+        set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+        app.update();
+        camera
+    }
+
+    fn transform(app: &App, camera: Entity) -> Transform {
+        *app.world().get::<Transform>(camera).unwrap()
+    }
+
+    fn save(app: &mut App, camera: Entity, name: &str) {
+        app.world_mut().send_event(SaveViewBookmark {
+            camera_entity: camera,
+            name: name.to_string(),
+        });
+        app.update();
+    }
+
+    fn restore(
+        app: &mut App,
+        camera: Entity,
+        name: &str,
+        duration: Option<Duration>,
+    ) {
+        app.world_mut().send_event(RestoreViewBookmark {
+            camera_entity: camera,
+            name: name.to_string(),
+            duration,
+        });
+        app.update();
+    }
+
+    fn move_away(app: &mut App, camera: Entity) {
+        app.world_mut().send_event(ViewpointEvent {
+            camera_entity: Some(camera),
+            viewpoint: Viewpoint::Top,
+            duration: None,
+            relative_to: None,
+        });
+        app.update();
+    }
+
+    #[test]
+    fn saved_view_is_restored() {
+        let mut app = test_app();
+        let camera = spawn_camera(&mut app);
+        let saved = transform(&app, camera);
+        save(&mut app, camera, "saved");
+        let bookmarks = app.world().get::<CameraBookmarks>(camera).unwrap();
+        assert_eq!(bookmarks.names().collect::<Vec<_>>(), ["saved"]);
+        move_away(&mut app, camera);
+        assert!(!transforms_approx_eq(&transform(&app, camera), &saved, 0.1));
+        restore(&mut app, camera, "saved", None);
+        assert_transform_approx_eq(&transform(&app, camera), &saved, 1e-4);
+        let controller =
+            app.world().get::<OrbitCameraController>(camera).unwrap();
+        assert_eq!(controller.focus, Vec3::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn saved_view_is_restored_with_the_fly_controller() {
+        let mut app = test_app();
+        let camera = spawn_camera(&mut app);
+        let saved = transform(&app, camera);
+        save(&mut app, camera, "saved");
+        app.world_mut().send_event(SwitchToFlyController {
+            camera_entity: camera,
+        });
+        app.update();
+        // Fly forward
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_key(KeyCode::KeyE);
+        for _ in 0..5 {
+            app.update();
+        }
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .release_key(KeyCode::KeyE);
+        app.update();
+        assert!(!transforms_approx_eq(
+            &transform(&app, camera),
+            &saved,
+            1e-3
+        ));
+        restore(&mut app, camera, "saved", None);
+        assert_transform_approx_eq(&transform(&app, camera), &saved, 1e-4);
+        let world = app.world();
+        assert!(world.get::<FlyCameraController>(camera).unwrap().is_enabled);
+        assert!(
+            !world
+                .get::<OrbitCameraController>(camera)
+                .unwrap()
+                .is_enabled
+        );
+    }
+
+    #[test]
+    fn animated_restore_ends_on_the_saved_view() {
+        let mut app = test_app();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            Duration::from_millis(100),
+        ));
+        let camera = spawn_camera(&mut app);
+        let saved = transform(&app, camera);
+        save(&mut app, camera, "saved");
+        move_away(&mut app, camera);
+        let moved = transform(&app, camera);
+        restore(&mut app, camera, "saved", Some(Duration::from_millis(500)));
+        app.update();
+        let halfway = transform(&app, camera);
+        assert!(app.world().get::<ViewTransition>(camera).is_some());
+        assert!(!transforms_approx_eq(&halfway, &saved, 1e-3));
+        assert!(!transforms_approx_eq(&halfway, &moved, 1e-3));
+        for _ in 0..6 {
+            app.update();
+        }
+        assert!(app.world().get::<ViewTransition>(camera).is_none());
+        assert_transform_approx_eq(&transform(&app, camera), &saved, 1e-4);
+    }
+}
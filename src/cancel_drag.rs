@@ -0,0 +1,266 @@
+use bevy::prelude::*;
+
+use crate::{
+    control_target::ControlTarget, fly::FlyCameraController,
+    input::CameraInputBlocked, is_dragging, orbit::OrbitCameraController,
+    transition::OrbitState, ActiveCameraData, BlendyCamerasDisabled,
+};
+
+/// State of a camera when the current drag started, restored if the drag is
+/// cancelled. Removed when the drag ends, so that the next drag takes a new
+/// one.
+#[derive(Component, Debug, Clone, Copy)]
+pub(crate) struct DragSnapshot {
+    orbit: Option<OrbitState>,
+    /// Entity moved by the fly controller and its transform
+    fly: Option<(Entity, Transform)>,
+}
+
+/// Whether the cancel key or click of one of the controllers was just
+/// pressed
+fn is_cancel_just_pressed(
+    orbit_controller_opt: Option<&OrbitCameraController>,
+    fly_controller_opt: Option<&FlyCameraController>,
+    mouse_input: &ButtonInput<MouseButton>,
+    key_input: &ButtonInput<KeyCode>,
+    input_blocked: &CameraInputBlocked,
+) -> bool {
+    let is_cancel = |key_cancel_drag: Option<KeyCode>, right_click: bool| {
+        key_cancel_drag.is_some_and(|key| {
+            key_input.just_pressed(key) && !input_blocked.is_keyboard_blocked()
+        }) || (right_click && mouse_input.just_pressed(MouseButton::Right))
+    };
+    orbit_controller_opt.is_some_and(|controller| {
+        controller.is_enabled
+            && is_cancel(
+                controller.key_cancel_drag,
+                controller.cancel_drag_on_right_click,
+            )
+    }) || fly_controller_opt.is_some_and(|controller| {
+        controller.is_enabled
+            && is_cancel(
+                controller.key_cancel_drag,
+                controller.cancel_drag_on_right_click,
+            )
+    })
+}
+
+/// Take a snapshot of the active camera when a drag starts, and restore it
+/// if the drag is cancelled. The input is then blocked until the mouse
+/// buttons are released, which ends the drag and releases the cursor.
+#[allow(clippy::type_complexity)]
+pub(crate) fn cancel_drag_system(
+    mut commands: Commands,
+    active_cam: Res<ActiveCameraData>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut input_blocked: ResMut<CameraInputBlocked>,
+    mut cameras: Query<
+        (
+            Entity,
+            Option<&mut OrbitCameraController>,
+            Option<&FlyCameraController>,
+            Option<&mut Projection>,
+            Option<&DragSnapshot>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+    mut transforms: Query<&mut Transform>,
+) {
+    for (
+        entity,
+        mut orbit_controller_opt,
+        fly_controller_opt,
+        projection_opt,
+        snapshot_opt,
+    ) in cameras.iter_mut()
+    {
+        let is_active_drag = active_cam.entity == Some(entity)
+            && !input_blocked.is_blocked()
+            && is_dragging(
+                orbit_controller_opt.as_deref(),
+                fly_controller_opt,
+                None,
+                &mouse_input,
+                &key_input,
+            );
+        if !is_active_drag {
+            if snapshot_opt.is_some() {
+                commands.entity(entity).remove::<DragSnapshot>();
+            }
+            continue;
+        }
+        let snapshot = match snapshot_opt {
+            Some(snapshot) => *snapshot,
+            None => {
+                // The drag starts, the controllers have not moved the camera
+                // yet. The snapshot is used right away if the drag is
+                // cancelled during this frame.
+                if let (Some(controller), Some(projection), Ok(transform)) = (
+                    orbit_controller_opt.as_deref_mut(),
+                    projection_opt,
+                    transforms.get_mut(entity),
+                ) {
+                    if controller.is_enabled {
+                        controller.initialize_if_necessary(
+                            transform.into_inner(),
+                            projection.into_inner(),
+                        );
+                    }
+                }
+                let fly = fly_controller_opt
+                    .filter(|controller| controller.is_enabled)
+                    .map(|controller| match controller.control_target {
+                        ControlTarget::SelfEntity => entity,
+                        ControlTarget::Entity(target_entity) => target_entity,
+                    })
+                    .and_then(|moved_entity| {
+                        let transform = transforms.get(moved_entity).ok()?;
+                        Some((moved_entity, *transform))
+                    });
+                let snapshot = DragSnapshot {
+                    orbit: orbit_controller_opt
+                        .as_deref()
+                        .filter(|controller| controller.is_enabled)
+                        .and_then(OrbitState::from_controller),
+                    fly,
+                };
+                commands.entity(entity).insert(snapshot);
+                snapshot
+            }
+        };
+        if !is_cancel_just_pressed(
+            orbit_controller_opt.as_deref(),
+            fly_controller_opt,
+            &mouse_input,
+            &key_input,
+            &input_blocked,
+        ) {
+            continue;
+        }
+        if let (Some(orbit), Some(mut controller)) =
+            (snapshot.orbit, orbit_controller_opt)
+        {
+            orbit.apply(&mut controller);
+        }
+        if let Some((moved_entity, transform)) = snapshot.fly {
+            if let Ok(mut moved_transform) = transforms.get_mut(moved_entity) {
+                moved_transform.set_if_neq(transform);
+            }
+        }
+        commands.entity(entity).remove::<DragSnapshot>();
+        // Ignore the rest of the drag, including the motion of this frame
+        input_blocked.block_until_released();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        assert_transform_approx_eq, set_active_camera, test_app, SimulatedInput,
+    };
+
+    /// Camera whose controller is not initialized before the first drag
+    fn spawn_camera(app: &mut App) -> Entity {
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 2.0, 5.0),
+                OrbitCameraController::default(),
+            ))
+            .id();
+        set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+        camera
+    }
+
+    fn transform(app: &App, camera: Entity) -> Transform {
+        *app.world().get::<Transform>(camera).unwrap()
+    }
+
+    fn drag(app: &mut App, delta: Vec2) {
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_button(MouseButton::Middle)
+            .orbit(delta);
+        app.update();
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .orbit(delta);
+        app.update();
+    }
+
+    fn cancel(app: &mut App) {
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_key(KeyCode::Escape);
+        app.update();
+    }
+
+    fn release(app: &mut App) {
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .release_button(MouseButton::Middle)
+            .release_key(KeyCode::Escape);
+        app.update();
+    }
+
+    #[test]
+    fn cancel_restores_the_view_and_ignores_the_rest_of_the_drag() {
+        let mut app = test_app();
+        let camera = spawn_camera(&mut app);
+        app.update();
+        let start = transform(&app, camera);
+        drag(&mut app, Vec2::new(100.0, 50.0));
+        assert!(!transform(&app, camera)
+            .translation
+            .abs_diff_eq(start.translation, 0.1));
+        cancel(&mut app);
+        assert_transform_approx_eq(&transform(&app, camera), &start, 1e-4);
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .orbit(Vec2::new(100.0, 0.0));
+        app.update();
+        assert_transform_approx_eq(&transform(&app, camera), &start, 1e-4);
+        assert!(app.world().get::<DragSnapshot>(camera).is_none());
+    }
+
+    #[test]
+    fn cancel_in_the_frame_the_drag_starts() {
+        let mut app = test_app();
+        let camera = spawn_camera(&mut app);
+        // The controller is initialized by the drag
+        let start =
+            Transform::from_xyz(0.0, 2.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y);
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_button(MouseButton::Middle)
+            .press_key(KeyCode::Escape)
+            .orbit(Vec2::new(100.0, 50.0));
+        app.update();
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .orbit(Vec2::new(100.0, 50.0));
+        app.update();
+        assert_transform_approx_eq(&transform(&app, camera), &start, 1e-4);
+    }
+
+    #[test]
+    fn new_drag_does_not_restore_the_previous_one() {
+        let mut app = test_app();
+        let camera = spawn_camera(&mut app);
+        app.update();
+        drag(&mut app, Vec2::new(100.0, 50.0));
+        release(&mut app);
+        let after_first_drag = transform(&app, camera);
+        drag(&mut app, Vec2::new(-40.0, 20.0));
+        cancel(&mut app);
+        assert_transform_approx_eq(
+            &transform(&app, camera),
+            &after_first_drag,
+            1e-4,
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+
+use crate::{fly::FlyCameraController, orbit::OrbitCameraController};
+
+/// Entity whose transform a controller writes, see
+/// [`OrbitCameraController::control_target`] and
+/// [`FlyCameraController::control_target`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum ControlTarget {
+    /// Move the camera entity holding the controller
+    #[default]
+    SelfEntity,
+    /// Move another entity, typically the pivot of a camera rig, so that
+    /// the camera ends up where the controller wants it. The camera entity
+    /// keeps the `Camera`, the controller and receives the input, and must
+    /// be a child of the target, which must have a `Transform`. The local
+    /// transform of the camera, i.e. its arm, is the one it has when the
+    /// target is set and is restored whenever the camera moves.
+    Entity(Entity),
+}
+
+/// Local transform of a camera relative to its [`ControlTarget::Entity`]
+#[derive(Component, Debug, Clone, Copy)]
+pub(crate) struct ControlTargetArm(pub Transform);
+
+/// Access to the transforms of the entities controlled instead of the
+/// cameras
+#[derive(SystemParam)]
+pub(crate) struct ControlTargets<'w, 's> {
+    #[allow(clippy::type_complexity)]
+    targets: Query<
+        'w,
+        's,
+        (
+            &'static mut Transform,
+            &'static GlobalTransform,
+            Option<&'static Parent>,
+        ),
+        (Without<OrbitCameraController>, Without<FlyCameraController>),
+    >,
+    global_transforms: Query<'w, 's, &'static GlobalTransform>,
+}
+
+impl ControlTargets<'_, '_> {
+    /// World transform of the camera attached to `target_entity` by `arm`,
+    /// `None` if the target has no transform
+    pub(crate) fn camera_transform(
+        &self,
+        target_entity: Entity,
+        arm: &Transform,
+    ) -> Option<Transform> {
+        let (_, target_global, _) = self.targets.get(target_entity).ok()?;
+        Some(target_global.mul_transform(*arm).compute_transform())
+    }
+
+    /// Move `target_entity` so that the camera attached to it by `arm` has
+    /// the world `camera_transform`
+    pub(crate) fn set_camera_transform(
+        &mut self,
+        target_entity: Entity,
+        arm: &Transform,
+        camera_transform: &Transform,
+    ) {
+        let parent_global = self
+            .targets
+            .get(target_entity)
+            .ok()
+            .and_then(|(_, _, parent_opt)| parent_opt)
+            .and_then(|parent| self.global_transforms.get(parent.get()).ok())
+            .copied();
+        let Ok((mut transform, _, _)) = self.targets.get_mut(target_entity)
+        else {
+            return;
+        };
+        let target_global = GlobalTransform::from(
+            GlobalTransform::from(*camera_transform).affine()
+                * arm.compute_affine().inverse(),
+        );
+        let local = match parent_global {
+            Some(parent_global) => target_global.reparented_to(&parent_global),
+            None => target_global.compute_transform(),
+        };
+        transform.set_if_neq(local);
+    }
+}
+
+/// Record the arm of the cameras controlling another entity and warn about
+/// the targets without a transform, when the controllers are added or
+/// changed. A missing target is only reported once as long as it does not
+/// change.
+#[allow(clippy::type_complexity)]
+pub(crate) fn validate_control_targets_system(
+    mut commands: Commands,
+    controllers: Query<
+        (
+            Entity,
+            &Transform,
+            Option<&OrbitCameraController>,
+            Option<&FlyCameraController>,
+            Has<ControlTargetArm>,
+        ),
+        Or<(Changed<OrbitCameraController>, Changed<FlyCameraController>)>,
+    >,
+    transforms: Query<(), With<Transform>>,
+    mut reported: Local<HashMap<Entity, Entity>>,
+) {
+    for (entity, transform, orbit_controller, fly_controller, has_arm) in
+        controllers.iter()
+    {
+        let target = [
+            orbit_controller.map(|controller| controller.control_target),
+            fly_controller.map(|controller| controller.control_target),
+        ]
+        .into_iter()
+        .flatten()
+        .find_map(|target| match target {
+            ControlTarget::Entity(target_entity) => Some(target_entity),
+            ControlTarget::SelfEntity => None,
+        });
+        let Some(target_entity) = target else {
+            if has_arm {
+                commands.entity(entity).remove::<ControlTargetArm>();
+            }
+            reported.remove(&entity);
+            continue;
+        };
+        if !has_arm {
+            commands.entity(entity).insert(ControlTargetArm(*transform));
+        }
+        if transforms.contains(target_entity) {
+            reported.remove(&entity);
+        } else if reported.get(&entity) != Some(&target_entity) {
+            warn!(
+                "Control target {target_entity} of camera {entity} has no \
+                Transform, the camera is not moved"
+            );
+            reported.insert(entity, target_entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+    use crate::{
+        math,
+        testing::{
+            assert_transform_approx_eq, set_active_camera, test_app,
+            SimulatedInput,
+        },
+    };
+
+    #[test]
+    fn orbiting_moves_the_rig_pivot_and_keeps_the_arm() {
+        let mut app = test_app();
+        // Vehicle carrying the pivot of the rig, itself carrying the camera
+        let vehicle_transform = Transform::from_xyz(10.0, 2.0, -3.0)
+            .with_rotation(Quat::from_rotation_y(30f32.to_radians()));
+        let pivot_local = Transform::from_xyz(0.0, 1.0, 0.0);
+        let arm = Transform::from_xyz(0.0, 0.0, 8.0);
+        let vehicle = app.world_mut().spawn(vehicle_transform).id();
+        let pivot = app.world_mut().spawn(pivot_local).set_parent(vehicle).id();
+        let focus = vehicle_transform.transform_point(pivot_local.translation);
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                arm,
+                OrbitCameraController {
+                    focus,
+                    // Orbit around the focus, there is no cursor
+                    auto_depth: false,
+                    control_target: ControlTarget::Entity(pivot),
+                    ..default()
+                },
+            ))
+            .set_parent(pivot)
+            .id();
+        set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+        app.update();
+        let global_transform = |app: &App, entity: Entity| {
+            app.world()
+                .get::<GlobalTransform>(entity)
+                .unwrap()
+                .compute_transform()
+        };
+        let start = global_transform(&app, camera);
+        assert_transform_approx_eq(
+            &start,
+            &vehicle_transform
+                .mul_transform(pivot_local)
+                .mul_transform(arm),
+            1e-4,
+        );
+
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .orbit(Vec2::new(20.0, 10.0));
+        app.update();
+        // The transforms are propagated at the end of the update
+        let world = app.world();
+        let controller = world.get::<OrbitCameraController>(camera).unwrap();
+        let radius = controller.radius.unwrap();
+        assert!((radius - 8.0).abs() < 1e-4);
+        let transform = global_transform(&app, camera);
+        assert_transform_approx_eq(
+            &transform,
+            &math::camera_transform_from_orbit(
+                controller.yaw.unwrap(),
+                controller.pitch.unwrap(),
+                radius,
+                focus,
+            ),
+            1e-4,
+        );
+        let delta_yaw = 20.0 / 800.0 * 2.0 * PI;
+        assert!(
+            transform.translation.distance(start.translation)
+                > 0.5 * radius * delta_yaw
+        );
+        // Only the pivot moved, around the focus
+        assert_eq!(*world.get::<Transform>(camera).unwrap(), arm);
+        assert_eq!(
+            *world.get::<Transform>(vehicle).unwrap(),
+            vehicle_transform
+        );
+        let pivot_transform = *world.get::<Transform>(pivot).unwrap();
+        assert_ne!(pivot_transform.rotation, pivot_local.rotation);
+        assert!(pivot_transform
+            .translation
+            .abs_diff_eq(pivot_local.translation, 1e-4));
+    }
+}
@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+
+use crate::{
+    fly::FlyCameraController, orbit::OrbitCameraController,
+    BlendyCamerasDisabled,
+};
+
+/// Resource with the settings, e.g. the sensitivities and bindings, of the
+/// controllers created with [`OrbitCameraController::from_defaults`] and
+/// [`FlyCameraController::from_defaults`], by [`spawn_blendy_camera`] and
+/// when switching to a missing controller. Not inserted by the plugin, the
+/// `Default` of the controllers is used when it is missing.
+#[derive(Resource, Default, Clone, Reflect)]
+#[reflect(Resource, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct DefaultControllerSettings {
+    /// Settings of the orbit controllers
+    pub orbit: OrbitCameraController,
+    /// Settings of the fly controllers
+    pub fly: FlyCameraController,
+}
+
+/// Event to apply the [`DefaultControllerSettings`] to existing controllers,
+/// e.g. after editing them in a settings screen. The state of the
+/// controllers, like the focus or whether they are enabled, is kept.
+#[derive(Event, Reflect)]
+pub struct ApplyDefaultControllerSettings {
+    /// The camera to update. If `None`, all the cameras are updated.
+    pub camera_entity: Option<Entity>,
+}
+
+/// Spawn a camera with `bundle`, e.g. `(Camera3d::default(), transform)`, an
+/// enabled [`OrbitCameraController`] and a disabled [`FlyCameraController`],
+/// using the [`DefaultControllerSettings`] if present
+pub fn spawn_blendy_camera(
+    commands: &mut Commands,
+    bundle: impl Bundle,
+) -> Entity {
+    let entity = commands.spawn(bundle).id();
+    commands.queue(move |world: &mut World| {
+        let settings = world
+            .get_resource::<DefaultControllerSettings>()
+            .cloned()
+            .unwrap_or_default();
+        let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+            return;
+        };
+        entity_mut.insert((
+            OrbitCameraController {
+                is_enabled: true,
+                ..OrbitCameraController::from_defaults(&settings)
+            },
+            FlyCameraController {
+                is_enabled: false,
+                ..FlyCameraController::from_defaults(&settings)
+            },
+        ));
+    });
+    entity
+}
+
+pub(crate) fn apply_default_controller_settings_system(
+    mut ev_read: EventReader<ApplyDefaultControllerSettings>,
+    settings_opt: Option<Res<DefaultControllerSettings>>,
+    mut orbit_controllers: Query<
+        (Entity, &mut OrbitCameraController),
+        Without<BlendyCamerasDisabled>,
+    >,
+    mut fly_controllers: Query<
+        (Entity, &mut FlyCameraController),
+        Without<BlendyCamerasDisabled>,
+    >,
+) {
+    if ev_read.is_empty() {
+        return;
+    }
+    let settings = settings_opt.as_deref().cloned().unwrap_or_default();
+    for ApplyDefaultControllerSettings { camera_entity } in ev_read.read() {
+        for (entity, mut controller) in orbit_controllers.iter_mut() {
+            if camera_entity.is_none_or(|camera| camera == entity) {
+                controller.apply_settings(&settings.orbit);
+            }
+        }
+        for (entity, mut controller) in fly_controllers.iter_mut() {
+            if camera_entity.is_none_or(|camera| camera == entity) {
+                controller.apply_settings(&settings.fly);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing::test_app, SwitchToFlyController};
+
+    fn settings(
+        orbit_sensitivity: f32,
+        speed: f32,
+    ) -> DefaultControllerSettings {
+        DefaultControllerSettings {
+            orbit: OrbitCameraController {
+                orbit_sensitivity,
+                ..default()
+            },
+            fly: FlyCameraController { speed, ..default() },
+        }
+    }
+
+    fn spawn_camera(app: &mut App) -> Entity {
+        let entity = spawn_blendy_camera(
+            &mut app.world_mut().commands(),
+            (
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 0.0, 5.0),
+            ),
+        );
+        app.world_mut().flush();
+        entity
+    }
+
+    fn orbit_sensitivity(app: &App, camera: Entity) -> f32 {
+        app.world()
+            .get::<OrbitCameraController>(camera)
+            .unwrap()
+            .orbit_sensitivity
+    }
+
+    #[test]
+    fn new_controllers_use_the_current_defaults() {
+        let mut app = test_app();
+        app.insert_resource(settings(2.0, 4.0));
+        let old = spawn_camera(&mut app);
+        let explicit = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 0.0, 5.0),
+                OrbitCameraController {
+                    orbit_sensitivity: 0.5,
+                    ..default()
+                },
+            ))
+            .id();
+        app.update();
+        app.insert_resource(settings(3.0, 6.0));
+        let new = spawn_camera(&mut app);
+        app.update();
+        assert_eq!(orbit_sensitivity(&app, new), 3.0);
+        assert_eq!(
+            app.world().get::<FlyCameraController>(new).unwrap().speed,
+            6.0
+        );
+        // The existing controllers are only updated on request
+        assert_eq!(orbit_sensitivity(&app, old), 2.0);
+        assert_eq!(orbit_sensitivity(&app, explicit), 0.5);
+        // A missing controller is created with the defaults
+        app.world_mut().send_event(SwitchToFlyController {
+            camera_entity: explicit,
+        });
+        app.update();
+        let world = app.world();
+        assert_eq!(
+            world.get::<FlyCameraController>(explicit).unwrap().speed,
+            6.0
+        );
+        assert_eq!(orbit_sensitivity(&app, explicit), 0.5);
+
+        let focus = Vec3::new(1.0, 2.0, 3.0);
+        app.world_mut()
+            .get_mut::<OrbitCameraController>(old)
+            .unwrap()
+            .focus = focus;
+        app.world_mut().send_event(ApplyDefaultControllerSettings {
+            camera_entity: Some(old),
+        });
+        app.update();
+        let controller = app.world().get::<OrbitCameraController>(old).unwrap();
+        assert_eq!(controller.orbit_sensitivity, 3.0);
+        assert_eq!(controller.focus, focus);
+        assert_eq!(orbit_sensitivity(&app, explicit), 0.5);
+    }
+}
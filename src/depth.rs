@@ -0,0 +1,367 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{prelude::*, utils::HashMap};
+#[cfg(feature = "depth_buffer")]
+pub(crate) use readback::{update_depth_probes_system, DepthReadbackPlugin};
+
+/// Maximum distance, in physical pixels, between the pixel of a depth sample
+/// and the queried position for the sample to be used
+const MAX_SAMPLE_PIXEL_DISTANCE: f32 = 4.0;
+
+/// Depth of the pixel under the cursor, read back from the depth buffer of a
+/// camera
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DepthSample {
+    /// Pixel of the sample, in physical render target coordinates
+    pixel: UVec2,
+    /// Depth of the pixel, `0.0` being the far plane
+    depth: f32,
+}
+
+/// Most recent depth sample of each camera, shared between the main world
+/// and the render world
+#[derive(Resource, Debug, Clone, Default)]
+pub(crate) struct DepthSamples(Arc<Mutex<HashMap<Entity, DepthSample>>>);
+
+impl DepthSamples {
+    /// World position of the most recent depth sample of `camera_entity` if
+    /// it was taken close to `position`, in physical render target
+    /// coordinates, and hit some geometry
+    pub(crate) fn world_point(
+        &self,
+        camera_entity: Entity,
+        camera: &Camera,
+        global_transform: &GlobalTransform,
+        position: Vec2,
+    ) -> Option<Vec3> {
+        let sample = *self.0.lock().ok()?.get(&camera_entity)?;
+        if sample.depth <= 0.0
+            || sample.pixel.as_vec2().distance(position.floor())
+                > MAX_SAMPLE_PIXEL_DISTANCE
+        {
+            return None;
+        }
+        let viewport = camera.physical_viewport_rect()?.as_rect();
+        let pixel_center = sample.pixel.as_vec2() + Vec2::splat(0.5);
+        if !viewport.contains(pixel_center) {
+            return None;
+        }
+        let uv = (pixel_center - viewport.min) / viewport.size();
+        let ndc = Vec3::new(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, sample.depth);
+        camera.ndc_to_world(global_transform, ndc)
+    }
+
+    #[cfg(feature = "depth_buffer")]
+    fn insert(&self, camera_entity: Entity, sample: DepthSample) {
+        if let Ok(mut samples) = self.0.lock() {
+            samples.insert(camera_entity, sample);
+        }
+    }
+
+    #[cfg(feature = "depth_buffer")]
+    fn retain(&self, mut keep: impl FnMut(&Entity) -> bool) {
+        if let Ok(mut samples) = self.0.lock() {
+            samples.retain(|entity, _| keep(entity));
+        }
+    }
+}
+
+#[cfg(feature = "depth_buffer")]
+mod readback {
+    use std::sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc,
+    };
+
+    use bevy::{
+        core_pipeline::core_3d::graph::{Core3d, Node3d},
+        ecs::query::QueryItem,
+        prelude::*,
+        render::{
+            extract_component::{ExtractComponent, ExtractComponentPlugin},
+            render_graph::{
+                NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel,
+                ViewNode, ViewNodeRunner,
+            },
+            render_resource::{
+                Buffer, BufferDescriptor, BufferUsages, Extent3d,
+                ImageCopyBuffer, ImageCopyTexture, ImageDataLayout, Maintain,
+                MapMode, Origin3d, TextureAspect, TextureUsages,
+            },
+            renderer::{render_system, RenderContext, RenderDevice},
+            sync_world::MainEntity,
+            view::{Msaa, ViewDepthTexture},
+            Render, RenderApp, RenderSet,
+        },
+        utils::HashMap,
+    };
+
+    use super::{DepthSample, DepthSamples};
+    use crate::{
+        orbit::AutoDepthSource, ActiveCameraData, OrbitCameraController,
+    };
+
+    /// The readback is idle and can be requested
+    const IDLE: u8 = 0;
+    /// The depth of the pixel must be copied to the buffer
+    const REQUESTED: u8 = 1;
+    /// The depth of the pixel has been copied to the buffer
+    const COPIED: u8 = 2;
+    /// The buffer is being mapped
+    const MAPPING: u8 = 3;
+    /// The buffer is mapped and can be read
+    const MAPPED: u8 = 4;
+
+    /// Adds the readback of the depth buffer under the cursor, used when
+    /// [`AutoDepthSource::DepthBuffer`] is selected
+    pub(crate) struct DepthReadbackPlugin;
+
+    impl Plugin for DepthReadbackPlugin {
+        fn build(&self, app: &mut App) {
+            app.add_plugins(ExtractComponentPlugin::<DepthProbe>::default());
+            let samples = app.world().resource::<DepthSamples>().clone();
+            let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+                return;
+            };
+            render_app
+                .insert_resource(samples)
+                .init_resource::<DepthReadbacks>()
+                .add_systems(
+                    Render,
+                    (
+                        prepare_depth_readbacks_system
+                            .in_set(RenderSet::PrepareResources),
+                        map_depth_readbacks_system
+                            .after(render_system)
+                            .in_set(RenderSet::Render),
+                    ),
+                )
+                .add_render_graph_node::<ViewNodeRunner<DepthReadbackNode>>(
+                    Core3d,
+                    DepthReadbackLabel,
+                )
+                .add_render_graph_edges(
+                    Core3d,
+                    (
+                        Node3d::EndMainPass,
+                        DepthReadbackLabel,
+                        Node3d::Tonemapping,
+                    ),
+                );
+        }
+    }
+
+    /// Pixel of the depth buffer of a camera to read back, in physical
+    /// render target coordinates
+    #[derive(Component, ExtractComponent, Debug, Clone, Default, PartialEq)]
+    pub(crate) struct DepthProbe {
+        pixel: Option<UVec2>,
+    }
+
+    /// Update the pixel under the cursor that is read back from the depth
+    /// buffer of the active camera, and make sure its depth texture can be
+    /// copied
+    pub(crate) fn update_depth_probes_system(
+        mut commands: Commands,
+        active_cam: Res<ActiveCameraData>,
+        mut cameras: Query<(
+            Entity,
+            &OrbitCameraController,
+            Option<&mut Camera3d>,
+            Option<&mut DepthProbe>,
+        )>,
+        windows: Query<&Window>,
+    ) {
+        let cursor_pixel = active_cam
+            .window_entity
+            .and_then(|window_entity| windows.get(window_entity).ok())
+            .and_then(Window::physical_cursor_position)
+            .map(|position| position.as_uvec2());
+        for (entity, controller, camera_3d_opt, probe_opt) in cameras.iter_mut()
+        {
+            let is_probed = controller.is_enabled
+                && (controller.auto_depth || controller.zoom_to_mouse_position)
+                && controller.auto_depth_source == AutoDepthSource::DepthBuffer
+                && active_cam.entity == Some(entity);
+            let probe = DepthProbe {
+                pixel: cursor_pixel.filter(|_| is_probed),
+            };
+            if is_probed {
+                if let Some(mut camera_3d) = camera_3d_opt {
+                    let usages =
+                        TextureUsages::from(camera_3d.depth_texture_usages);
+                    if !usages.contains(TextureUsages::COPY_SRC) {
+                        camera_3d.depth_texture_usages =
+                            (usages | TextureUsages::COPY_SRC).into();
+                    }
+                }
+            }
+            match probe_opt {
+                Some(mut current_probe) => {
+                    current_probe.set_if_neq(probe);
+                }
+                None if is_probed => {
+                    commands.entity(entity).insert(probe);
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Render graph label of [`DepthReadbackNode`]
+    #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+    struct DepthReadbackLabel;
+
+    /// Readback of one pixel of the depth buffer of a camera
+    struct DepthReadback {
+        buffer: Buffer,
+        pixel: UVec2,
+        state: Arc<AtomicU8>,
+    }
+
+    /// Depth readbacks of the probed cameras, by main world entity
+    #[derive(Resource, Default)]
+    struct DepthReadbacks(HashMap<Entity, DepthReadback>);
+
+    fn prepare_depth_readbacks_system(
+        mut readbacks: ResMut<DepthReadbacks>,
+        samples: Res<DepthSamples>,
+        render_device: Res<RenderDevice>,
+        views: Query<(&MainEntity, &DepthProbe)>,
+    ) {
+        // Let the buffers mapped in the previous frames finish mapping
+        render_device.poll(Maintain::Poll);
+        let mut probed = Vec::new();
+        for (main_entity, probe) in views.iter() {
+            let Some(pixel) = probe.pixel else {
+                continue;
+            };
+            probed.push(main_entity.id());
+            let readback =
+                readbacks.0.entry(main_entity.id()).or_insert_with(|| {
+                    DepthReadback {
+                        buffer: render_device.create_buffer(
+                            &BufferDescriptor {
+                                label: Some("blendy_cameras_depth_readback"),
+                                size: size_of::<f32>() as u64,
+                                usage: BufferUsages::COPY_DST
+                                    | BufferUsages::MAP_READ,
+                                mapped_at_creation: false,
+                            },
+                        ),
+                        pixel,
+                        state: Arc::new(AtomicU8::new(IDLE)),
+                    }
+                });
+            match readback.state.load(Ordering::Acquire) {
+                MAPPED => {
+                    let slice = readback.buffer.slice(..);
+                    let mut bytes = [0; size_of::<f32>()];
+                    bytes.copy_from_slice(&slice.get_mapped_range());
+                    readback.buffer.unmap();
+                    samples.insert(
+                        main_entity.id(),
+                        DepthSample {
+                            pixel: readback.pixel,
+                            depth: f32::from_ne_bytes(bytes),
+                        },
+                    );
+                }
+                IDLE => {}
+                _ => continue,
+            }
+            readback.pixel = pixel;
+            readback.state.store(REQUESTED, Ordering::Release);
+        }
+        readbacks.0.retain(|entity, _| probed.contains(entity));
+        samples.retain(|entity| probed.contains(entity));
+    }
+
+    fn map_depth_readbacks_system(readbacks: Res<DepthReadbacks>) {
+        for readback in readbacks.0.values() {
+            if readback
+                .state
+                .compare_exchange(
+                    COPIED,
+                    MAPPING,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                )
+                .is_ok()
+            {
+                let state = readback.state.clone();
+                readback.buffer.slice(..).map_async(
+                    MapMode::Read,
+                    move |result| {
+                        let new_state =
+                            if result.is_ok() { MAPPED } else { IDLE };
+                        state.store(new_state, Ordering::Release);
+                    },
+                );
+            }
+        }
+    }
+
+    /// Copy the requested pixel of the depth buffer of the view once the
+    /// main pass is done
+    #[derive(Default)]
+    struct DepthReadbackNode;
+
+    impl ViewNode for DepthReadbackNode {
+        type ViewQuery = (
+            &'static MainEntity,
+            &'static ViewDepthTexture,
+            &'static Msaa,
+        );
+
+        fn run<'w>(
+            &self,
+            _graph: &mut RenderGraphContext,
+            render_context: &mut RenderContext<'w>,
+            (main_entity, depth, msaa): QueryItem<'w, Self::ViewQuery>,
+            world: &'w World,
+        ) -> Result<(), NodeRunError> {
+            // Multisampled textures can not be copied
+            if msaa.samples() > 1 {
+                return Ok(());
+            }
+            let Some(readback) = world
+                .get_resource::<DepthReadbacks>()
+                .and_then(|readbacks| readbacks.0.get(&main_entity.id()))
+            else {
+                return Ok(());
+            };
+            if readback.state.load(Ordering::Acquire) != REQUESTED {
+                return Ok(());
+            }
+            let size = depth.texture.size();
+            render_context.command_encoder().copy_texture_to_buffer(
+                ImageCopyTexture {
+                    texture: &depth.texture,
+                    mip_level: 0,
+                    origin: Origin3d {
+                        x: readback.pixel.x.min(size.width.saturating_sub(1)),
+                        y: readback.pixel.y.min(size.height.saturating_sub(1)),
+                        z: 0,
+                    },
+                    aspect: TextureAspect::DepthOnly,
+                },
+                ImageCopyBuffer {
+                    buffer: &readback.buffer,
+                    layout: ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: None,
+                        rows_per_image: None,
+                    },
+                },
+                Extent3d {
+                    width: 1,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+            );
+            readback.state.store(COPIED, Ordering::Release);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use std::{f32::consts::PI, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{
+    orbit::OrbitCameraController,
+    transition::{OrbitState, ViewTransition},
+    BlendyCamerasDisabled,
+};
+
+/// Event to animate the field of view of a perspective camera to
+/// `target_fov` while moving the camera so that the subject at the focus
+/// keeps the same size on screen, also known as the "vertigo" effect.
+/// The camera must have an enabled [`OrbitCameraController`]. A
+/// [`DollyZoomCompleted`] event is sent when the animation ends.
+#[derive(Event, Debug, Clone, Reflect)]
+pub struct DollyZoomEvent {
+    /// The camera to animate
+    pub camera_entity: Entity,
+    /// Field of view at the end of the animation, in radian
+    pub target_fov: f32,
+    /// Duration of the animation
+    pub duration: Duration,
+}
+
+/// Event sent when the animation started by a [`DollyZoomEvent`] ends
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub struct DollyZoomCompleted {
+    /// The animated camera
+    pub camera_entity: Entity,
+    /// Whether the animation was cancelled before reaching the target field
+    /// of view, e.g. because the user moved the camera
+    pub is_cancelled: bool,
+}
+
+/// Radius keeping the size of the subject at the focus when changing the
+/// field of view from `start_fov` at `start_radius` to `fov`
+pub(crate) fn dolly_zoom_radius(
+    start_radius: f32,
+    start_fov: f32,
+    fov: f32,
+) -> f32 {
+    start_radius * (start_fov / 2.0).tan() / (fov / 2.0).tan()
+}
+
+pub(crate) fn dolly_zoom_system(
+    mut commands: Commands,
+    mut ev_read: EventReader<DollyZoomEvent>,
+    mut cameras_query: Query<
+        (&mut OrbitCameraController, &mut Transform, &mut Projection),
+        Without<BlendyCamerasDisabled>,
+    >,
+) {
+    for DollyZoomEvent {
+        camera_entity,
+        target_fov,
+        duration,
+    } in ev_read.read()
+    {
+        let Ok((mut controller, mut transform, mut projection)) =
+            cameras_query.get_mut(*camera_entity)
+        else {
+            warn!("Camera not found while trying to dolly zoom");
+            continue;
+        };
+        if !controller.is_enabled {
+            warn!("Orbit controller disabled while trying to dolly zoom");
+            continue;
+        }
+        let Projection::Perspective(ref p) = *projection else {
+            warn!("Dolly zoom is not possible with an orthographic projection");
+            continue;
+        };
+        let start_fov = p.fov;
+        if !(*target_fov > 0.0 && *target_fov < PI) {
+            warn!("Invalid field of view {target_fov} for dolly zoom");
+            continue;
+        }
+        controller.initialize_if_necessary(&mut transform, &mut projection);
+        let Some(start) = OrbitState::from_controller(&controller) else {
+            continue;
+        };
+        commands
+            .entity(*camera_entity)
+            .insert(ViewTransition::dolly_zoom(
+                start,
+                start_fov,
+                *target_fov,
+                *duration,
+            ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::testing::{set_active_camera, test_app, SimulatedInput};
+
+    /// App with updates of 100 ms and an active camera at 10 from the focus
+    fn app_with_camera(projection: Projection) -> (App, Entity) {
+        let mut app = test_app();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            Duration::from_millis(100),
+        ));
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                projection,
+                Transform::from_xyz(0.0, 0.0, 10.0),
+                OrbitCameraController::default(),
+            ))
+            .id();
+        set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+        app.update();
+        (app, camera)
+    }
+
+    fn dolly_zoom(app: &mut App, camera: Entity) {
+        app.world_mut().send_event(DollyZoomEvent {
+            camera_entity: camera,
+            target_fov: 0.3,
+            duration: Duration::from_millis(500),
+        });
+    }
+
+    fn fov(app: &App, camera: Entity) -> f32 {
+        match app.world().get::<Projection>(camera).unwrap() {
+            Projection::Perspective(p) => p.fov,
+            Projection::Orthographic(_) => panic!("not perspective"),
+        }
+    }
+
+    /// Half height of the view at the focus
+    fn subject_size(app: &App, camera: Entity) -> f32 {
+        let distance =
+            app.world().get::<Transform>(camera).unwrap().translation.z;
+        distance * (fov(app, camera) / 2.0).tan()
+    }
+
+    fn completed_events(app: &App) -> Vec<DollyZoomCompleted> {
+        app.world()
+            .resource::<Events<DollyZoomCompleted>>()
+            .iter_current_update_events()
+            .copied()
+            .collect()
+    }
+
+    #[test]
+    fn subject_size_is_kept_during_the_animation() {
+        let (mut app, camera) = app_with_camera(Projection::default());
+        let size = subject_size(&app, camera);
+        let start_fov = fov(&app, camera);
+        dolly_zoom(&mut app, camera);
+        let mut completed = Vec::new();
+        let mut fovs = vec![start_fov];
+        for _ in 0..8 {
+            app.update();
+            assert!((subject_size(&app, camera) - size).abs() < 1e-4);
+            fovs.push(fov(&app, camera));
+            completed.extend(completed_events(&app));
+        }
+        // Animated, not applied at once
+        assert!(fovs.windows(2).all(|fovs| fovs[1] <= fovs[0]));
+        assert!(fovs.iter().any(|&fov| fov > 0.3 && fov < start_fov));
+        assert_eq!(fov(&app, camera), 0.3);
+        assert!(app.world().get::<ViewTransition>(camera).is_none());
+        assert_eq!(
+            completed,
+            [DollyZoomCompleted {
+                camera_entity: camera,
+                is_cancelled: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn orthographic_cameras_are_rejected() {
+        let (mut app, camera) = app_with_camera(Projection::Orthographic(
+            OrthographicProjection::default_3d(),
+        ));
+        let transform = *app.world().get::<Transform>(camera).unwrap();
+        dolly_zoom(&mut app, camera);
+        app.update();
+        assert!(app.world().get::<ViewTransition>(camera).is_none());
+        assert!(matches!(
+            app.world().get::<Projection>(camera).unwrap(),
+            Projection::Orthographic(_)
+        ));
+        assert_eq!(*app.world().get::<Transform>(camera).unwrap(), transform);
+        assert!(completed_events(&app).is_empty());
+    }
+
+    #[test]
+    fn input_cancels_the_animation() {
+        let (mut app, camera) = app_with_camera(Projection::default());
+        let size = subject_size(&app, camera);
+        dolly_zoom(&mut app, camera);
+        app.update();
+        app.update();
+        let fov_at_cancel = fov(&app, camera);
+        assert!(fov_at_cancel > 0.3);
+        app.world_mut().resource_mut::<SimulatedInput>().scroll(1.0);
+        app.update();
+        assert!(app.world().get::<ViewTransition>(camera).is_none());
+        assert_eq!(
+            completed_events(&app),
+            [DollyZoomCompleted {
+                camera_entity: camera,
+                is_cancelled: true,
+            }]
+        );
+        // The zoom moved the camera but the field of view stays
+        assert_eq!(fov(&app, camera), fov_at_cancel);
+        assert!(subject_size(&app, camera) < size);
+        for _ in 0..5 {
+            app.update();
+        }
+        assert_eq!(fov(&app, camera), fov_at_cancel);
+    }
+}
@@ -1,11 +1,14 @@
 // From bevy_panorbit_camera
 use bevy::prelude::*;
+use bevy_egui::egui;
+
+use crate::{input::CameraInputBlocked, ControllerMode, Viewpoint};
 
 /// A resource that tracks whether egui wants focus on the current and previous
 /// frames.
 ///
 /// The reason the previous frame's value is saved is because when you click
-/// inside an egui window, Context::wants_pointer_input() still returns false
+/// inside an egui window, `Context::wants_pointer_input()` still returns false
 /// once before returning true. If the camera stops taking input only when it
 /// returns false, there's one frame where both egui and the camera are using
 /// the input events, which is not desirable.
@@ -18,11 +21,49 @@ pub struct EguiWantsFocus {
     pub prev: bool,
     /// Whether egui wants focus on the current frame
     pub curr: bool,
+    /// Whether egui blocked the scroll on the previous frame
+    pub scroll_prev: bool,
+    /// Whether egui blocks the scroll on the current frame. Same as `curr`
+    /// unless [`EguiFocusConfig::block_scroll_only_when_egui_wants_scroll`]
+    /// is set.
+    pub scroll_curr: bool,
+}
+
+/// Configuration of what egui state stops the cameras from taking the input.
+/// The default blocks all the input while the pointer is over an egui area.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct EguiFocusConfig {
+    /// The pointer being over an egui area is enough to block the input.
+    /// Otherwise, only egui wanting the pointer or keyboard input does.
+    /// Defaults to `true`.
+    pub treat_hover_as_focus: bool,
+    /// Let the scroll zoom the camera over the egui areas, e.g. a toolbar
+    /// overlaying the viewport, unless egui is using the pointer. Note that
+    /// a scrollable egui area under the pointer also scrolls. Defaults to
+    /// `false`.
+    pub block_scroll_only_when_egui_wants_scroll: bool,
+}
+
+impl Default for EguiFocusConfig {
+    fn default() -> Self {
+        Self {
+            treat_hover_as_focus: true,
+            block_scroll_only_when_egui_wants_scroll: false,
+        }
+    }
 }
 
 pub(crate) fn check_egui_wants_focus(
     mut contexts: bevy_egui::EguiContexts,
     mut wants_focus: ResMut<EguiWantsFocus>,
+    mut input_blocked: ResMut<CameraInputBlocked>,
+    config: Res<EguiFocusConfig>,
     windows: Query<Entity, With<Window>>,
 ) {
     // The window that the user is interacting with and the window that
@@ -30,19 +71,170 @@ pub(crate) fn check_egui_wants_focus(
     // going to be the same. Therefore, we can assume that if any of the egui
     // contexts want focus, then it must be the one that the user is
     // interacting with.
-    let new_wants_focus = windows.iter().any(|window| {
-        if let Some(ctx) = contexts.try_ctx_for_entity_mut(window) {
-            ctx.wants_pointer_input()
-                || ctx.wants_keyboard_input()
-                || ctx.is_pointer_over_area()
+    let mut new_wants_focus = false;
+    let mut new_wants_scroll = false;
+    let mut wants_keyboard = false;
+    for window in windows.iter() {
+        let Some(ctx) = contexts.try_ctx_for_entity_mut(window) else {
+            continue;
+        };
+        let ctx_wants_focus = ctx.wants_pointer_input()
+            || ctx.wants_keyboard_input()
+            || (config.treat_hover_as_focus && ctx.is_pointer_over_area());
+        new_wants_focus |= ctx_wants_focus;
+        new_wants_scroll |= if config.block_scroll_only_when_egui_wants_scroll {
+            ctx.is_using_pointer()
         } else {
-            false
-        }
-    });
+            ctx_wants_focus
+        };
+        wants_keyboard |= ctx.wants_keyboard_input();
+    }
     let new_res = EguiWantsFocus {
         prev: wants_focus.curr,
         curr: new_wants_focus,
+        scroll_prev: wants_focus.scroll_curr,
+        scroll_curr: new_wants_scroll,
     };
+    // Do not move the cameras with the keys typed in a text field
+    if wants_keyboard {
+        input_blocked.block_keyboard();
+    }
     trace!("Egui want focus: {new_res:?}");
     wants_focus.set_if_neq(new_res);
 }
+
+/// Axes of the navigation gizmo: direction, viewpoint looking from this
+/// direction, label and color. The negative axes have no label.
+const GIZMO_AXES: [(Vec3, Viewpoint, Option<&str>, [u8; 3]); 6] = [
+    (Vec3::X, Viewpoint::Right, Some("X"), [230, 70, 80]),
+    (Vec3::Y, Viewpoint::Top, Some("Y"), [110, 190, 40]),
+    (Vec3::Z, Viewpoint::Front, Some("Z"), [60, 130, 230]),
+    (Vec3::NEG_X, Viewpoint::Left, None, [230, 70, 80]),
+    (Vec3::NEG_Y, Viewpoint::Bottom, None, [110, 190, 40]),
+    (Vec3::NEG_Z, Viewpoint::Back, None, [60, 130, 230]),
+];
+
+/// Axis handle of the navigation gizmo, projected on the widget
+struct GizmoHandle {
+    position: egui::Pos2,
+    /// Distance toward the viewer, used to draw back to front
+    depth: f32,
+    viewpoint: Viewpoint,
+    label: Option<&'static str>,
+    color: egui::Color32,
+}
+
+/// Navigation gizmo showing the world axes seen from a camera with
+/// `transform`, like the one in the corner of Blender's viewport. Returns the
+/// viewpoint looking from the axis handle the user clicked, to send a
+/// [`ViewpointEvent`](crate::ViewpointEvent) with it for example. The widget
+/// is a square of `size` points.
+pub fn navigation_gizmo_ui(
+    ui: &mut egui::Ui,
+    transform: &Transform,
+    size: f32,
+) -> Option<Viewpoint> {
+    let (rect, response) =
+        ui.allocate_exact_size(egui::Vec2::splat(size), egui::Sense::click());
+    if !ui.is_rect_visible(rect) {
+        return None;
+    }
+    let handle_radius = size * 0.1;
+    let axis_length = size * 0.5 - handle_radius;
+    let center = rect.center();
+    let inverse_rotation = transform.rotation.inverse();
+    let mut handles = GIZMO_AXES.map(|(direction, viewpoint, label, color)| {
+        // In view space, `z` points toward the viewer
+        let view_direction = inverse_rotation * direction;
+        GizmoHandle {
+            position: center
+                + egui::vec2(view_direction.x, -view_direction.y) * axis_length,
+            depth: view_direction.z,
+            viewpoint,
+            label,
+            color: egui::Color32::from_rgb(color[0], color[1], color[2]),
+        }
+    });
+    handles.sort_by(|a, b| a.depth.total_cmp(&b.depth));
+    // The front most handle under the pointer is hovered
+    let hovered = response.hover_pos().and_then(|pointer| {
+        handles.iter().rposition(|handle| {
+            handle.position.distance(pointer) <= handle_radius
+        })
+    });
+    let painter = ui.painter_at(rect);
+    if response.hovered() {
+        painter.circle_filled(
+            center,
+            size * 0.5,
+            ui.visuals()
+                .widgets
+                .hovered
+                .weak_bg_fill
+                .gamma_multiply(0.5),
+        );
+    }
+    for (index, handle) in handles.iter().enumerate() {
+        // Only the positive axes have a line and a label, the negative ones
+        // are dimmed
+        let fill = if handle.label.is_some() {
+            painter.line_segment(
+                [center, handle.position],
+                egui::Stroke::new(2.0, handle.color),
+            );
+            handle.color
+        } else {
+            handle.color.gamma_multiply(0.5)
+        };
+        painter.circle_filled(handle.position, handle_radius, fill);
+        if hovered == Some(index) {
+            painter.circle_stroke(
+                handle.position,
+                handle_radius,
+                egui::Stroke::new(2.0, ui.visuals().strong_text_color()),
+            );
+        }
+        if let Some(label) = handle.label {
+            painter.text(
+                handle.position,
+                egui::Align2::CENTER_CENTER,
+                label,
+                egui::FontId::proportional(handle_radius * 1.4),
+                egui::Color32::BLACK,
+            );
+        }
+    }
+    let hovered_viewpoint = hovered.map(|index| handles[index].viewpoint);
+    if hovered_viewpoint.is_some() {
+        ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+    }
+    hovered_viewpoint.filter(|_| response.clicked())
+}
+
+/// Toolbar with a button for each camera controller, showing which one is
+/// enabled. Returns the mode of the button the user clicked, to send a
+/// [`SetCameraControllerEvent`](crate::SetCameraControllerEvent) with it for
+/// example.
+pub fn controller_toolbar_ui(
+    ui: &mut egui::Ui,
+    orbit_enabled: bool,
+    fly_enabled: bool,
+) -> Option<ControllerMode> {
+    ui.horizontal(|ui| {
+        let mut mode = None;
+        if ui
+            .add(egui::Button::new("Orbit").selected(orbit_enabled))
+            .clicked()
+        {
+            mode = Some(ControllerMode::Orbit);
+        }
+        if ui
+            .add(egui::Button::new("Fly").selected(fly_enabled))
+            .clicked()
+        {
+            mode = Some(ControllerMode::Fly);
+        }
+        mode
+    })
+    .inner
+}
@@ -1,13 +1,31 @@
-use std::f32::consts::PI;
+use std::{borrow::Cow, f32::consts::PI};
 
 use bevy::prelude::*;
 
-use crate::{input::MouseKeyTracker, ActiveCameraData};
+use crate::{
+    bookmarks::ViewBookmark,
+    control_target::{ControlTarget, ControlTargetArm, ControlTargets},
+    default_settings::DefaultControllerSettings,
+    input::{
+        self, CameraInputBlocked, CameraInputState, HorizontalScrollAction,
+        InputBinding, SensitivityCurve,
+    },
+    orbit::OrbitCameraController,
+    utils, ActiveCameraData, BlendyCamerasDisabled, CameraViewChanged,
+    ViewChangeCause,
+};
 
 /// Component to tag an entiy as able to be controlled in "fly mode"
 /// The entity must have `Transform` and `Projection` components. Typically
 /// you would add `Camera3d` to this entity.
-#[derive(Component)]
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+#[require(CameraInputState)]
 pub struct FlyCameraController {
     /// Speed with wich the entity is moved. Updated when scrolling mouse wheel
     pub speed: f32,
@@ -23,20 +41,95 @@ pub struct FlyCameraController {
     pub key_move_up: KeyCode,
     /// Key used to move the camera down
     pub key_move_down: KeyCode,
-    /// Mouse button used to rotate the camera
+    /// Mouse button used to rotate the camera. Ignored if `rotate_bindings`
+    /// is not empty.
     pub button_rotate: MouseButton,
-    /// Key that must be pressed for the `button_rotate` to work
+    /// Key that must be pressed for the `button_rotate` to work. Ignored if
+    /// `rotate_bindings` is not empty.
     pub modifier_rotate: Option<KeyCode>,
+    /// What to do with the horizontal scroll of trackpads and tilt wheels.
+    /// Defaults to strafing sideways.
+    pub horizontal_scroll_action: HorizontalScrollAction,
+    /// Bindings used to rotate the camera, any of them works. If empty,
+    /// `button_rotate` and `modifier_rotate` are used instead.
+    pub rotate_bindings: Vec<InputBinding>,
+    /// Key that, while held, keeps the bindings of the controller from
+    /// matching, e.g. while a tool of the application uses the same mouse
+    /// button. Pressing it during a drag ends the drag as if the button was
+    /// released. Defaults to `None`.
+    pub suppress_modifier: Option<KeyCode>,
     /// Sensitivity of the speed change
     pub speed_sensitivity: f32,
     /// Sensitivity of the movement
     pub move_sensitivity: f32,
     /// Sensitivity of the rotation
     pub rotate_sensitivity: f32,
+    /// Response to the rotating motion, applied before `rotate_sensitivity`
+    pub rotate_curve: SensitivityCurve,
     /// Do not control the camera if `false`
     pub is_enabled: bool,
     /// Grab the mouse cursor while rotating if `true`
     pub grab_cursor: bool,
+    /// Hide the mouse cursor while rotating
+    pub hide_cursor_during_drag: bool,
+    /// Move the mouse cursor back to where the rotation started when it ends
+    pub restore_cursor_on_release: bool,
+    /// Key cancelling the current rotation, turning the camera back to where
+    /// it was when the rotation started. The rotation ends and the input is
+    /// ignored until the mouse buttons are released.
+    pub key_cancel_drag: Option<KeyCode>,
+    /// Also cancel the current rotation with a click of the right mouse
+    /// button, see `key_cancel_drag`
+    pub cancel_drag_on_right_click: bool,
+    /// Distance, in logical pixels, the mouse must move before rotating
+    /// starts, see [`OrbitCameraController::drag_start_threshold_px`].
+    /// Defaults to `0.0`, starting the rotation immediately.
+    pub drag_start_threshold_px: f32,
+    /// Entity moved by the controller, the camera itself by default
+    pub control_target: ControlTarget,
+    /// Axes along which the movement keys move the camera
+    pub translation_frame: TranslationFrame,
+    /// How the mouse rotates the camera
+    pub rotation_mode: FlyRotationMode,
+    /// Key used to remove the roll of the camera
+    pub key_level_horizon: Option<KeyCode>,
+}
+
+/// Axes along which a [`FlyCameraController`] moves the camera
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum TranslationFrame {
+    /// Move along the axes of the camera, diving when looking down
+    #[default]
+    View,
+    /// Move forward and sideways horizontally whatever the pitch of the
+    /// camera, up and down along the world Y axis
+    Planar,
+}
+
+/// How a [`FlyCameraController`] rotates the camera
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum FlyRotationMode {
+    /// Yaw around the world Y axis and pitch around the horizontal axis of
+    /// the camera, so that the rotation never adds roll. A roll set by
+    /// another system is kept.
+    #[default]
+    Upright,
+    /// Yaw and pitch around the axes of the camera, for 6 degrees of
+    /// freedom scenes like space or underwater ones. Roll accumulates and
+    /// can be removed with [`FlyCameraController::key_level_horizon`].
+    FreeLook,
 }
 
 impl Default for FlyCameraController {
@@ -51,31 +144,242 @@ impl Default for FlyCameraController {
             key_move_down: KeyCode::KeyW,
             button_rotate: MouseButton::Middle,
             modifier_rotate: None,
+            horizontal_scroll_action: HorizontalScrollAction::Pan,
+            rotate_bindings: Vec::new(),
+            suppress_modifier: None,
             speed_sensitivity: 1.0,
             move_sensitivity: 1.0,
             rotate_sensitivity: 1.0,
+            rotate_curve: SensitivityCurve::Linear,
             is_enabled: true,
             grab_cursor: true,
+            hide_cursor_during_drag: false,
+            restore_cursor_on_release: false,
+            key_cancel_drag: Some(KeyCode::Escape),
+            cancel_drag_on_right_click: false,
+            drag_start_threshold_px: 0.0,
+            control_target: ControlTarget::SelfEntity,
+            translation_frame: TranslationFrame::View,
+            rotation_mode: FlyRotationMode::Upright,
+            key_level_horizon: None,
         }
     }
 }
 
+impl FlyCameraController {
+    /// Controller with the fly settings of `settings`
+    pub fn from_defaults(settings: &DefaultControllerSettings) -> Self {
+        settings.fly.clone()
+    }
+
+    /// Replace the settings, e.g. the sensitivities and bindings, with the
+    /// ones of `settings`, keeping the state of the controller: the speed,
+    /// whether it is enabled and its control target
+    pub fn apply_settings(&mut self, settings: &FlyCameraController) {
+        *self = Self {
+            speed: self.speed,
+            control_target: self.control_target,
+            is_enabled: self.is_enabled,
+            ..settings.clone()
+        };
+    }
+
+    /// The bindings used to rotate the camera, either `rotate_bindings` or
+    /// the binding made of `button_rotate` and `modifier_rotate`
+    pub fn rotate_bindings(&self) -> Cow<'_, [InputBinding]> {
+        if self.rotate_bindings.is_empty() {
+            Cow::Owned(vec![InputBinding::from_button_and_modifiers(
+                self.button_rotate,
+                self.modifier_rotate,
+                &[],
+            )])
+        } else {
+            Cow::Borrowed(&self.rotate_bindings)
+        }
+    }
+
+    /// Whether one of the bindings used to rotate the camera is active
+    pub fn is_rotate_pressed(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_pressed(
+            &self.rotate_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+
+    /// Whether one of the bindings used to rotate the camera has just been activated
+    pub fn is_rotate_just_pressed(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_just_pressed(
+            &self.rotate_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+
+    /// Whether one of the bindings used to rotate the camera has just been deactivated
+    pub fn is_rotate_just_released(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_just_released(
+            &self.rotate_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+
+    /// Whether one of the keys used to move the camera is pressed
+    pub fn is_move_pressed(&self, key_input: &ButtonInput<KeyCode>) -> bool {
+        key_input.any_pressed(self.move_keys())
+    }
+
+    /// Whether one of the keys used to move the camera has just been pressed
+    pub fn is_move_just_pressed(
+        &self,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        key_input.any_just_pressed(self.move_keys())
+    }
+
+    fn move_keys(&self) -> [KeyCode; 6] {
+        [
+            self.key_move_forward,
+            self.key_move_backward,
+            self.key_move_left,
+            self.key_move_right,
+            self.key_move_up,
+            self.key_move_down,
+        ]
+    }
+
+    /// Settings emulating a 3 button mouse like Blender does, for laptops
+    /// without a middle mouse button. In addition to the middle mouse
+    /// button, rotate with Alt + left mouse button.
+    pub fn blender_emulated_3_button() -> Self {
+        Self {
+            rotate_bindings: vec![
+                InputBinding::mouse(MouseButton::Middle),
+                InputBinding::mouse(MouseButton::Left)
+                    .with_modifier(KeyCode::AltLeft),
+            ],
+            ..default()
+        }
+    }
+}
+
+/// Distance, relative to the speed, moved sideways per pixel of horizontal
+/// scroll
+const STRAFE_PER_PIXEL: f32 = 0.01;
+
+/// Run condition of the fly controller system: the active camera has input,
+/// or the arm of a camera controlling another entity moved. Otherwise all
+/// the cameras are idle and the system is skipped entirely.
+#[allow(clippy::type_complexity)]
+pub(crate) fn fly_controllers_need_update(
+    active_cam: Res<ActiveCameraData>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    key_input: Res<ButtonInput<KeyCode>>,
+    input_states: Query<&CameraInputState>,
+    moved_arms: Query<
+        (),
+        (
+            With<FlyCameraController>,
+            Or<(Changed<Transform>, Changed<ControlTargetArm>)>,
+            With<ControlTargetArm>,
+        ),
+    >,
+) -> bool {
+    input::active_camera_has_pending_input(
+        &active_cam,
+        &input_states,
+        &mouse_input,
+        &key_input,
+    ) || !moved_arms.is_empty()
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub(crate) fn fly_camera_controller_system(
     active_cam: Res<ActiveCameraData>,
     key_input: Res<ButtonInput<KeyCode>>,
-    mouse_key_tracker: Res<MouseKeyTracker>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    input_blocked: Res<CameraInputBlocked>,
     time: Res<Time>,
-    mut fly_cameras: Query<(Entity, &mut FlyCameraController, &mut Transform)>,
+    mut fly_cameras: Query<
+        (
+            Entity,
+            &mut FlyCameraController,
+            &mut Transform,
+            &CameraInputState,
+            &Projection,
+            Option<&OrbitCameraController>,
+            Option<&ControlTargetArm>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+    mut control_targets: ControlTargets,
+    mut changed_ev_write: EventWriter<CameraViewChanged>,
 ) {
-    for (entity, mut controller, mut transform) in fly_cameras.iter_mut() {
-        if controller.is_enabled && active_cam.entity == Some(entity) {
+    for (
+        entity,
+        mut controller,
+        mut local_transform,
+        input_state,
+        projection,
+        orbit_controller_opt,
+        arm_opt,
+    ) in fly_cameras.iter_mut()
+    {
+        // Skip the idle cameras without touching the components, so that
+        // they are not marked as changed
+        let has_input = controller.is_enabled
+            && active_cam.entity == Some(entity)
+            && !input_blocked.is_blocked()
+            && input::has_pending_input(input_state, &mouse_input, &key_input);
+        let is_arm_moved = arm_opt.is_some_and(|arm| arm.0 != *local_transform);
+        if !has_input && !is_arm_moved {
+            continue;
+        }
+        let target_opt = match controller.control_target {
+            ControlTarget::Entity(target_entity) => Some((
+                target_entity,
+                arm_opt.map_or(*local_transform, |arm| arm.0),
+            )),
+            ControlTarget::SelfEntity => None,
+        };
+        let mut transform = match target_opt {
+            Some((target_entity, arm)) => {
+                // Reported by the validation of the control targets
+                let Some(transform) =
+                    control_targets.camera_transform(target_entity, &arm)
+                else {
+                    continue;
+                };
+                transform
+            }
+            None => *local_transform,
+        };
+        let pre_transform = transform;
+        if has_input {
             // TODO: remove duplicated code with orbit?
             let rotate =
-                mouse_key_tracker.rotate * controller.rotate_sensitivity;
+                controller.rotate_curve.apply_motion(input_state.rotate)
+                    * controller.rotate_sensitivity;
             let scroll_line =
-                mouse_key_tracker.scroll_line * controller.speed_sensitivity;
+                input_state.scroll_line * controller.speed_sensitivity;
             let scroll_pixel =
-                mouse_key_tracker.scroll_pixel * controller.speed_sensitivity;
+                input_state.scroll_pixel * controller.speed_sensitivity;
 
             if (scroll_line + scroll_pixel).abs() > 0.0 {
                 let old_speed = controller.speed;
@@ -92,23 +396,70 @@ pub(crate) fn fly_camera_controller_system(
                 // Use window size for rotation otherwise the sensitivity
                 // is far too high for small viewports
                 // TODO: remove duplicated code with orbit
-                if let Some(win_size) = active_cam.window_size {
+                if let Some(win_size) =
+                    utils::non_empty_size(active_cam.window_size)
+                {
                     let delta_yaw = rotate.x / win_size.x * PI * 2.0;
                     let delta_pitch = rotate.y / win_size.y * PI;
-                    // Order is important to avoid unwanted roll
-                    let (mut yaw, mut pitch, _) =
+                    transform.rotation = match controller.rotation_mode {
+                        FlyRotationMode::Upright => {
+                            // Order is important to avoid unwanted roll
+                            let (yaw, pitch, roll) =
+                                transform.rotation.to_euler(EulerRot::YXZ);
+                            Quat::from_euler(
+                                EulerRot::YXZ,
+                                yaw - delta_yaw,
+                                pitch - delta_pitch,
+                                roll,
+                            )
+                        }
+                        FlyRotationMode::FreeLook => (transform.rotation
+                            * Quat::from_rotation_y(-delta_yaw)
+                            * Quat::from_rotation_x(-delta_pitch))
+                        .normalize(),
+                    };
+                }
+            }
+            let level_horizon =
+                controller.key_level_horizon.is_some_and(|key| {
+                    key_input.just_pressed(key)
+                        && !input_blocked.is_keyboard_blocked()
+                });
+            if level_horizon {
+                let (yaw, pitch, _) =
+                    transform.rotation.to_euler(EulerRot::YXZ);
+                transform.rotation =
+                    Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
+            }
+            let (forward, left, up) = match controller.translation_frame {
+                TranslationFrame::View => (
+                    Vec3::from(transform.forward()),
+                    Vec3::from(transform.left()),
+                    Vec3::from(transform.up()),
+                ),
+                TranslationFrame::Planar => {
+                    // Only keep the yaw, which is also defined when looking
+                    // straight down
+                    let (yaw, _, _) =
                         transform.rotation.to_euler(EulerRot::YXZ);
-                    yaw -= delta_yaw;
-                    pitch -= delta_pitch;
-                    transform.rotation = Quat::from_axis_angle(Vec3::Y, yaw)
-                        * Quat::from_axis_angle(Vec3::X, pitch);
+                    let yaw = Quat::from_rotation_y(yaw);
+                    (yaw * Vec3::NEG_Z, yaw * Vec3::NEG_X, Vec3::Y)
                 }
+            };
+            // Strafe with the horizontal scroll, which is a displacement
+            // rather than a velocity
+            let strafe = input_state.pan.x * STRAFE_PER_PIXEL;
+            if strafe != 0.0 {
+                transform.translation -= left
+                    * strafe
+                    * controller.speed
+                    * controller.move_sensitivity;
             }
-            let forward = Vec3::from(transform.forward());
-            let left = Vec3::from(transform.left());
-            let up = Vec3::from(transform.up());
             let mut translation = Vec3::ZERO;
-            for key in key_input.get_pressed() {
+            let pressed_keys = key_input
+                .get_pressed()
+                .filter(|_| !input_blocked.is_keyboard_blocked());
+            for key in pressed_keys {
                 if *key == controller.key_move_forward {
                     translation += forward;
                 }
@@ -129,10 +480,171 @@ pub(crate) fn fly_camera_controller_system(
                 }
             }
             translation = translation.normalize_or_zero();
+            // External input, e.g. from a 3D mouse
+            let external = input_state.translate;
+            translation +=
+                forward * -external.z - left * external.x + up * external.y;
             translation *= controller.speed
                 * controller.move_sensitivity
                 * time.delta_secs();
             transform.translation += translation;
         }
+        // Never write a poisoned transform, e.g. after a division by zero
+        let non_finite_field = [
+            ("speed", controller.speed.is_finite()),
+            ("translation", transform.translation.is_finite()),
+            ("rotation", transform.rotation.is_finite()),
+        ]
+        .into_iter()
+        .find(|(_, is_finite)| !is_finite);
+        if let Some((field, _)) = non_finite_field {
+            error!(
+                "Non finite {field} in the fly controller of camera {entity}, \
+                recovering from the last finite transform. Last input: rotate \
+                {}, translate {}, scroll {} lines {} pixels, window size {:?}",
+                input_state.rotate,
+                input_state.translate,
+                input_state.scroll_line,
+                input_state.scroll_pixel,
+                active_cam.window_size,
+            );
+            if !controller.speed.is_finite() {
+                controller.speed = FlyCameraController::default().speed;
+            }
+            transform = if pre_transform.is_finite() {
+                pre_transform
+            } else {
+                Transform::default()
+            };
+        }
+        if let Some((target_entity, arm)) = target_opt {
+            if transform != pre_transform {
+                control_targets.set_camera_transform(
+                    target_entity,
+                    &arm,
+                    &transform,
+                );
+            }
+            local_transform.set_if_neq(arm);
+        } else {
+            local_transform.set_if_neq(transform);
+        }
+        if transform != pre_transform {
+            if let Some(view) = ViewBookmark::from_camera(
+                &transform,
+                orbit_controller_opt,
+                Some(&controller),
+                projection,
+            ) {
+                changed_ev_write.send(CameraViewChanged::new(
+                    entity,
+                    ViewChangeCause::FlyMove,
+                    &view,
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{set_active_camera, test_app, SimulatedInput};
+
+    /// Mixed mouse motions, in logical pixels, that keep the pitch away
+    /// from the poles
+    const DELTAS: [Vec2; 6] = [
+        Vec2::new(37.0, 12.0),
+        Vec2::new(-120.0, 5.0),
+        Vec2::new(3.0, -41.0),
+        Vec2::new(250.0, 17.0),
+        Vec2::new(-8.0, 30.0),
+        Vec2::new(61.0, -23.0),
+    ];
+
+    fn spawn_camera(
+        app: &mut App,
+        rotation: Quat,
+        rotation_mode: FlyRotationMode,
+    ) -> Entity {
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_rotation(rotation),
+                FlyCameraController {
+                    rotation_mode,
+                    ..default()
+                },
+            ))
+            .id();
+        set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+        app.update();
+        camera
+    }
+
+    fn rotate(app: &mut App, delta: Vec2) {
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .rotate(delta);
+        app.update();
+    }
+
+    fn rotation(app: &App, camera: Entity) -> Quat {
+        app.world().get::<Transform>(camera).unwrap().rotation
+    }
+
+    fn roll(app: &App, camera: Entity) -> f32 {
+        rotation(app, camera).to_euler(EulerRot::YXZ).2
+    }
+
+    #[test]
+    fn upright_never_adds_roll_and_keeps_an_external_one() {
+        let mut app = test_app();
+        let camera =
+            spawn_camera(&mut app, Quat::IDENTITY, FlyRotationMode::Upright);
+        for _ in 0..20 {
+            for delta in DELTAS {
+                rotate(&mut app, delta);
+                assert!(roll(&app, camera).abs() < 1e-4);
+            }
+        }
+        // Roll set by another system
+        let (yaw, pitch, _) = rotation(&app, camera).to_euler(EulerRot::YXZ);
+        app.world_mut()
+            .get_mut::<Transform>(camera)
+            .unwrap()
+            .rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.3);
+        for _ in 0..20 {
+            for delta in DELTAS {
+                rotate(&mut app, delta);
+                assert!((roll(&app, camera) - 0.3).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn free_look_round_trips_without_drift() {
+        let mut app = test_app();
+        let start = Quat::from_euler(EulerRot::YXZ, 0.4, -0.2, 0.1);
+        let camera = spawn_camera(&mut app, start, FlyRotationMode::FreeLook);
+        // Each motion along a single axis, so that the opposite one undoes it
+        let deltas = DELTAS
+            .iter()
+            .flat_map(|delta| {
+                [Vec2::new(delta.x, 0.0), Vec2::new(0.0, delta.y)]
+            })
+            .collect::<Vec<_>>();
+        for _ in 0..20 {
+            for &delta in &deltas {
+                rotate(&mut app, delta);
+            }
+            assert!(rotation(&app, camera).dot(start).abs() < 0.99);
+            for &delta in deltas.iter().rev() {
+                rotate(&mut app, -delta);
+            }
+        }
+        assert!(rotation(&app, camera).dot(start).abs() > 1.0 - 1e-5);
     }
 }
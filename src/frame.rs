@@ -1,9 +1,24 @@
-use bevy::{prelude::*, render::primitives::Aabb};
+use std::time::Duration;
 
-use crate::{fly::FlyCameraController, orbit::OrbitCameraController, utils};
+use bevy::{
+    prelude::*, render::primitives::Aabb, utils::HashMap, window::PrimaryWindow,
+};
+
+use crate::{
+    bookmarks::ViewBookmark,
+    fly::FlyCameraController,
+    get_window_if_cursor_in_camera_viewport, math,
+    orbit::{OrbitCameraController, OrthoPlacement},
+    raycast::{
+        get_cursor_ray, get_nearest_included_intersection, RaycastFilter,
+    },
+    transition::{OrbitState, ViewTransition},
+    utils, ActiveCameraData, BlendyCamerasDisabled, CameraViewChanged,
+    OtherProjection, ViewChangeCause,
+};
 
 /// Event to move the camera to frame certain entities
-#[derive(Event)]
+#[derive(Event, Reflect)]
 pub struct FrameEvent {
     /// Camera to be used for framing
     pub camera_entity: Entity,
@@ -11,93 +26,382 @@ pub struct FrameEvent {
     pub entities_to_be_framed: Vec<Entity>,
     /// Also frame children of entities
     pub include_children: bool,
+    /// Use the mesh vertices instead of the [`Aabb`] to compute tighter
+    /// bounds. This is slower and only applies to entities with a [`Mesh3d`]
+    pub precise: bool,
+    /// Ignore the bounds of hidden entities (see [`InheritedVisibility`]).
+    /// The children of a hidden entity are still taken into account if they
+    /// are visible themselves. Use [`IgnoreWhenFraming`] to exclude a whole
+    /// hierarchy.
+    pub ignore_hidden: bool,
+    /// How the camera is moved to frame the entities
+    pub mode: FrameMode,
+    /// Duration of the animated transition to the new framing. The camera
+    /// is moved immediately if `None`
+    pub duration: Option<Duration>,
+}
+
+/// Event sent after handling a [`FrameEvent`], for each framed camera
+#[derive(Event, Debug, Clone, PartialEq, Reflect)]
+pub struct FramingCompleted {
+    /// The framed camera
+    pub camera_entity: Entity,
+    /// The world space bounds of the framed entities, or why the camera
+    /// could not be framed
+    pub result: Result<FramedBounds, FramingFailed>,
+}
+
+/// World space bounds of the entities framed by a [`FrameEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct FramedBounds {
+    /// Center of the axis aligned bounding box
+    pub center: Vec3,
+    /// Half size of the axis aligned bounding box
+    pub half_extents: Vec3,
+    /// Radius of the bounding sphere
+    pub radius: f32,
+}
+
+/// Reason why a [`FrameEvent`] could not be applied to a camera
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum FramingFailed {
+    /// The camera entity does not exist or has no controller
+    CameraNotFound,
+    /// None of the entities to frame (and children) have bounds
+    NoBounds,
+    /// The camera has no enabled controller
+    NoEnabledController,
+    /// There is no geometry under the cursor, see [`FrameUnderCursorEvent`]
+    NothingUnderCursor,
+}
+
+/// Marker component to exclude an entity and all its descendants from the
+/// bounds computed when framing entities, regardless of their visibility
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[reflect(Component, Default)]
+pub struct IgnoreWhenFraming;
+
+/// How the camera is moved when framing entities
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum FrameMode {
+    /// Keep the view direction, move the camera so that the entities fill
+    /// the view
+    #[default]
+    KeepDirection,
+    /// Keep the view direction and the distance to the focus, only center
+    /// the entities in the view
+    CenterOnly,
+    /// Keep the camera position, only rotate the camera to look at the
+    /// center of the entities
+    LookAtFromCurrentPosition,
+}
+
+/// Settings used when framing entities with a [`FrameEvent`]
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct FrameSettings {
+    /// Factor applied to the size of the framed entities bounds, so that they
+    /// do not touch the viewport edges. `1.0` fits the bounds exactly
+    pub margin: f32,
+}
+
+impl Default for FrameSettings {
+    fn default() -> Self {
+        Self { margin: 1.05 }
+    }
+}
+
+/// Optional resource caching the bounds computed when handling the
+/// [`FrameEvent`]s, so that framing the same entities again, e.g. pressing
+/// "Frame All" repeatedly, is nearly free on large scenes. The whole cache is
+/// cleared as soon as the transform, bounds, visibility, children or mesh of
+/// any entity changes. Not inserted by the plugin, enable it with
+/// `app.init_resource::<SceneBounds>()`.
+#[derive(Resource, Debug, Default)]
+pub struct SceneBounds {
+    bounds: HashMap<SceneBoundsKey, (Vec3, Vec3)>,
+}
+
+impl SceneBounds {
+    /// Whether some bounds are cached
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_empty()
+    }
+
+    /// Remove all the cached bounds
+    pub fn clear(&mut self) {
+        self.bounds.clear();
+    }
+}
+
+/// The parameters of a [`FrameEvent`] the bounds depend on
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct SceneBoundsKey {
+    entities: Vec<Entity>,
+    include_children: bool,
+    precise: bool,
+    ignore_hidden: bool,
+}
+
+/// Distance from the camera to the center of a bounding sphere of `radius`
+/// so that the sphere fits into the view, depending on the field of view and
+/// aspect ratio. For orthographic projections, the returned value is the
+/// projection scale.
+fn framing_distance(projection: &Projection, radius: f32, margin: f32) -> f32 {
+    let radius = radius * margin;
+    let distance = match projection {
+        Projection::Perspective(p) => {
+            let half_fov_y = p.fov * 0.5;
+            let half_fov_x = (half_fov_y.tan() * p.aspect_ratio).atan();
+            // The sphere is tangent to the narrowest side of the frustum
+            radius / half_fov_y.min(half_fov_x).sin()
+        }
+        Projection::Orthographic(p) => {
+            // Size of the view for a scale of 1.0. The area is not computed
+            // before the first frame, assume a fixed vertical size of 1.0 then.
+            let unit_size =
+                if p.scale > 0.0 && p.area.size().min_element() > 0.0 {
+                    p.area.size() / p.scale
+                } else {
+                    Vec2::ONE
+                };
+            2.0 * radius / unit_size.min_element()
+        }
+    };
+    distance.max(0.05)
+}
+
+/// World space bounds of an entity local [`Aabb`]. All the eight corners of
+/// the box are taken into account, so that the bounds of rotated entities are
+/// not underestimated.
+fn aabb_world_bounds(aabb: &Aabb, transform: &GlobalTransform) -> (Vec3, Vec3) {
+    let affine = transform.affine();
+    let center = affine.transform_point3a(aabb.center);
+    let half_extents = affine.matrix3.abs() * aabb.half_extents;
+    (
+        Vec3::from(center - half_extents),
+        Vec3::from(center + half_extents),
+    )
+}
+
+/// World space bounds of the vertices of a mesh. Return `None` if the mesh
+/// has no positions.
+fn mesh_world_bounds(
+    mesh: &Mesh,
+    transform: &GlobalTransform,
+) -> Option<(Vec3, Vec3)> {
+    let positions = mesh.attribute(Mesh::ATTRIBUTE_POSITION)?.as_float3()?;
+    if positions.is_empty() {
+        return None;
+    }
+    Some(positions.iter().fold(
+        (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+        |(min, max), &position| {
+            let position = transform.transform_point(Vec3::from(position));
+            (min.min(position), max.max(position))
+        },
+    ))
 }
 
 /// Return (min, max). If min > max there was no valid bounds to return.
+///
+/// The hierarchy is walked with an explicit stack rather than recursively, so
+/// that deep hierarchies cannot overflow the stack, and the subtrees that are
+/// ignored are skipped as a whole.
 #[allow(clippy::type_complexity)]
 fn get_entities_aabb(
     entities: &[Entity],
     include_children: bool,
+    precise: bool,
+    ignore_hidden: bool,
     entities_query: &Query<
-        (&GlobalTransform, Option<&Aabb>, Option<&Children>),
+        (
+            &GlobalTransform,
+            Option<&Aabb>,
+            Option<&Mesh3d>,
+            Option<&Children>,
+            Option<&InheritedVisibility>,
+            Has<IgnoreWhenFraming>,
+        ),
         (Without<OrbitCameraController>, Without<FlyCameraController>),
     >,
+    meshes: &Assets<Mesh>,
 ) -> (Vec3, Vec3) {
-    let combine_bounds =
-        |(a_min, a_max): (Vec3, Vec3), (b_min, b_max): (Vec3, Vec3)| {
-            (a_min.min(b_min), a_max.max(b_max))
+    let mut bounds_min = Vec3::splat(f32::INFINITY);
+    let mut bounds_max = Vec3::splat(f32::NEG_INFINITY);
+    let mut stack = entities.to_vec();
+    while let Some(entity) = stack.pop() {
+        let Ok((tf, bounds, mesh, children, visibility, ignored)) =
+            entities_query.get(entity)
+        else {
+            continue;
         };
-    let default_bounds =
-        (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY));
-    entities
-        .iter()
-        .filter_map(|&entity| {
-            entities_query
-                .get(entity)
-                .map(|(&tf, bounds, children)| {
-                    let mut entity_bounds =
-                        bounds.map_or(default_bounds, |bounds| {
-                            (
-                                tf * Vec3::from(bounds.min()),
-                                tf * Vec3::from(bounds.max()),
-                            )
-                        });
-                    if include_children {
-                        if let Some(children) = children {
-                            let children_bounds = get_entities_aabb(
-                                children,
-                                include_children,
-                                entities_query,
-                            );
-                            entity_bounds =
-                                combine_bounds(entity_bounds, children_bounds);
-                        }
-                    }
-                    entity_bounds
-                })
-                .ok()
-        })
-        .fold(default_bounds, combine_bounds)
+        if ignored {
+            continue;
+        }
+        // Children set to `Visibility::Visible` are visible even if their
+        // parent is hidden, so only the entity itself is skipped
+        let is_visible = !ignore_hidden
+            || visibility.is_none_or(|visibility| visibility.get());
+        // Do not read the vertices of the hidden meshes
+        let entity_bounds = if is_visible {
+            mesh.filter(|_| precise)
+                .and_then(|mesh| meshes.get(&mesh.0))
+                .and_then(|mesh| mesh_world_bounds(mesh, tf))
+                .or_else(|| bounds.map(|bounds| aabb_world_bounds(bounds, tf)))
+        } else {
+            None
+        };
+        if let Some((entity_min, entity_max)) = entity_bounds {
+            bounds_min = bounds_min.min(entity_min);
+            bounds_max = bounds_max.max(entity_max);
+        }
+        if include_children {
+            if let Some(children) = children {
+                stack.extend(children.iter().copied());
+            }
+        }
+    }
+    (bounds_min, bounds_max)
 }
 
+/// Clear the [`SceneBounds`] when the bounds of any entity may have changed
 #[allow(clippy::type_complexity)]
+pub(crate) fn invalidate_scene_bounds_system(
+    mut scene_bounds: ResMut<SceneBounds>,
+    changed_query: Query<
+        (),
+        (
+            Or<(
+                Changed<GlobalTransform>,
+                Changed<Aabb>,
+                Changed<Mesh3d>,
+                Changed<Children>,
+                Changed<InheritedVisibility>,
+                Changed<IgnoreWhenFraming>,
+            )>,
+            Without<OrbitCameraController>,
+            Without<FlyCameraController>,
+        ),
+    >,
+    mut removed_transforms: RemovedComponents<GlobalTransform>,
+    mut removed_aabbs: RemovedComponents<Aabb>,
+    mut removed_ignored: RemovedComponents<IgnoreWhenFraming>,
+    mut mesh_events: EventReader<AssetEvent<Mesh>>,
+) {
+    // Always consume the events, so that they cannot clear bounds cached later
+    let removed_count = removed_transforms.read().count()
+        + removed_aabbs.read().count()
+        + removed_ignored.read().count()
+        + mesh_events.read().count();
+    // Only go through the entities if there is something to clear
+    if !scene_bounds.is_empty()
+        && (removed_count > 0 || !changed_query.is_empty())
+    {
+        scene_bounds.clear();
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub(crate) fn frame_system(
+    mut commands: Commands,
     mut ev_read: EventReader<FrameEvent>,
+    mut ev_write: EventWriter<FramingCompleted>,
+    settings: Res<FrameSettings>,
     // active_cam: Res<ActiveCameraData>,
     mut cameras_query: Query<
         (
-            // Entity,
+            Entity,
             &mut Transform,
             Option<&mut OrbitCameraController>,
             Option<&mut FlyCameraController>,
             &mut Projection,
+            Option<&mut OtherProjection>,
+        ),
+        (
+            Or<(With<OrbitCameraController>, With<FlyCameraController>)>,
+            Without<BlendyCamerasDisabled>,
         ),
-        Or<(With<OrbitCameraController>, With<FlyCameraController>)>,
     >,
+    mut changed_ev_write: EventWriter<CameraViewChanged>,
     entities_query: Query<
-        (&GlobalTransform, Option<&Aabb>, Option<&Children>),
+        (
+            &GlobalTransform,
+            Option<&Aabb>,
+            Option<&Mesh3d>,
+            Option<&Children>,
+            Option<&InheritedVisibility>,
+            Has<IgnoreWhenFraming>,
+        ),
         (Without<OrbitCameraController>, Without<FlyCameraController>),
     >,
+    meshes: Res<Assets<Mesh>>,
+    mut scene_bounds_opt: Option<ResMut<SceneBounds>>,
 ) {
     for FrameEvent {
         camera_entity,
         entities_to_be_framed,
         include_children,
+        precise,
+        ignore_hidden,
+        mode,
+        duration,
     } in ev_read.read()
     {
         if let Ok((
-            // entity,
+            entity,
             mut transform,
-            orbit_controller_opt,
+            mut orbit_controller_opt,
             fly_controller_opt,
             mut projection,
+            mut other_projection_opt,
         )) = cameras_query.get_mut(*camera_entity)
         {
-            let (bounds_min, bounds_max) = get_entities_aabb(
-                entities_to_be_framed,
-                *include_children,
-                &entities_query,
+            let pre_view = ViewBookmark::from_camera(
+                &transform,
+                orbit_controller_opt.as_deref(),
+                fly_controller_opt.as_deref(),
+                &projection,
             );
+            let auto_clip_planes = orbit_controller_opt
+                .as_ref()
+                .is_some_and(|controller| controller.auto_clip_planes);
+            let key = SceneBoundsKey {
+                entities: entities_to_be_framed.clone(),
+                include_children: *include_children,
+                precise: *precise,
+                ignore_hidden: *ignore_hidden,
+            };
+            let cached_bounds = scene_bounds_opt
+                .as_ref()
+                .and_then(|scene_bounds| scene_bounds.bounds.get(&key))
+                .copied();
+            let (bounds_min, bounds_max) = cached_bounds.unwrap_or_else(|| {
+                get_entities_aabb(
+                    entities_to_be_framed,
+                    *include_children,
+                    *precise,
+                    *ignore_hidden,
+                    &entities_query,
+                    &meshes,
+                )
+            });
+            if cached_bounds.is_none() {
+                if let Some(ref mut scene_bounds) = scene_bounds_opt {
+                    scene_bounds.bounds.insert(key, (bounds_min, bounds_max));
+                }
+            }
             let aabb_diag = bounds_max - bounds_min;
             let aabb_diag = if aabb_diag.max_element() > 0.0 {
                 aabb_diag
@@ -106,51 +410,925 @@ pub(crate) fn frame_system(
                     "Could not focus because entities (and children) do not \
                      have any AABB"
                 );
+                ev_write.send(FramingCompleted {
+                    camera_entity: entity,
+                    result: Err(FramingFailed::NoBounds),
+                });
                 continue;
             };
             let aabb_center = bounds_min + aabb_diag * 0.5;
-            let aabb_radius = aabb_diag.length();
-            // TODO: Calculate distance acording to view angle (if projection is
-            // perspective). Also (in perspective) center on the projection of
-            // the object. For the moment we center on the AABB center but the
-            // object is not centered in the view if viewed diagonaly.
-            // For the moment just multiply distance to center to make sure all
-            // the object is into view.
-            let distance_camera_to_aabb_center = 1.3 * aabb_radius;
+            let aabb_radius = aabb_diag.length() * 0.5;
+            // NOTE: For orthographic projections this is the projection scale
             let distance_camera_to_aabb_center =
-                distance_camera_to_aabb_center.max(0.05);
+                framing_distance(&projection, aabb_radius, settings.margin);
+            let mut is_framed = false;
 
-            if let Some(mut controller) = orbit_controller_opt {
+            if let Some(ref mut controller) = orbit_controller_opt {
                 // NOTE: Checking if viewport is active does not work if
                 // no manual manipulation of the camera is done a priory.
 
                 // if controller.is_enabled && active_cam.entity == Some(entity) {
                 if controller.is_enabled {
-                    controller.focus = aabb_center;
-                    controller.radius = Some(distance_camera_to_aabb_center);
                     controller.initialize_if_necessary(
                         &mut transform,
                         &mut projection,
                     );
-                    utils::update_orbit_transform(
-                        controller.yaw.unwrap(),
-                        controller.pitch.unwrap(),
-                        controller.radius.unwrap(),
-                        controller.focus,
-                        &mut transform,
-                        &mut projection,
-                    );
+                    if let Some(start) = OrbitState::from_controller(controller)
+                    {
+                        is_framed = true;
+                        let end = match mode {
+                            FrameMode::KeepDirection => OrbitState {
+                                focus: aabb_center,
+                                radius: distance_camera_to_aabb_center,
+                                ..start
+                            },
+                            FrameMode::CenterOnly => OrbitState {
+                                focus: aabb_center,
+                                ..start
+                            },
+                            FrameMode::LookAtFromCurrentPosition => {
+                                // In orthographic projection the camera
+                                // translation does not depend on the radius,
+                                // use the equivalent perspective position
+                                let eye = math::camera_transform_from_orbit(
+                                    start.yaw,
+                                    start.pitch,
+                                    start.radius,
+                                    start.focus,
+                                )
+                                .translation;
+                                let (yaw, pitch, radius) =
+                                    math::yaw_pitch_radius_from_translation(
+                                        eye,
+                                        aabb_center,
+                                    );
+                                let radius = match *projection {
+                                    Projection::Perspective(_) => radius,
+                                    Projection::Orthographic(_) => start.radius,
+                                };
+                                OrbitState {
+                                    focus: aabb_center,
+                                    yaw,
+                                    pitch,
+                                    radius,
+                                }
+                            }
+                        };
+                        // Do not clip the framed entities in orthographic
+                        // views, the planes only depend on the scale
+                        // otherwise
+                        let placement = match controller.ortho_placement {
+                            OrthoPlacement::AtRadius => {
+                                OrthoPlacement::Fixed(end.radius)
+                            }
+                            placement => placement,
+                        };
+                        utils::fit_orthographic_clip_planes(
+                            aabb_radius,
+                            placement,
+                            &mut projection,
+                        );
+                        if let Some(ref mut other_projection) =
+                            other_projection_opt
+                        {
+                            utils::fit_orthographic_clip_planes(
+                                aabb_radius,
+                                placement,
+                                &mut other_projection.0,
+                            );
+                        }
+                        if let Some(duration) = duration {
+                            commands.entity(entity).insert(
+                                ViewTransition::orbit(start, end, *duration),
+                            );
+                        } else {
+                            commands.entity(entity).remove::<ViewTransition>();
+                            end.apply(controller);
+                            math::update_orbit_transform(
+                                end.yaw,
+                                end.pitch,
+                                end.radius,
+                                end.focus,
+                                &mut transform,
+                                &mut projection,
+                                controller.ortho_placement,
+                            );
+                        }
+                    }
                 }
             }
-            if let Some(controller) = fly_controller_opt {
+            if let Some(ref controller) = fly_controller_opt {
                 // if controller.is_enabled && active_cam.entity == Some(entity) {
                 if controller.is_enabled {
-                    transform.translation = aabb_center
-                        + (transform.back() * distance_camera_to_aabb_center);
+                    is_framed = true;
+                    let end = match mode {
+                        FrameMode::KeepDirection => Transform {
+                            translation: aabb_center
+                                + (transform.back()
+                                    * distance_camera_to_aabb_center),
+                            ..*transform
+                        },
+                        FrameMode::CenterOnly => {
+                            let distance =
+                                transform.translation.distance(aabb_center);
+                            Transform {
+                                translation: aabb_center
+                                    + (transform.back() * distance),
+                                ..*transform
+                            }
+                        }
+                        FrameMode::LookAtFromCurrentPosition => {
+                            if transform.translation == aabb_center {
+                                *transform
+                            } else {
+                                transform.looking_at(aabb_center, Vec3::Y)
+                            }
+                        }
+                    };
+                    if let Some(duration) = duration {
+                        commands.entity(entity).insert(ViewTransition::fly(
+                            *transform, end, *duration,
+                        ));
+                    } else {
+                        commands.entity(entity).remove::<ViewTransition>();
+                        *transform = end;
+                    }
+                    utils::fit_orthographic_clip_planes(
+                        aabb_radius,
+                        OrthoPlacement::Fixed(
+                            end.translation.distance(aabb_center),
+                        ),
+                        &mut projection,
+                    );
+                    // The orbit controller handles this itself when enabled
+                    if auto_clip_planes {
+                        let distance = end.translation.distance(aabb_center);
+                        utils::update_clip_planes(distance, &mut projection);
+                        if let Some(mut other_projection) = other_projection_opt
+                        {
+                            utils::update_clip_planes(
+                                distance,
+                                &mut other_projection.0,
+                            );
+                        }
+                    }
                 }
             }
+            let view = ViewBookmark::from_camera(
+                &transform,
+                orbit_controller_opt.as_deref(),
+                fly_controller_opt.as_deref(),
+                &projection,
+            );
+            if let Some(event) = CameraViewChanged::between(
+                entity,
+                ViewChangeCause::FrameEvent,
+                pre_view,
+                view,
+            ) {
+                changed_ev_write.send(event);
+            }
+            ev_write.send(FramingCompleted {
+                camera_entity: entity,
+                result: if is_framed {
+                    Ok(FramedBounds {
+                        center: aabb_center,
+                        half_extents: aabb_diag * 0.5,
+                        radius: aabb_radius,
+                    })
+                } else {
+                    Err(FramingFailed::NoEnabledController)
+                },
+            });
         } else {
             warn!("Camera not found while trying to frame view");
+            ev_write.send(FramingCompleted {
+                camera_entity: *camera_entity,
+                result: Err(FramingFailed::CameraNotFound),
+            });
         }
     }
 }
+
+/// Event to frame the same entities in several cameras at once, e.g. "Frame
+/// Selected in All Views" in a quad view layout. Each camera is framed like
+/// with a [`FrameEvent`], computing the distance, or the scale of an
+/// orthographic projection, from its own projection.
+#[derive(Event, Reflect)]
+pub struct FrameAllCamerasEvent {
+    /// Only frame the camera of the [`ActiveCameraData`] instead of all the
+    /// cameras with an enabled controller
+    pub only_active: bool,
+    /// Entities to frame
+    pub entities_to_be_framed: Vec<Entity>,
+    /// Also frame children of entities
+    pub include_children: bool,
+    /// Use the mesh vertices instead of the [`Aabb`] to compute tighter
+    /// bounds, see [`FrameEvent::precise`]
+    pub precise: bool,
+    /// Ignore the bounds of hidden entities, see [`FrameEvent::ignore_hidden`]
+    pub ignore_hidden: bool,
+    /// How the cameras are moved to frame the entities
+    pub mode: FrameMode,
+    /// Duration of the animated transition to the new framing. The cameras
+    /// are moved immediately if `None`
+    pub duration: Option<Duration>,
+}
+
+/// Convert [`FrameAllCamerasEvent`] into [`FrameEvent`]
+#[allow(clippy::type_complexity)]
+pub(crate) fn frame_all_cameras_system(
+    mut ev_read: EventReader<FrameAllCamerasEvent>,
+    mut frame_ev_write: EventWriter<FrameEvent>,
+    active_cam: Res<ActiveCameraData>,
+    cameras_query: Query<
+        (
+            Entity,
+            Option<&OrbitCameraController>,
+            Option<&FlyCameraController>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+) {
+    for event in ev_read.read() {
+        let camera_entities = if event.only_active {
+            active_cam.entity.into_iter().collect::<Vec<_>>()
+        } else {
+            cameras_query
+                .iter()
+                .filter(|(_, orbit_controller_opt, fly_controller_opt)| {
+                    orbit_controller_opt
+                        .is_some_and(|controller| controller.is_enabled)
+                        || fly_controller_opt
+                            .is_some_and(|controller| controller.is_enabled)
+                })
+                .map(|(entity, _, _)| entity)
+                .collect()
+        };
+        if camera_entities.is_empty() {
+            warn!("No camera to frame while trying to frame all cameras");
+        }
+        for camera_entity in camera_entities {
+            frame_ev_write.send(FrameEvent {
+                camera_entity,
+                entities_to_be_framed: event.entities_to_be_framed.clone(),
+                include_children: event.include_children,
+                precise: event.precise,
+                ignore_hidden: event.ignore_hidden,
+                mode: event.mode,
+                duration: event.duration,
+            });
+        }
+    }
+}
+
+/// Event to zoom the camera on a region of its viewport, like Blender's
+/// "Zoom to Border"
+#[derive(Event, Reflect)]
+pub struct FrameRegionEvent {
+    /// Camera to be used for framing
+    pub camera_entity: Entity,
+    /// Region to frame, in logical viewport coordinates. A region without
+    /// area (e.g. a click) zooms by a fixed factor centered on that point.
+    pub viewport_rect: Rect,
+}
+
+/// Zoom factor applied when the region to frame has no area
+const REGION_CLICK_ZOOM_FACTOR: f32 = 0.5;
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn frame_region_system(
+    mut commands: Commands,
+    mut ev_read: EventReader<FrameRegionEvent>,
+    mut cameras_query: Query<
+        (
+            &Camera,
+            &GlobalTransform,
+            &mut Transform,
+            Option<&mut OrbitCameraController>,
+            Option<&FlyCameraController>,
+            &mut Projection,
+        ),
+        (
+            Or<(With<OrbitCameraController>, With<FlyCameraController>)>,
+            Without<BlendyCamerasDisabled>,
+        ),
+    >,
+    mut ray_cast: MeshRayCast,
+    raycast_filter: RaycastFilter,
+) {
+    for FrameRegionEvent {
+        camera_entity,
+        viewport_rect,
+    } in ev_read.read()
+    {
+        let Ok((
+            camera,
+            global_transform,
+            mut transform,
+            orbit_controller_opt,
+            fly_controller_opt,
+            mut projection,
+        )) = cameras_query.get_mut(*camera_entity)
+        else {
+            warn!("Camera not found while trying to frame region");
+            continue;
+        };
+        let Some(viewport_size) =
+            utils::non_empty_size(camera.logical_viewport_size())
+        else {
+            continue;
+        };
+        let Ok(ray) =
+            camera.viewport_to_world(global_transform, viewport_rect.center())
+        else {
+            continue;
+        };
+        let zoom_factor =
+            if viewport_rect.width() < 1.0 || viewport_rect.height() < 1.0 {
+                REGION_CLICK_ZOOM_FACTOR
+            } else {
+                (viewport_rect.size() / viewport_size).max_element()
+            };
+        let forward = transform.forward();
+        // Use the geometry under the region center if any, otherwise the
+        // depth of the current focus
+        let target = get_nearest_included_intersection(
+            &mut ray_cast,
+            ray,
+            &raycast_filter,
+        )
+        .map(|(_entity, hit)| hit.point)
+        .or_else(|| {
+            let focus = orbit_controller_opt.as_ref()?.focus;
+            let factor = ray.direction.dot(*forward);
+            (factor > f32::EPSILON).then(|| {
+                ray.origin
+                    + ray.direction
+                        * ((focus - ray.origin).dot(*forward) / factor)
+            })
+        });
+        let Some(target) = target else {
+            warn!("Could not find the depth of the region to frame");
+            continue;
+        };
+        let depth = (target - transform.translation).dot(*forward);
+        commands.entity(*camera_entity).remove::<ViewTransition>();
+        if let Some(mut controller) = orbit_controller_opt {
+            if controller.is_enabled {
+                controller
+                    .initialize_if_necessary(&mut transform, &mut projection);
+                if let Some(start) = OrbitState::from_controller(&controller) {
+                    let radius = match *projection {
+                        Projection::Perspective(_) => depth * zoom_factor,
+                        Projection::Orthographic(_) => {
+                            start.radius * zoom_factor
+                        }
+                    };
+                    let end = OrbitState {
+                        focus: target,
+                        radius: radius.max(controller.zoom_lower_limit),
+                        ..start
+                    };
+                    end.apply(&mut controller);
+                    math::update_orbit_transform(
+                        end.yaw,
+                        end.pitch,
+                        end.radius,
+                        end.focus,
+                        &mut transform,
+                        &mut projection,
+                        controller.ortho_placement,
+                    );
+                }
+            }
+        }
+        if let Some(controller) = fly_controller_opt {
+            if controller.is_enabled {
+                transform.translation = target
+                    + (transform.back() * (depth * zoom_factor).max(0.05));
+            }
+        }
+    }
+}
+
+/// Marker component for the root of a hierarchy that should be framed as a
+/// whole by a [`FrameUnderCursorEvent`] using [`FrameUnderCursorTarget::Root`]
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[reflect(Component, Default)]
+pub struct FramingRoot;
+
+/// Which entity to frame, starting from the entity under the cursor
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum FrameUnderCursorTarget {
+    /// Frame the entity under the cursor
+    HitEntity,
+    /// Frame the first ancestor of the entity under the cursor (including
+    /// itself) with a [`Name`]
+    #[default]
+    Named,
+    /// Frame the first ancestor of the entity under the cursor (including
+    /// itself) with a [`FramingRoot`] component
+    Root,
+}
+
+/// Event to frame the geometry under the mouse cursor, in the viewport of
+/// the camera. If there is nothing under the cursor, a failed
+/// [`FramingCompleted`] event is sent.
+#[derive(Event, Reflect)]
+pub struct FrameUnderCursorEvent {
+    /// Camera to be used for framing
+    pub camera_entity: Entity,
+    /// Which entity to frame, with all its children. If no matching ancestor
+    /// is found, the entity under the cursor is framed.
+    pub target: FrameUnderCursorTarget,
+    /// Duration of the animated transition to the new framing. The camera
+    /// is moved immediately if `None`
+    pub duration: Option<Duration>,
+}
+
+/// Convert [`FrameUnderCursorEvent`] into [`FrameEvent`]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn frame_under_cursor_system(
+    mut ev_read: EventReader<FrameUnderCursorEvent>,
+    mut frame_ev_write: EventWriter<FrameEvent>,
+    mut result_ev_write: EventWriter<FramingCompleted>,
+    cameras_query: Query<
+        (&Camera, &GlobalTransform),
+        Without<BlendyCamerasDisabled>,
+    >,
+    ancestors_query: Query<(Option<&Parent>, Has<Name>, Has<FramingRoot>)>,
+    primary_window: Query<(Entity, &Window), With<PrimaryWindow>>,
+    other_windows: Query<(Entity, &Window), Without<PrimaryWindow>>,
+    mut ray_cast: MeshRayCast,
+    raycast_filter: RaycastFilter,
+) {
+    for FrameUnderCursorEvent {
+        camera_entity,
+        target,
+        duration,
+    } in ev_read.read()
+    {
+        let Ok((camera, global_transform)) = cameras_query.get(*camera_entity)
+        else {
+            warn!("Camera not found while trying to frame under cursor");
+            result_ev_write.send(FramingCompleted {
+                camera_entity: *camera_entity,
+                result: Err(FramingFailed::CameraNotFound),
+            });
+            continue;
+        };
+        // Only use the cursor if it is in the viewport of this camera
+        let hit_entity = get_window_if_cursor_in_camera_viewport(
+            camera,
+            None,
+            &primary_window,
+            &other_windows,
+        )
+        .and_then(|(_window_entity, window)| {
+            get_cursor_ray(camera, global_transform, window)
+        })
+        .and_then(|ray| {
+            get_nearest_included_intersection(
+                &mut ray_cast,
+                ray,
+                &raycast_filter,
+            )
+        })
+        .map(|(entity, _hit)| *entity);
+        let Some(hit_entity) = hit_entity else {
+            result_ev_write.send(FramingCompleted {
+                camera_entity: *camera_entity,
+                result: Err(FramingFailed::NothingUnderCursor),
+            });
+            continue;
+        };
+        let mut entity = hit_entity;
+        let entity_to_frame = loop {
+            let Ok((parent, has_name, is_root)) = ancestors_query.get(entity)
+            else {
+                break hit_entity;
+            };
+            let is_target = match target {
+                FrameUnderCursorTarget::HitEntity => true,
+                FrameUnderCursorTarget::Named => has_name,
+                FrameUnderCursorTarget::Root => is_root,
+            };
+            if is_target {
+                break entity;
+            }
+            match parent {
+                Some(parent) => entity = parent.get(),
+                None => break hit_entity,
+            }
+        };
+        frame_ev_write.send(FrameEvent {
+            camera_entity: *camera_entity,
+            entities_to_be_framed: vec![entity_to_frame],
+            include_children: true,
+            precise: false,
+            ignore_hidden: true,
+            mode: FrameMode::KeepDirection,
+            duration: *duration,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{set_active_camera, test_app},
+        SwitchProjection,
+    };
+
+    fn spawn_camera(app: &mut App) -> Entity {
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 0.0, 10.0),
+                OrbitCameraController::default(),
+            ))
+            .id();
+        set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+        app.update();
+        camera
+    }
+
+    fn spawn_box(app: &mut App, translation: Vec3) -> Entity {
+        app.world_mut()
+            .spawn((
+                Transform::from_translation(translation),
+                Aabb::from_min_max(Vec3::splat(-0.5), Vec3::splat(0.5)),
+            ))
+            .id()
+    }
+
+    /// Frame `entities` and their children, and return the framing result
+    fn frame(
+        app: &mut App,
+        camera: Entity,
+        entities: &[Entity],
+    ) -> Result<FramedBounds, FramingFailed> {
+        app.world_mut().send_event(FrameEvent {
+            camera_entity: camera,
+            entities_to_be_framed: entities.to_vec(),
+            include_children: true,
+            precise: false,
+            ignore_hidden: true,
+            mode: FrameMode::KeepDirection,
+            duration: None,
+        });
+        app.update();
+        let events = app.world().resource::<Events<FramingCompleted>>();
+        let completed = events.iter_current_update_events().last().unwrap();
+        assert_eq!(completed.camera_entity, camera);
+        completed.result
+    }
+
+    #[test]
+    fn scene_bounds_are_cached_until_an_entity_moves() {
+        let mut app = test_app();
+        app.init_resource::<SceneBounds>();
+        let camera = spawn_camera(&mut app);
+        let entity = spawn_box(&mut app, Vec3::ZERO);
+        app.update();
+        let bounds = frame(&mut app, camera, &[entity]).unwrap();
+        assert_eq!(bounds.center, Vec3::ZERO);
+        assert_eq!(frame(&mut app, camera, &[entity]), Ok(bounds));
+        assert_eq!(app.world().resource::<SceneBounds>().bounds.len(), 1);
+        app.world_mut()
+            .get_mut::<Transform>(entity)
+            .unwrap()
+            .translation = Vec3::X;
+        app.update();
+        let bounds = frame(&mut app, camera, &[entity]).unwrap();
+        assert_eq!(bounds.center, Vec3::X);
+    }
+
+    /// Panic if a corner of the world space box centered on `center` with
+    /// `half_size` is clipped by the orthographic `projection` of a camera
+    /// with `transform`
+    #[track_caller]
+    fn assert_box_within_clip_planes(
+        transform: &Transform,
+        projection: &Projection,
+        center: Vec3,
+        half_size: f32,
+    ) {
+        let Projection::Orthographic(p) = projection else {
+            panic!("the projection is not orthographic");
+        };
+        let forward = transform.forward();
+        for x in [-1.0, 1.0] {
+            for y in [-1.0, 1.0] {
+                for z in [-1.0, 1.0] {
+                    let corner = center + Vec3::new(x, y, z) * half_size;
+                    let depth = (corner - transform.translation).dot(*forward);
+                    assert!(
+                        depth >= p.near - 1e-2 && depth <= p.far + 1e-2,
+                        "corner {corner} at depth {depth} is outside of the \
+                        clip planes {} and {}",
+                        p.near,
+                        p.far,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn orthographic_framing_does_not_clip_a_large_scene() {
+        let placements = [
+            OrthoPlacement::ClipPlanesMiddle,
+            OrthoPlacement::AtRadius,
+            OrthoPlacement::Fixed(50.0),
+        ];
+        for placement in placements {
+            for starts_orthographic in [true, false] {
+                let mut app = test_app();
+                let projection = if starts_orthographic {
+                    Projection::Orthographic(
+                        OrthographicProjection::default_3d(),
+                    )
+                } else {
+                    Projection::default()
+                };
+                let camera = app
+                    .world_mut()
+                    .spawn((
+                        Camera::default(),
+                        projection,
+                        Transform::from_xyz(1.0, 2.0, 10.0)
+                            .looking_at(Vec3::ZERO, Vec3::Y),
+                        OrbitCameraController {
+                            ortho_placement: placement,
+                            ..default()
+                        },
+                    ))
+                    .id();
+                set_active_camera(
+                    app.world_mut(),
+                    camera,
+                    Vec2::new(800.0, 600.0),
+                );
+                app.update();
+                // 10000 units wide
+                let entity = app
+                    .world_mut()
+                    .spawn((
+                        Transform::from_xyz(100.0, -50.0, 20.0)
+                            .with_scale(Vec3::splat(10000.0)),
+                        Aabb::from_min_max(Vec3::splat(-0.5), Vec3::splat(0.5)),
+                    ))
+                    .id();
+                app.update();
+                let bounds = frame(&mut app, camera, &[entity]).unwrap();
+                let half_size = bounds.half_extents.x;
+                assert_eq!(half_size, 5000.0);
+                if !starts_orthographic {
+                    // The orthographic projection is fitted before switching
+                    let world = app.world();
+                    let controller =
+                        world.get::<OrbitCameraController>(camera).unwrap();
+                    let Projection::Orthographic(ref other) =
+                        *world.get::<OtherProjection>(camera).unwrap().get()
+                    else {
+                        panic!("the other projection is not orthographic");
+                    };
+                    let distance = match placement {
+                        OrthoPlacement::AtRadius => controller.radius.unwrap(),
+                        placement => placement.distance(other),
+                    };
+                    assert!(other.near <= distance - bounds.radius);
+                    assert!(other.far >= distance + bounds.radius);
+                    app.world_mut().send_event(SwitchProjection {
+                        camera_entity: camera,
+                    });
+                    app.update();
+                }
+                // One more update for the controller to settle
+                app.update();
+                let world = app.world();
+                assert_box_within_clip_planes(
+                    world.get::<Transform>(camera).unwrap(),
+                    world.get::<Projection>(camera).unwrap(),
+                    bounds.center,
+                    half_size,
+                );
+            }
+        }
+    }
+
+    /// World space corners of a unit box centered on the origin, with
+    /// `transform`
+    fn box_corners(transform: &Transform) -> Vec<Vec3> {
+        let mut corners = Vec::new();
+        for x in [-0.5, 0.5] {
+            for y in [-0.5, 0.5] {
+                for z in [-0.5, 0.5] {
+                    corners.push(transform.transform_point(Vec3::new(x, y, z)));
+                }
+            }
+        }
+        corners
+    }
+
+    /// Panic if one of the `points` is outside of the view of the camera
+    /// with `transform` and perspective `projection`
+    #[track_caller]
+    fn assert_points_in_view(
+        transform: &Transform,
+        projection: &Projection,
+        points: &[Vec3],
+    ) {
+        let Projection::Perspective(p) = projection else {
+            panic!("the projection is not perspective");
+        };
+        let half_height = (p.fov * 0.5).tan();
+        let half_width = half_height * p.aspect_ratio;
+        let view_from_world = transform.compute_matrix().inverse();
+        for point in points {
+            let local = view_from_world.transform_point3(*point);
+            let depth = -local.z;
+            assert!(depth > 0.0, "{point} is behind the camera");
+            assert!(
+                local.x.abs() <= depth * half_width + 1e-4,
+                "{point} is outside of the view horizontally",
+            );
+            assert!(
+                local.y.abs() <= depth * half_height + 1e-4,
+                "{point} is outside of the view vertically",
+            );
+        }
+    }
+
+    #[test]
+    fn rotated_box_bounds_enclose_all_its_corners() {
+        let mut app = test_app();
+        let camera = spawn_camera(&mut app);
+        let transform = Transform::from_xyz(1.0, 0.5, -2.0).with_rotation(
+            Quat::from_euler(
+                EulerRot::YXZ,
+                45f32.to_radians(),
+                45f32.to_radians(),
+                0.0,
+            ),
+        );
+        let entity = app
+            .world_mut()
+            .spawn((
+                transform,
+                Aabb::from_min_max(Vec3::splat(-0.5), Vec3::splat(0.5)),
+            ))
+            .id();
+        app.update();
+        let bounds = frame(&mut app, camera, &[entity]).unwrap();
+        let corners = box_corners(&transform);
+        let (min, max) = corners.iter().fold(
+            (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+            |(min, max), corner| (min.min(*corner), max.max(*corner)),
+        );
+        // The bounds are the tightest axis aligned box around the corners
+        assert!(bounds.center.abs_diff_eq((min + max) * 0.5, 1e-5));
+        assert!(bounds.half_extents.abs_diff_eq((max - min) * 0.5, 1e-5));
+        assert!(bounds.half_extents.max_element() > 0.5);
+        for corner in &corners {
+            assert!((*corner - bounds.center).length() <= bounds.radius + 1e-5);
+        }
+        let world = app.world();
+        assert_points_in_view(
+            world.get::<Transform>(camera).unwrap(),
+            world.get::<Projection>(camera).unwrap(),
+            &corners,
+        );
+    }
+
+    #[test]
+    fn long_box_fits_in_portrait_and_landscape_views() {
+        // 20 units long along the horizontal axis of the view
+        let transform = Transform::from_xyz(0.0, 1.0, 0.0)
+            .with_scale(Vec3::new(20.0, 0.5, 0.5));
+        let mut radii = Vec::new();
+        for aspect_ratio in [0.5, 2.0] {
+            let mut app = test_app();
+            let camera = app
+                .world_mut()
+                .spawn((
+                    Camera::default(),
+                    Projection::Perspective(PerspectiveProjection {
+                        aspect_ratio,
+                        ..default()
+                    }),
+                    Transform::from_xyz(0.0, 0.0, 10.0),
+                    OrbitCameraController::default(),
+                ))
+                .id();
+            set_active_camera(
+                app.world_mut(),
+                camera,
+                Vec2::new(600.0 * aspect_ratio, 600.0),
+            );
+            app.update();
+            let entity = app
+                .world_mut()
+                .spawn((
+                    transform,
+                    Aabb::from_min_max(Vec3::splat(-0.5), Vec3::splat(0.5)),
+                ))
+                .id();
+            app.update();
+            frame(&mut app, camera, &[entity]).unwrap();
+            let world = app.world();
+            assert_points_in_view(
+                world.get::<Transform>(camera).unwrap(),
+                world.get::<Projection>(camera).unwrap(),
+                &box_corners(&transform),
+            );
+            radii.push(
+                world
+                    .get::<OrbitCameraController>(camera)
+                    .unwrap()
+                    .radius
+                    .unwrap(),
+            );
+        }
+        // The narrow portrait view needs the camera to move further away
+        assert!(radii[0] > radii[1] * 1.5);
+    }
+
+    /// Set the computed visibility of `entity`, the headless test app does
+    /// not propagate the visibility
+    fn set_visible(app: &mut App, entity: Entity, is_visible: bool) {
+        let visibility = if is_visible {
+            InheritedVisibility::VISIBLE
+        } else {
+            InheritedVisibility::HIDDEN
+        };
+        app.world_mut().entity_mut(entity).insert(visibility);
+    }
+
+    #[test]
+    fn visible_child_of_a_hidden_parent_is_framed() {
+        let mut app = test_app();
+        let camera = spawn_camera(&mut app);
+        let parent = spawn_box(&mut app, Vec3::ZERO);
+        let child = spawn_box(&mut app, Vec3::new(5.0, 0.0, 0.0));
+        app.world_mut().entity_mut(child).set_parent(parent);
+        set_visible(&mut app, parent, false);
+        set_visible(&mut app, child, true);
+        app.update();
+        let bounds = frame(&mut app, camera, &[parent]).unwrap();
+        assert_eq!(bounds.center, Vec3::new(5.0, 0.0, 0.0));
+        assert_eq!(bounds.half_extents, Vec3::splat(0.5));
+
+        // Hidden as well once the child inherits the visibility
+        set_visible(&mut app, child, false);
+        app.update();
+        assert_eq!(
+            frame(&mut app, camera, &[parent]),
+            Err(FramingFailed::NoBounds)
+        );
+    }
+
+    #[test]
+    fn ignored_entity_drops_its_whole_subtree() {
+        let mut app = test_app();
+        let camera = spawn_camera(&mut app);
+        let root = spawn_box(&mut app, Vec3::ZERO);
+        let ignored = spawn_box(&mut app, Vec3::new(5.0, 0.0, 0.0));
+        let child = spawn_box(&mut app, Vec3::new(0.0, 5.0, 0.0));
+        let grandchild = spawn_box(&mut app, Vec3::new(0.0, 0.0, 5.0));
+        app.world_mut().entity_mut(ignored).set_parent(root);
+        app.world_mut().entity_mut(child).set_parent(ignored);
+        app.world_mut().entity_mut(grandchild).set_parent(child);
+        // Even visible descendants are dropped
+        for entity in [root, ignored, child, grandchild] {
+            set_visible(&mut app, entity, true);
+        }
+        app.update();
+        let bounds = frame(&mut app, camera, &[root]).unwrap();
+        assert_eq!(bounds.center, Vec3::splat(2.5));
+
+        app.world_mut()
+            .entity_mut(ignored)
+            .insert(IgnoreWhenFraming);
+        app.update();
+        let bounds = frame(&mut app, camera, &[root]).unwrap();
+        assert_eq!(bounds.center, Vec3::ZERO);
+        assert_eq!(bounds.half_extents, Vec3::splat(0.5));
+        // Also when the entity is framed directly
+        assert_eq!(
+            frame(&mut app, camera, &[ignored]),
+            Err(FramingFailed::NoBounds)
+        );
+    }
+}
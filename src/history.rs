@@ -0,0 +1,385 @@
+use bevy::prelude::*;
+
+use crate::{
+    bookmarks::ViewBookmark, fly::FlyCameraController, input::CameraInputState,
+    orbit::OrbitCameraController, transition::ViewTransition, ActiveCameraData,
+    BlendyCamerasDisabled, CameraViewChanged, OtherProjection, ViewChangeCause,
+};
+
+/// Default maximum number of previous views kept by a [`ViewHistory`]
+const DEFAULT_MAX_DEPTH: usize = 50;
+
+/// Tolerance used to detect that the view changed
+const EPSILON: f32 = 0.0001;
+
+/// Component recording the views of a camera, to go back to them with
+/// [`ViewHistoryUndo`] and [`ViewHistoryRedo`]. Only the cameras with this
+/// component have their history recorded.
+///
+/// A view is recorded when a gesture ends (a whole drag is a single entry),
+/// after handling each event like [`ViewpointEvent`](crate::ViewpointEvent)
+/// and at the end of the animated transitions. The changes reported with
+/// [`ViewChangeCause::Forced`], e.g. by the auto rotation, are not recorded.
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct ViewHistory {
+    /// Maximum number of previous views that can be restored
+    pub max_depth: usize,
+    undo_stack: Vec<ViewBookmark>,
+    redo_stack: Vec<ViewBookmark>,
+    current: Option<ViewBookmark>,
+    has_pending_change: bool,
+}
+
+impl Default for ViewHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_DEPTH)
+    }
+}
+
+impl ViewHistory {
+    /// Empty history keeping at most `max_depth` previous views
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            current: None,
+            has_pending_change: false,
+        }
+    }
+
+    /// Whether there is a previous view to go back to
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Whether there is an undone view to go forward to
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Forget the previous and undone views
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    /// Record `view` as the current view
+    fn commit(&mut self, view: ViewBookmark) {
+        if let Some(current) = self.current.replace(view) {
+            self.undo_stack.push(current);
+            let excess = self.undo_stack.len().saturating_sub(self.max_depth);
+            self.undo_stack.drain(..excess);
+        }
+        self.redo_stack.clear();
+        self.has_pending_change = false;
+    }
+}
+
+/// Event to go back to the previous view recorded in the [`ViewHistory`] of
+/// a camera
+#[derive(Event, Reflect)]
+pub struct ViewHistoryUndo {
+    /// The camera whose view is restored
+    pub camera_entity: Entity,
+}
+
+/// Event to go forward to the view undone with [`ViewHistoryUndo`]
+#[derive(Event, Reflect)]
+pub struct ViewHistoryRedo {
+    /// The camera whose view is restored
+    pub camera_entity: Entity,
+}
+
+/// Record the views of the cameras with a [`ViewHistory`] when the gestures,
+/// the events or the transitions changing them end
+#[allow(clippy::type_complexity)]
+pub(crate) fn view_history_system(
+    active_cam: Res<ActiveCameraData>,
+    mut changed_ev_read: EventReader<CameraViewChanged>,
+    mut cameras_query: Query<
+        (
+            Entity,
+            &mut ViewHistory,
+            &Transform,
+            Option<&OrbitCameraController>,
+            Option<&FlyCameraController>,
+            &Projection,
+            Option<&CameraInputState>,
+            Has<ViewTransition>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+) {
+    let changed_cameras = changed_ev_read
+        .read()
+        .filter(|event| event.cause != ViewChangeCause::Forced)
+        .map(|event| event.camera_entity)
+        .collect::<Vec<_>>();
+    for (
+        entity,
+        mut history,
+        transform,
+        orbit_controller_opt,
+        fly_controller_opt,
+        projection,
+        input_state_opt,
+        has_transition,
+    ) in cameras_query.iter_mut()
+    {
+        let Some(view) = ViewBookmark::from_camera(
+            transform,
+            orbit_controller_opt,
+            fly_controller_opt,
+            projection,
+        ) else {
+            continue;
+        };
+        let Some(current) = history.current else {
+            history.current = Some(view);
+            continue;
+        };
+        let is_moving = has_transition
+            || (active_cam.entity == Some(entity)
+                && active_cam.interaction.is_some())
+            || input_state_opt.is_some_and(|input_state| {
+                input_state.has_input()
+                    || input_state.touch_position.is_some()
+                    || input_state.scroll_accumulator != 0.0
+            });
+        // The transitions are started by events, or by restoring a view
+        if (changed_cameras.contains(&entity) || has_transition)
+            && !history.has_pending_change
+        {
+            history.has_pending_change = true;
+        }
+        if is_moving || !history.has_pending_change {
+            continue;
+        }
+        if view.approx_eq(&current, EPSILON) {
+            history.has_pending_change = false;
+        } else {
+            history.commit(view);
+        }
+    }
+}
+
+/// Handle [`ViewHistoryUndo`] and [`ViewHistoryRedo`]
+#[allow(clippy::type_complexity)]
+pub(crate) fn view_history_event_system(
+    mut commands: Commands,
+    mut undo_ev_read: EventReader<ViewHistoryUndo>,
+    mut redo_ev_read: EventReader<ViewHistoryRedo>,
+    mut cameras_query: Query<
+        (
+            &mut ViewHistory,
+            &mut Transform,
+            Option<&mut OrbitCameraController>,
+            Option<&FlyCameraController>,
+            Option<&Camera>,
+            &mut Projection,
+            Option<&mut OtherProjection>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+) {
+    let events = undo_ev_read
+        .read()
+        .map(|event| (event.camera_entity, true))
+        .chain(
+            redo_ev_read
+                .read()
+                .map(|event| (event.camera_entity, false)),
+        );
+    for (camera_entity, is_undo) in events {
+        let Ok((
+            mut history,
+            mut transform,
+            mut orbit_controller_opt,
+            fly_controller_opt,
+            camera_opt,
+            mut projection,
+            other_projection_opt,
+        )) = cameras_query.get_mut(camera_entity)
+        else {
+            warn!("Camera not found while trying to undo or redo view change");
+            continue;
+        };
+        if let Some(ref mut controller) = orbit_controller_opt {
+            if controller.is_enabled {
+                controller
+                    .initialize_if_necessary(&mut transform, &mut projection);
+            }
+        }
+        // Record the change that is not recorded yet, so that it can be
+        // undone or is forgotten when redoing
+        let view_opt = ViewBookmark::from_camera(
+            &transform,
+            orbit_controller_opt.as_deref(),
+            fly_controller_opt,
+            &projection,
+        );
+        if let (Some(view), Some(current)) = (view_opt, history.current) {
+            if !view.approx_eq(&current, EPSILON) {
+                history.commit(view);
+            }
+        }
+        let history = history.as_mut();
+        let (from_stack, to_stack) = if is_undo {
+            (&mut history.undo_stack, &mut history.redo_stack)
+        } else {
+            (&mut history.redo_stack, &mut history.undo_stack)
+        };
+        let Some(view) = from_stack.pop() else {
+            continue;
+        };
+        if let Some(current) = history.current.replace(view) {
+            to_stack.push(current);
+        }
+        history.has_pending_change = false;
+        view.restore(
+            &mut commands,
+            camera_entity,
+            None,
+            &mut transform,
+            orbit_controller_opt.map(Mut::into_inner),
+            fly_controller_opt,
+            camera_opt.and_then(Camera::logical_viewport_size),
+            &mut projection,
+            other_projection_opt.map(Mut::into_inner),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{
+            assert_transform_approx_eq, set_active_camera, test_app,
+            SimulatedInput,
+        },
+        Viewpoint, ViewpointEvent,
+    };
+
+    fn spawn_camera(app: &mut App, history: ViewHistory) -> Entity {
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 2.0, 5.0),
+                OrbitCameraController::default(),
+                history,
+            ))
+            .id();
+        set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+        app.update();
+        camera
+    }
+
+    fn history(app: &App, camera: Entity) -> &ViewHistory {
+        app.world().get::<ViewHistory>(camera).unwrap()
+    }
+
+    fn transform(app: &App, camera: Entity) -> Transform {
+        *app.world().get::<Transform>(camera).unwrap()
+    }
+
+    /// Move the camera immediately to `viewpoint`
+    fn send_viewpoint(app: &mut App, camera: Entity, viewpoint: Viewpoint) {
+        app.world_mut().send_event(ViewpointEvent {
+            camera_entity: Some(camera),
+            viewpoint,
+            duration: None,
+            relative_to: None,
+        });
+        app.update();
+    }
+
+    fn undo(app: &mut App, camera: Entity) {
+        app.world_mut().send_event(ViewHistoryUndo {
+            camera_entity: camera,
+        });
+        app.update();
+    }
+
+    #[test]
+    fn drag_is_a_single_entry() {
+        let mut app = test_app();
+        let camera = spawn_camera(&mut app, ViewHistory::default());
+        let start = transform(&app, camera);
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_button(MouseButton::Middle);
+        for _ in 0..5 {
+            app.world_mut()
+                .resource_mut::<SimulatedInput>()
+                .orbit(Vec2::new(20.0, 10.0));
+            app.update();
+            // Pausing without releasing the button is still the same drag
+            app.update();
+        }
+        assert!(!history(&app, camera).can_undo());
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .release_button(MouseButton::Middle);
+        app.update();
+        app.update();
+        assert_eq!(history(&app, camera).undo_stack.len(), 1);
+        undo(&mut app, camera);
+        assert_transform_approx_eq(&transform(&app, camera), &start, 1e-4);
+    }
+
+    #[test]
+    fn each_event_is_an_entry() {
+        let mut app = test_app();
+        let camera = spawn_camera(&mut app, ViewHistory::default());
+        send_viewpoint(&mut app, camera, Viewpoint::Front);
+        let front = transform(&app, camera);
+        // Sent during the next frame, without waiting
+        send_viewpoint(&mut app, camera, Viewpoint::Right);
+        assert_eq!(history(&app, camera).undo_stack.len(), 2);
+        undo(&mut app, camera);
+        assert_transform_approx_eq(&transform(&app, camera), &front, 1e-4);
+    }
+
+    #[test]
+    fn oldest_entries_are_dropped_past_the_max_depth() {
+        let mut app = test_app();
+        let camera = spawn_camera(&mut app, ViewHistory::new(2));
+        let mut views = Vec::new();
+        for viewpoint in [
+            Viewpoint::Front,
+            Viewpoint::Right,
+            Viewpoint::Back,
+            Viewpoint::Left,
+        ] {
+            views.push(transform(&app, camera));
+            send_viewpoint(&mut app, camera, viewpoint);
+        }
+        assert_eq!(history(&app, camera).undo_stack.len(), 2);
+        undo(&mut app, camera);
+        undo(&mut app, camera);
+        assert_transform_approx_eq(&transform(&app, camera), &views[2], 1e-4);
+        assert!(!history(&app, camera).can_undo());
+    }
+
+    #[test]
+    fn new_change_clears_the_redo_stack() {
+        let mut app = test_app();
+        let camera = spawn_camera(&mut app, ViewHistory::default());
+        send_viewpoint(&mut app, camera, Viewpoint::Front);
+        send_viewpoint(&mut app, camera, Viewpoint::Right);
+        undo(&mut app, camera);
+        assert!(history(&app, camera).can_redo());
+        // Idle updates are not a change
+        app.update();
+        assert!(history(&app, camera).can_redo());
+        send_viewpoint(&mut app, camera, Viewpoint::Top);
+        let history = history(&app, camera);
+        assert!(!history.can_redo());
+        assert_eq!(history.undo_stack.len(), 2);
+    }
+}
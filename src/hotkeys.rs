@@ -0,0 +1,295 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    get_camera_entity_from_cursor_position,
+    input::{CameraInputBlocked, InputBinding},
+    is_plugin_enabled, ActiveCameraData, ControllerMode, FrameEvent, FrameMode,
+    SetCameraControllerEvent, SwitchProjection, Viewpoint, ViewpointEvent,
+};
+
+/// Optional plugin sending the camera events from the standard Blender
+/// hotkeys, configured with the [`HotkeyConfig`] resource. The events are
+/// sent to the camera under the cursor, or to the active camera if there is
+/// none, so multiple viewports work without extra code. Requires the
+/// [`BlendyCamerasPlugin`](crate::BlendyCamerasPlugin).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BlendyCamerasHotkeysPlugin;
+
+impl Plugin for BlendyCamerasHotkeysPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HotkeyConfig>()
+            .init_resource::<FrameTarget>()
+            .register_type::<HotkeyConfig>()
+            .register_type::<FrameTarget>()
+            .add_systems(Update, hotkeys_system.run_if(is_plugin_enabled));
+    }
+}
+
+/// Bindings of the [`BlendyCamerasHotkeysPlugin`]. An action is triggered
+/// when any of its bindings is just pressed, an empty list disables it.
+/// Defaults to the Blender keymap.
+#[derive(Resource, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Resource, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct HotkeyConfig {
+    /// View from the front. Defaults to Numpad 1.
+    pub front: Vec<InputBinding>,
+    /// View from the back. Defaults to Shift + Numpad 1.
+    pub back: Vec<InputBinding>,
+    /// View from the right. Defaults to Numpad 3.
+    pub right: Vec<InputBinding>,
+    /// View from the left. Defaults to Shift + Numpad 3.
+    pub left: Vec<InputBinding>,
+    /// View from the top. Defaults to Numpad 7.
+    pub top: Vec<InputBinding>,
+    /// View from the bottom. Defaults to Shift + Numpad 7.
+    pub bottom: Vec<InputBinding>,
+    /// Switch between the perspective and orthographic projections, see
+    /// [`SwitchProjection`]. Defaults to Numpad 5.
+    pub switch_projection: Vec<InputBinding>,
+    /// Frame the [`FrameTarget`]. Defaults to Numpad Period and Home.
+    pub frame: Vec<InputBinding>,
+    /// Switch between the orbit and fly controllers, see
+    /// [`ControllerMode::Toggle`]. Defaults to Shift + Backquote.
+    pub toggle_fly: Vec<InputBinding>,
+    /// Duration of the animated transitions to the viewpoints and framings.
+    /// The camera is moved immediately if `None`. Defaults to `None`.
+    pub transition_duration: Option<Duration>,
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        Self {
+            front: unshifted(KeyCode::Numpad1),
+            back: shifted(KeyCode::Numpad1),
+            right: unshifted(KeyCode::Numpad3),
+            left: shifted(KeyCode::Numpad3),
+            top: unshifted(KeyCode::Numpad7),
+            bottom: shifted(KeyCode::Numpad7),
+            switch_projection: vec![InputBinding::key(KeyCode::Numpad5)],
+            frame: vec![
+                InputBinding::key(KeyCode::NumpadDecimal),
+                InputBinding::key(KeyCode::Home),
+            ],
+            toggle_fly: shifted(KeyCode::Backquote),
+            transition_duration: None,
+        }
+    }
+}
+
+/// Bindings to `key` without any Shift key pressed
+fn unshifted(key: KeyCode) -> Vec<InputBinding> {
+    vec![InputBinding::key(key)
+        .without_modifier(KeyCode::ShiftLeft)
+        .without_modifier(KeyCode::ShiftRight)]
+}
+
+/// Bindings to `key` with either Shift key pressed
+fn shifted(key: KeyCode) -> Vec<InputBinding> {
+    vec![
+        InputBinding::key(key).with_modifier(KeyCode::ShiftLeft),
+        InputBinding::key(key).with_modifier(KeyCode::ShiftRight),
+    ]
+}
+
+/// Entities framed by the frame hotkey of the [`BlendyCamerasHotkeysPlugin`].
+/// The whole scene, i.e. all the root entities except the cameras, is framed
+/// when empty.
+#[derive(Resource, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Resource, Default)]
+pub struct FrameTarget {
+    /// The entities to frame
+    pub entities: Vec<Entity>,
+    /// Also frame the children of the entities
+    pub include_children: bool,
+}
+
+impl Default for FrameTarget {
+    fn default() -> Self {
+        Self {
+            entities: Vec::new(),
+            include_children: true,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn hotkeys_system(
+    config: Res<HotkeyConfig>,
+    frame_target: Res<FrameTarget>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    key_input: Res<ButtonInput<KeyCode>>,
+    input_blocked: Res<CameraInputBlocked>,
+    active_cam: Res<ActiveCameraData>,
+    cameras_query: Query<(Entity, &Camera)>,
+    primary_window: Query<(Entity, &Window), With<PrimaryWindow>>,
+    other_windows: Query<(Entity, &Window), Without<PrimaryWindow>>,
+    scene_roots: Query<
+        Entity,
+        (Without<Parent>, With<Transform>, Without<Camera>),
+    >,
+    mut viewpoint_ev_write: EventWriter<ViewpointEvent>,
+    mut projection_ev_write: EventWriter<SwitchProjection>,
+    mut frame_ev_write: EventWriter<FrameEvent>,
+    mut controller_ev_write: EventWriter<SetCameraControllerEvent>,
+) {
+    if input_blocked.is_keyboard_blocked() {
+        return;
+    }
+    let just_pressed = |bindings: &[InputBinding]| {
+        bindings
+            .iter()
+            .any(|binding| binding.just_pressed(&mouse_input, &key_input))
+    };
+    let viewpoints = [
+        (&config.front, Viewpoint::Front),
+        (&config.back, Viewpoint::Back),
+        (&config.right, Viewpoint::Right),
+        (&config.left, Viewpoint::Left),
+        (&config.top, Viewpoint::Top),
+        (&config.bottom, Viewpoint::Bottom),
+    ];
+    let viewpoint = viewpoints
+        .into_iter()
+        .find(|(bindings, _)| just_pressed(bindings))
+        .map(|(_, viewpoint)| viewpoint);
+    let switch_projection = just_pressed(&config.switch_projection);
+    let frame = just_pressed(&config.frame);
+    let toggle_fly = just_pressed(&config.toggle_fly);
+    if viewpoint.is_none() && !switch_projection && !frame && !toggle_fly {
+        return;
+    }
+    let Some(camera_entity) = get_camera_entity_from_cursor_position(
+        &cameras_query,
+        &primary_window,
+        &other_windows,
+    )
+    .or(active_cam.entity) else {
+        return;
+    };
+    if let Some(viewpoint) = viewpoint {
+        viewpoint_ev_write.send(ViewpointEvent {
+            camera_entity: Some(camera_entity),
+            viewpoint,
+            duration: config.transition_duration,
+            relative_to: None,
+        });
+    }
+    if switch_projection {
+        projection_ev_write.send(SwitchProjection { camera_entity });
+    }
+    if frame {
+        let (entities_to_be_framed, include_children) =
+            if frame_target.entities.is_empty() {
+                (scene_roots.iter().collect(), true)
+            } else {
+                (frame_target.entities.clone(), frame_target.include_children)
+            };
+        frame_ev_write.send(FrameEvent {
+            camera_entity,
+            entities_to_be_framed,
+            include_children,
+            precise: false,
+            ignore_hidden: true,
+            mode: FrameMode::KeepDirection,
+            duration: config.transition_duration,
+        });
+    }
+    if toggle_fly {
+        controller_ev_write.send(SetCameraControllerEvent {
+            camera_entity,
+            mode: ControllerMode::Toggle,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{set_active_camera, test_app, SimulatedInput},
+        OrbitCameraController,
+    };
+
+    fn app_with_camera() -> (App, Entity) {
+        let mut app = test_app();
+        app.add_plugins(BlendyCamerasHotkeysPlugin);
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 0.0, 5.0),
+                OrbitCameraController::default(),
+            ))
+            .id();
+        set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+        app.update();
+        (app, camera)
+    }
+
+    /// Press and release `keys`, and return the viewpoints sent meanwhile
+    fn press(
+        app: &mut App,
+        keys: &[KeyCode],
+    ) -> Vec<(Option<Entity>, Viewpoint)> {
+        app.world_mut()
+            .resource_mut::<Events<ViewpointEvent>>()
+            .clear();
+        let mut input = app.world_mut().resource_mut::<SimulatedInput>();
+        for &key in keys {
+            input.press_key(key);
+        }
+        app.update();
+        let viewpoints = app
+            .world()
+            .resource::<Events<ViewpointEvent>>()
+            .iter_current_update_events()
+            .map(|event| (event.camera_entity, event.viewpoint))
+            .collect();
+        let mut input = app.world_mut().resource_mut::<SimulatedInput>();
+        for &key in keys {
+            input.release_key(key);
+        }
+        app.update();
+        viewpoints
+    }
+
+    #[test]
+    fn numpad_keys_send_the_viewpoints() {
+        let (mut app, camera) = app_with_camera();
+        for (keys, viewpoint) in [
+            (&[KeyCode::Numpad1][..], Viewpoint::Front),
+            (&[KeyCode::ShiftLeft, KeyCode::Numpad1], Viewpoint::Back),
+            (&[KeyCode::Numpad3], Viewpoint::Right),
+            (&[KeyCode::ShiftRight, KeyCode::Numpad3], Viewpoint::Left),
+            (&[KeyCode::Numpad7], Viewpoint::Top),
+            (&[KeyCode::ShiftLeft, KeyCode::Numpad7], Viewpoint::Bottom),
+        ] {
+            assert_eq!(press(&mut app, keys), [(Some(camera), viewpoint)]);
+        }
+    }
+
+    #[test]
+    fn rebound_hotkeys_are_used() {
+        let (mut app, camera) = app_with_camera();
+        app.world_mut().resource_mut::<HotkeyConfig>().front =
+            vec![InputBinding::key(KeyCode::Digit1)];
+        assert_eq!(press(&mut app, &[KeyCode::Numpad1]), []);
+        assert_eq!(
+            press(&mut app, &[KeyCode::Digit1]),
+            [(Some(camera), Viewpoint::Front)]
+        );
+        // The other bindings are unchanged
+        assert_eq!(
+            press(&mut app, &[KeyCode::Numpad3]),
+            [(Some(camera), Viewpoint::Right)]
+        );
+    }
+}
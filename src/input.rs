@@ -1,132 +1,1182 @@
 use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
 use bevy::prelude::*;
+use bevy::render::camera::NormalizedRenderTarget;
+use bevy::utils::HashMap;
+use bevy::window::PrimaryWindow;
 
 use crate::{
-    fly::FlyCameraController, orbit::OrbitCameraController, ActiveCameraData,
+    fly::FlyCameraController, orbit::OrbitCameraController,
+    pan2d::Pan2dCameraController, ActiveCameraData, CameraInteraction,
 };
 
-#[derive(Resource, Default, Debug)]
-pub(crate) struct MouseKeyTracker {
+/// Mouse button or key that triggers a camera action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum InputTrigger {
+    /// A mouse button
+    Mouse(MouseButton),
+    /// A keyboard key
+    Key(KeyCode),
+}
+
+/// Binding of a camera action, like orbiting or panning, to a mouse button
+/// or key and a set of modifier keys
+#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct InputBinding {
+    /// The mouse button or key that triggers the action
+    pub trigger: InputTrigger,
+    /// Keys that must all be pressed for the binding to work
+    pub required_modifiers: Vec<KeyCode>,
+    /// Keys that must not be pressed for the binding to work
+    pub forbidden_modifiers: Vec<KeyCode>,
+}
+
+impl InputBinding {
+    /// Binding to a mouse button without modifiers
+    pub fn mouse(button: MouseButton) -> Self {
+        Self {
+            trigger: InputTrigger::Mouse(button),
+            required_modifiers: Vec::new(),
+            forbidden_modifiers: Vec::new(),
+        }
+    }
+
+    /// Binding to a key without modifiers
+    pub fn key(key: KeyCode) -> Self {
+        Self {
+            trigger: InputTrigger::Key(key),
+            required_modifiers: Vec::new(),
+            forbidden_modifiers: Vec::new(),
+        }
+    }
+
+    /// Add a key that must be pressed for the binding to work
+    pub fn with_modifier(mut self, modifier: KeyCode) -> Self {
+        self.required_modifiers.push(modifier);
+        self
+    }
+
+    /// Add a key that must not be pressed for the binding to work
+    pub fn without_modifier(mut self, modifier: KeyCode) -> Self {
+        self.forbidden_modifiers.push(modifier);
+        self
+    }
+
+    /// Binding equivalent to a button with an optional modifier, that does
+    /// not work when any of the `exclusive_modifiers` is pressed. Used to
+    /// build the bindings from the single button and modifier fields of the
+    /// controllers.
+    pub(crate) fn from_button_and_modifiers(
+        button: MouseButton,
+        modifier: Option<KeyCode>,
+        exclusive_modifiers: &[Option<KeyCode>],
+    ) -> Self {
+        Self {
+            trigger: InputTrigger::Mouse(button),
+            required_modifiers: modifier.into_iter().collect(),
+            forbidden_modifiers: exclusive_modifiers
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|exclusive| Some(*exclusive) != modifier)
+                .collect(),
+        }
+    }
+
+    fn modifiers_match(&self, key_input: &ButtonInput<KeyCode>) -> bool {
+        key_input.all_pressed(self.required_modifiers.iter().copied())
+            && !key_input.any_pressed(self.forbidden_modifiers.iter().copied())
+    }
+
+    /// Whether the binding is active
+    pub fn pressed(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        let is_pressed = match self.trigger {
+            InputTrigger::Mouse(button) => mouse_input.pressed(button),
+            InputTrigger::Key(key) => key_input.pressed(key),
+        };
+        is_pressed && self.modifiers_match(key_input)
+    }
+
+    /// Whether the binding has just been activated
+    pub fn just_pressed(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        let just_pressed = match self.trigger {
+            InputTrigger::Mouse(button) => mouse_input.just_pressed(button),
+            InputTrigger::Key(key) => key_input.just_pressed(key),
+        };
+        just_pressed && self.modifiers_match(key_input)
+    }
+
+    /// Whether the binding has just been deactivated
+    pub fn just_released(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        let just_released = match self.trigger {
+            InputTrigger::Mouse(button) => mouse_input.just_released(button),
+            InputTrigger::Key(key) => key_input.just_released(key),
+        };
+        just_released && self.modifiers_match(key_input)
+    }
+}
+
+impl InputBinding {
+    /// Whether both bindings can be active at the same time
+    fn overlaps(&self, other: &InputBinding) -> bool {
+        let is_disjoint =
+            |a: &[KeyCode], b: &[KeyCode]| !a.iter().any(|key| b.contains(key));
+        self.trigger == other.trigger
+            && is_disjoint(&self.required_modifiers, &self.forbidden_modifiers)
+            && is_disjoint(
+                &other.required_modifiers,
+                &other.forbidden_modifiers,
+            )
+            && is_disjoint(&self.required_modifiers, &other.forbidden_modifiers)
+            && is_disjoint(&other.required_modifiers, &self.forbidden_modifiers)
+    }
+
+    /// Whether this binding is active whenever `other` is active
+    fn shadows(&self, other: &InputBinding) -> bool {
+        let is_subset =
+            |a: &[KeyCode], b: &[KeyCode]| a.iter().all(|key| b.contains(key));
+        self.overlaps(other)
+            && is_subset(&self.required_modifiers, &other.required_modifiers)
+            && is_subset(&self.forbidden_modifiers, &other.forbidden_modifiers)
+    }
+}
+
+/// Whether the `suppress_modifier` of a controller is held
+fn is_suppressed(
+    suppress_modifier: Option<KeyCode>,
+    key_input: &ButtonInput<KeyCode>,
+) -> bool {
+    suppress_modifier.is_some_and(|key| key_input.pressed(key))
+}
+
+/// Whether one of the bindings of a controller is active, never while its
+/// `suppress_modifier` is held
+pub(crate) fn bindings_pressed(
+    bindings: &[InputBinding],
+    suppress_modifier: Option<KeyCode>,
+    mouse_input: &ButtonInput<MouseButton>,
+    key_input: &ButtonInput<KeyCode>,
+) -> bool {
+    !is_suppressed(suppress_modifier, key_input)
+        && bindings
+            .iter()
+            .any(|binding| binding.pressed(mouse_input, key_input))
+}
+
+/// Whether one of the bindings of a controller has just been activated,
+/// including when its `suppress_modifier` is released while the binding is
+/// held
+pub(crate) fn bindings_just_pressed(
+    bindings: &[InputBinding],
+    suppress_modifier: Option<KeyCode>,
+    mouse_input: &ButtonInput<MouseButton>,
+    key_input: &ButtonInput<KeyCode>,
+) -> bool {
+    if is_suppressed(suppress_modifier, key_input) {
+        return false;
+    }
+    let is_unsuppressed =
+        suppress_modifier.is_some_and(|key| key_input.just_released(key));
+    bindings.iter().any(|binding| {
+        binding.just_pressed(mouse_input, key_input)
+            || (is_unsuppressed && binding.pressed(mouse_input, key_input))
+    })
+}
+
+/// Whether one of the bindings of a controller has just been deactivated,
+/// including when its `suppress_modifier` is pressed while the binding is
+/// held, so that a drag in progress ends as if the button was released
+pub(crate) fn bindings_just_released(
+    bindings: &[InputBinding],
+    suppress_modifier: Option<KeyCode>,
+    mouse_input: &ButtonInput<MouseButton>,
+    key_input: &ButtonInput<KeyCode>,
+) -> bool {
+    let is_just_suppressed =
+        suppress_modifier.is_some_and(|key| key_input.just_pressed(key));
+    if !is_just_suppressed && is_suppressed(suppress_modifier, key_input) {
+        return false;
+    }
+    bindings.iter().any(|binding| {
+        binding.just_released(mouse_input, key_input)
+            || (is_just_suppressed && binding.pressed(mouse_input, key_input))
+    })
+}
+
+/// Camera action bound to an [`InputBinding`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum BindingAction {
+    /// Orbit with [`OrbitCameraController`]
+    Orbit,
+    /// Pan with [`OrbitCameraController`]
+    Pan,
+    /// Zoom by dragging with [`OrbitCameraController`]
+    Zoom,
+    /// Rotate with [`FlyCameraController`]
+    FlyRotate,
+}
+
+/// Two camera actions that can be triggered by the same input
+#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
+pub struct BindingConflict {
+    /// The action taking precedence when both bindings are active
+    pub action: BindingAction,
+    /// The binding of `action`
+    pub binding: InputBinding,
+    /// The other action
+    pub other_action: BindingAction,
+    /// The binding of `other_action`
+    pub other_binding: InputBinding,
+    /// Whether `other_binding` can never trigger `other_action`, because
+    /// `binding` is active whenever `other_binding` is
+    pub shadowed: bool,
+}
+
+/// Event sent when the bindings of the controllers of a camera conflict,
+/// see [`OrbitCameraController::validate_bindings`]
+#[derive(Event, Debug, Clone, PartialEq, Reflect)]
+pub struct BindingConflictEvent {
+    /// Entity of the camera with the conflicting bindings
+    pub camera_entity: Entity,
+    /// The conflicts found
+    pub conflicts: Vec<BindingConflict>,
+}
+
+/// Find the conflicts between the bindings of the actions, given in order of
+/// precedence. If `exclusive` is false, the actions are triggered together
+/// and no binding is considered shadowed.
+pub(crate) fn find_binding_conflicts(
+    actions: &[(BindingAction, &[InputBinding])],
+    exclusive: bool,
+) -> Vec<BindingConflict> {
+    let mut conflicts = Vec::new();
+    for (index, (action, bindings)) in actions.iter().enumerate() {
+        for (other_action, other_bindings) in &actions[index + 1..] {
+            for binding in bindings.iter() {
+                for other_binding in other_bindings.iter() {
+                    if binding.overlaps(other_binding) {
+                        conflicts.push(BindingConflict {
+                            action: *action,
+                            binding: binding.clone(),
+                            other_action: *other_action,
+                            other_binding: other_binding.clone(),
+                            shadowed: exclusive
+                                && binding.shadows(other_binding),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    conflicts
+}
+
+/// Warn about the conflicting bindings when the controllers are added or
+/// changed. Conflicts are only reported once as long as they do not change.
+#[allow(clippy::type_complexity)]
+pub(crate) fn validate_bindings_system(
+    mut conflict_events: EventWriter<BindingConflictEvent>,
+    controllers: Query<
+        (
+            Entity,
+            Option<&OrbitCameraController>,
+            Option<&FlyCameraController>,
+        ),
+        Or<(Changed<OrbitCameraController>, Changed<FlyCameraController>)>,
+    >,
+    mut reported: Local<HashMap<Entity, Vec<BindingConflict>>>,
+) {
+    for (entity, orbit_controller, fly_controller) in controllers.iter() {
+        let orbit_controller =
+            orbit_controller.filter(|controller| controller.is_enabled);
+        let fly_controller =
+            fly_controller.filter(|controller| controller.is_enabled);
+        let mut conflicts = orbit_controller
+            .map(OrbitCameraController::validate_bindings)
+            .unwrap_or_default();
+        // Both controllers handle the input when enabled together
+        if let (Some(orbit_controller), Some(fly_controller)) =
+            (orbit_controller, fly_controller)
+        {
+            let orbit_bindings = orbit_controller.orbit_bindings();
+            let pan_bindings = orbit_controller.pan_bindings();
+            let zoom_bindings = orbit_controller.zoom_bindings();
+            let rotate_bindings = fly_controller.rotate_bindings();
+            for (action, bindings) in [
+                (BindingAction::Orbit, &orbit_bindings),
+                (BindingAction::Pan, &pan_bindings),
+                (BindingAction::Zoom, &zoom_bindings),
+            ] {
+                conflicts.extend(find_binding_conflicts(
+                    &[
+                        (action, bindings),
+                        (BindingAction::FlyRotate, &rotate_bindings),
+                    ],
+                    false,
+                ));
+            }
+        }
+        if reported.get(&entity) == Some(&conflicts) {
+            continue;
+        }
+        for conflict in &conflicts {
+            if conflict.shadowed {
+                warn!(
+                    "Camera {entity}: {:?} binding {:?} is shadowed by {:?} \
+                    binding {:?}",
+                    conflict.other_action,
+                    conflict.other_binding,
+                    conflict.action,
+                    conflict.binding
+                );
+            } else {
+                warn!(
+                    "Camera {entity}: {:?} binding {:?} and {:?} binding {:?} \
+                    can be triggered by the same input",
+                    conflict.action,
+                    conflict.binding,
+                    conflict.other_action,
+                    conflict.other_binding
+                );
+            }
+        }
+        if !conflicts.is_empty() {
+            conflict_events.send(BindingConflictEvent {
+                camera_entity: entity,
+                conflicts: conflicts.clone(),
+            });
+        }
+        reported.insert(entity, conflicts);
+    }
+}
+
+/// What to do with the horizontal scroll of trackpads and tilt wheels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum HorizontalScrollAction {
+    /// Pan the camera horizontally, or strafe sideways in fly mode
+    #[default]
+    Pan,
+    /// Ignore the horizontal scroll
+    Ignore,
+    /// Zoom like the vertical scroll, or change the speed in fly mode
+    Zoom,
+}
+
+/// Acceleration of the zoom when scrolling quickly, see
+/// [`OrbitCameraController::zoom_acceleration`]. Each line scrolled shortly
+/// after the previous scroll multiplies the zoom step by `factor`, up to
+/// `max_multiplier`. The pixel scroll of trackpads is counted in lines.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ZoomAcceleration {
+    /// Multiplier of the zoom step for each consecutive line of scroll
+    pub factor: f32,
+    /// Maximum multiplier of the zoom step
+    pub max_multiplier: f32,
+    /// Pause, in seconds, after which the zoom step is reset
+    pub reset_delay: f32,
+}
+
+impl Default for ZoomAcceleration {
+    fn default() -> Self {
+        Self {
+            factor: 1.25,
+            max_multiplier: 4.0,
+            reset_delay: 0.2,
+        }
+    }
+}
+
+/// Response of a camera controller to the input of each frame, applied
+/// before the sensitivity, to make small motions more precise while keeping
+/// large ones fast. The curve maps the length of a mouse motion, so that a
+/// diagonal drag keeps its direction, or the absolute value of a zoom, so
+/// that its sign is kept. The mouse motions are in logical pixels and the
+/// zoom in lines of scroll, the zoom by dragging 200 pixels being one line.
+#[derive(Debug, Clone, PartialEq, Default, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum SensitivityCurve {
+    /// The input is used as is
+    #[default]
+    Linear,
+    /// The input is raised to this power, a value of 1 being unchanged.
+    /// Above 1, the small motions are damped more than the large ones, for
+    /// precise adjustments. Below 1, they are damped less.
+    Power(f32),
+    /// Points `(input, output)` sorted by input, linearly interpolated and
+    /// starting from `(0, 0)`. Past the last point, the input is scaled like
+    /// the last point.
+    Table(Vec<Vec2>),
+}
+
+impl SensitivityCurve {
+    /// Output of the curve for a non negative `input`
+    pub fn response(&self, input: f32) -> f32 {
+        match self {
+            Self::Linear => input,
+            Self::Power(exponent) => input.powf(*exponent),
+            Self::Table(points) => {
+                let mut previous = Vec2::ZERO;
+                for point in points {
+                    if input <= point.x {
+                        let t = if point.x > previous.x {
+                            (input - previous.x) / (point.x - previous.x)
+                        } else {
+                            1.0
+                        };
+                        return previous.y + (point.y - previous.y) * t;
+                    }
+                    previous = *point;
+                }
+                if previous.x > 0.0 {
+                    input * previous.y / previous.x
+                } else {
+                    input
+                }
+            }
+        }
+    }
+
+    /// Apply the curve to the length of a mouse motion, keeping its
+    /// direction
+    pub fn apply_motion(&self, motion: Vec2) -> Vec2 {
+        let length = motion.length();
+        if length == 0.0 || *self == Self::Linear {
+            return motion;
+        }
+        motion * (self.response(length) / length)
+    }
+
+    /// Apply the curve to the absolute value of `value`, keeping its sign
+    pub fn apply_value(&self, value: f32) -> f32 {
+        if value == 0.0 {
+            return value;
+        }
+        value.signum() * self.response(value.abs())
+    }
+}
+
+/// Time constant, in seconds, of the scroll smoothing. A mouse wheel detent
+/// is mostly applied after about 3 times this value.
+const SMOOTH_SCROLL_TIME: f32 = 0.033;
+
+/// Number of pixels equivalent to one line of horizontal scroll when
+/// panning
+const SCROLL_LINE_PIXELS: f32 = 20.0;
+
+/// Sum the scroll events, returning the deltas in lines and in pixels
+fn scroll_deltas(scroll_events: &mut EventReader<MouseWheel>) -> (Vec2, Vec2) {
+    scroll_events.read().fold(
+        (Vec2::ZERO, Vec2::ZERO),
+        |(line, pixel), event| {
+            let delta = Vec2::new(event.x, event.y);
+            match event.unit {
+                MouseScrollUnit::Line => (line + delta, pixel),
+                MouseScrollUnit::Pixel => (line, pixel + delta),
+            }
+        },
+    )
+}
+
+/// Input to be handled by a camera controller during the current frame.
+/// Automatically added to the entities with a camera controller and only
+/// updated for the active camera, the other cameras having no input.
+#[derive(Component, Default, Debug, Clone, PartialEq)]
+pub struct CameraInputState {
+    /// Mouse motion, in logical pixels, while orbiting
     pub orbit: Vec2,
+    /// Mouse motion, in logical pixels, while panning
     pub pan: Vec2,
+    /// Scroll, in lines, used to zoom or to change the fly speed
     pub scroll_line: f32,
+    /// Scroll, in pixels scaled down to be comparable to lines, used to zoom
+    /// or to change the fly speed
     pub scroll_pixel: f32,
+    /// Vertical mouse motion while dragging to zoom
+    pub zoom_drag: f32,
+    /// Scroll, in lines, not yet applied when smoothing the scroll
+    pub scroll_accumulator: f32,
+    /// Multiplier of the scroll applied by the zoom acceleration
+    pub scroll_multiplier: f32,
+    /// Elapsed time, in seconds, of the last scroll, used by the zoom
+    /// acceleration
+    pub last_scroll_secs: Option<f32>,
+    /// Whether orbiting just started or stopped
     pub orbit_button_changed: bool,
+    /// Mouse motion, in logical pixels, while rotating in fly mode
+    pub rotate: Vec2,
+    /// Movement in fly mode from the [`ExternalCameraInput`], see
+    /// [`ExternalCameraInput::translate`]
+    pub translate: Vec3,
+    /// Centroid of the touches, in logical window coordinates, while a touch
+    /// gesture is in progress
+    pub touch_position: Option<Vec2>,
+    /// Whether a touch gesture just started
+    pub touch_just_started: bool,
+    /// Mouse motion, in logical pixels, of the current drag that is not
+    /// applied yet because it is below the drag start threshold
+    pub pending_drag_motion: Vec2,
+    /// Whether the current drag exceeded the drag start threshold
+    pub is_drag_started: bool,
+    /// Whether the current drag just exceeded the drag start threshold, or
+    /// just started if there is no threshold
+    pub drag_just_started: bool,
+}
+
+impl CameraInputState {
+    /// Whether any mouse motion or scroll is to be handled by the camera
+    pub fn has_input(&self) -> bool {
+        self.orbit != Vec2::ZERO
+            || self.pan != Vec2::ZERO
+            || self.scroll_line != 0.0
+            || self.scroll_pixel != 0.0
+            || self.zoom_drag != 0.0
+            || self.rotate != Vec2::ZERO
+            || self.translate != Vec3::ZERO
+    }
+}
+
+/// Resource to move the active camera with other devices than the mouse and
+/// the keyboard, e.g. a gamepad or a 3D mouse. Fill it every frame from a
+/// system running before
+/// [`BlendyCamerasSystemSet::ProcessInput`](crate::BlendyCamerasSystemSet),
+/// it is added to the mouse input of the active camera, before the
+/// sensitivities of the controller are applied, then cleared. The values are
+/// per frame, so analog axes should be scaled by the frame time.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource, Default)]
+pub struct ExternalCameraInput {
+    /// Camera made active to handle the input, without having to hover its
+    /// viewport with the cursor. The active camera is used if `None`. It
+    /// does not change during a mouse drag.
+    pub target_camera: Option<Entity>,
+    /// Orbit of [`OrbitCameraController`], like a mouse motion in logical
+    /// pixels
+    pub orbit: Vec2,
+    /// Pan of [`OrbitCameraController`] and [`Pan2dCameraController`], like
+    /// a mouse motion in logical pixels
+    pub pan: Vec2,
+    /// Zoom, like a scroll in lines. Changes the speed of
+    /// [`FlyCameraController`]
+    pub zoom: f32,
+    /// Rotation of [`FlyCameraController`], like a mouse motion in logical
+    /// pixels
     pub rotate: Vec2,
+    /// Movement of [`FlyCameraController`], in the axes of the camera (`-Z`
+    /// forward) and along its `translation_frame`. A length of 1 is the
+    /// speed of the movement keys, and it is not scaled by the frame time.
+    pub translate: Vec3,
+}
+
+impl ExternalCameraInput {
+    /// Whether there is input for the cameras
+    pub fn has_input(&self) -> bool {
+        self.orbit != Vec2::ZERO
+            || self.pan != Vec2::ZERO
+            || self.zoom != 0.0
+            || self.rotate != Vec2::ZERO
+            || self.translate != Vec3::ZERO
+    }
+}
+
+/// Input state of the active camera
+#[deprecated(
+    since = "0.7.0",
+    note = "use the `CameraInputState` component of the camera instead"
+)]
+#[derive(Default, Debug)]
+pub struct MouseKeyTracker(pub CameraInputState);
+
+#[allow(deprecated)]
+impl Resource for MouseKeyTracker {}
+
+#[allow(deprecated)]
+impl std::ops::Deref for MouseKeyTracker {
+    type Target = CameraInputState;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Resource to stop the cameras from handling the input, e.g. while the user
+/// drags a gizmo with a mouse button also used by the camera controllers.
+/// Set it during `PreUpdate`, or in `PostUpdate` before
+/// [`BlendyCamerasSystemSet::ProcessInput`](crate::BlendyCamerasSystemSet).
+#[derive(Resource, PartialEq, Eq, Default, Debug)]
+pub struct CameraInputBlocked {
+    /// Block the input during the current frame only. Reset after the
+    /// cameras are updated.
+    pub blocked: bool,
+    /// Block the input until all the mouse buttons and touches are released,
+    /// so that a drag that started on a gizmo stays blocked even if the
+    /// cursor leaves the gizmo. Reset once released.
+    pub until_released: bool,
+    /// Block the keyboard driven movements during the current frame only,
+    /// e.g. while a text field has the focus. Mouse drags are not blocked.
+    /// Reset after the cameras are updated.
+    pub keyboard_blocked: bool,
+}
+
+impl CameraInputBlocked {
+    /// Block the input during the current frame
+    pub fn block(&mut self) {
+        self.blocked = true;
+    }
+
+    /// Block the input until all the mouse buttons and touches are released
+    pub fn block_until_released(&mut self) {
+        self.until_released = true;
+    }
+
+    /// Block the keyboard driven movements during the current frame
+    pub fn block_keyboard(&mut self) {
+        self.keyboard_blocked = true;
+    }
+
+    /// Whether the cameras must ignore the input
+    pub fn is_blocked(&self) -> bool {
+        self.blocked || self.until_released
+    }
+
+    /// Whether the cameras must ignore the keyboard driven movements
+    pub fn is_keyboard_blocked(&self) -> bool {
+        self.is_blocked() || self.keyboard_blocked
+    }
+}
+
+/// Reset the blocking of the input at the end of the frame
+pub(crate) fn reset_camera_input_blocked_system(
+    mut input_blocked: ResMut<CameraInputBlocked>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+) {
+    let is_released = mouse_input.get_pressed().next().is_none()
+        && touches.iter().next().is_none();
+    let new_value = CameraInputBlocked {
+        blocked: false,
+        until_released: input_blocked.until_released && !is_released,
+        keyboard_blocked: false,
+    };
+    input_blocked.set_if_neq(new_value);
+}
+
+/// Deltas of the touch gestures during the last frame
+#[derive(Default)]
+struct TouchGestures {
+    /// One finger drag
+    orbit: Vec2,
+    /// Two fingers drag
+    pan: Vec2,
+    /// Change of the distance between two fingers
+    pinch: f32,
+    /// Centroid of the touches
+    position: Option<Vec2>,
+    just_started: bool,
+}
+
+/// Touches of the gesture on the active camera, by id. A touch joins the
+/// gesture when it begins inside the viewport of the active camera and
+/// leaves it when released, so that a finger can be added or lifted and put
+/// back during a gesture.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct GestureTouches(Vec<u64>);
+
+impl GestureTouches {
+    /// Whether at least one of the touches of the gesture is still pressed
+    pub(crate) fn is_active(&self, touches: &Touches) -> bool {
+        self.0.iter().any(|id| touches.get_pressed(*id).is_some())
+    }
+
+    /// Forget the released touches and add the touches that just began
+    /// inside `viewport`, in logical window coordinates
+    fn update(&mut self, touches: &Touches, viewport: Option<Rect>) {
+        self.0.retain(|id| touches.get_pressed(*id).is_some());
+        for touch in touches.iter_just_pressed() {
+            if !self.0.contains(&touch.id())
+                && viewport
+                    .is_none_or(|viewport| viewport.contains(touch.position()))
+            {
+                self.0.push(touch.id());
+            }
+        }
+    }
+}
+
+/// Collect the gestures made with the touches of the gesture
+fn touch_gestures(
+    touches: &Touches,
+    gesture_touches: &GestureTouches,
+) -> TouchGestures {
+    let active = touches
+        .iter()
+        .filter(|touch| gesture_touches.0.contains(&touch.id()))
+        .collect::<Vec<_>>();
+    if active.is_empty() {
+        return TouchGestures::default();
+    }
+    let position = active.iter().map(|touch| touch.position()).sum::<Vec2>()
+        / active.len() as f32;
+    let just_started =
+        active.iter().any(|touch| touches.just_pressed(touch.id()));
+    match active.as_slice() {
+        [touch] => TouchGestures {
+            orbit: touch.delta(),
+            position: Some(position),
+            just_started,
+            ..default()
+        },
+        [first, second] => {
+            let distance = first.position().distance(second.position());
+            let previous_distance = first
+                .previous_position()
+                .distance(second.previous_position());
+            TouchGestures {
+                pan: (first.delta() + second.delta()) / 2.0,
+                pinch: distance - previous_distance,
+                position: Some(position),
+                just_started,
+                ..default()
+            }
+        }
+        // Gestures with more fingers are not handled
+        _ => TouchGestures::default(),
+    }
 }
 
-// TODO: Maybe make 2 systems
-#[allow(clippy::too_many_arguments)]
+/// Make the target camera of the [`ExternalCameraInput`] active, unless the
+/// active camera is being dragged
+pub(crate) fn external_input_target_system(
+    external_input: Res<ExternalCameraInput>,
+    mut active_cam: ResMut<ActiveCameraData>,
+    input_blocked: Res<CameraInputBlocked>,
+    cameras: Query<&Camera>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    windows: Query<&Window>,
+) {
+    let Some(target) = external_input.target_camera else {
+        return;
+    };
+    if !external_input.has_input()
+        || input_blocked.is_blocked()
+        || active_cam.entity == Some(target)
+        || active_cam.interaction.is_some()
+    {
+        return;
+    }
+    let Ok(camera) = cameras.get(target) else {
+        warn!("Target camera {target} of the external input not found");
+        return;
+    };
+    let window_entity =
+        match camera.target.normalize(primary_window.get_single().ok()) {
+            Some(NormalizedRenderTarget::Window(window_ref)) => {
+                Some(window_ref.entity())
+            }
+            _ => None,
+        };
+    let window_opt =
+        window_entity.and_then(|window_entity| windows.get(window_entity).ok());
+    *active_cam = ActiveCameraData {
+        entity: Some(target),
+        viewport_size: camera.logical_viewport_size(),
+        window_size: window_opt
+            .map(|window| Vec2::new(window.width(), window.height()))
+            .or(camera.logical_target_size()),
+        scale_factor: window_opt.map(Window::scale_factor),
+        manual: active_cam.manual,
+        window_entity,
+        interaction: None,
+    };
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity, deprecated)]
 pub(crate) fn mouse_key_tracker_system(
-    mut camera_movement: ResMut<MouseKeyTracker>,
+    mut mouse_key_tracker: ResMut<MouseKeyTracker>,
+    mut external_input: ResMut<ExternalCameraInput>,
     mouse_input: Res<ButtonInput<MouseButton>>,
     key_input: Res<ButtonInput<KeyCode>>,
     mut mouse_motion: EventReader<MouseMotion>,
     mut scroll_events: EventReader<MouseWheel>,
-    active_cam: Res<ActiveCameraData>,
-    orbit_cameras: Query<&OrbitCameraController>,
-    fly_cameras: Query<&FlyCameraController>,
+    touches: Res<Touches>,
+    time: Res<Time>,
+    mut active_cam: ResMut<ActiveCameraData>,
+    input_blocked: Res<CameraInputBlocked>,
+    mut gesture_touches: ResMut<GestureTouches>,
+    mut cameras: Query<(
+        Entity,
+        &mut CameraInputState,
+        Option<&Camera>,
+        Option<&OrbitCameraController>,
+        Option<&FlyCameraController>,
+        Option<&Pan2dCameraController>,
+    )>,
 ) {
-    if let Some(active_entity) = active_cam.entity {
-        // TODO: clean, remove duplicate code
-        if let Ok(orbit_controller) = orbit_cameras.get(active_entity) {
-            if orbit_controller.is_enabled {
-                let mut orbit = Vec2::ZERO;
-                let mut pan = Vec2::ZERO;
-                let mut scroll_line = 0.0;
-                let mut scroll_pixel = 0.0;
-                let mut orbit_button_changed = false;
-                let rotate = Vec2::ZERO;
-
-                // Collect input deltas
-                let mouse_delta =
-                    mouse_motion.read().map(|event| event.delta).sum::<Vec2>();
-                let (scroll_line_delta, scroll_pixel_delta) = scroll_events
-                    .read()
-                    .map(|event| match event.unit {
-                        MouseScrollUnit::Line => (event.y, 0.0),
-                        MouseScrollUnit::Pixel => (0.0, event.y * 0.005),
-                    })
-                    .fold((0.0, 0.0), |acc, item| {
-                        (acc.0 + item.0, acc.1 + item.1)
-                    });
-
-                // Orbit and pan
-                if orbit_pressed(orbit_controller, &mouse_input, &key_input) {
-                    orbit += mouse_delta;
-                } else if pan_pressed(
-                    orbit_controller,
-                    &mouse_input,
-                    &key_input,
-                ) {
-                    // Pan only if we're not rotating at the moment
-                    pan += mouse_delta;
+    // Collect input deltas, converting the mouse motion to logical pixels
+    let mouse_delta =
+        mouse_motion.read().map(|event| event.delta).sum::<Vec2>()
+            / active_cam.scale_factor.unwrap_or(1.0);
+    let (scroll_line_delta, scroll_pixel_delta) =
+        scroll_deltas(&mut scroll_events);
+    // Only used during this frame
+    let external = if external_input.has_input() {
+        std::mem::take(&mut *external_input)
+    } else {
+        ExternalCameraInput::default()
+    };
+
+    let mut active_state = CameraInputState::default();
+    let mut interaction = None;
+    let mut has_active_camera = false;
+    let keyboard_blocked = input_blocked.is_keyboard_blocked();
+    for (
+        entity,
+        mut input_state,
+        camera,
+        orbit_controller,
+        fly_controller,
+        pan2d_controller,
+    ) in cameras.iter_mut()
+    {
+        if active_cam.entity != Some(entity) || input_blocked.is_blocked() {
+            // Only the active camera has input, unless blocked
+            input_state.set_if_neq(CameraInputState::default());
+            continue;
+        }
+        has_active_camera = true;
+        gesture_touches
+            .update(&touches, camera.and_then(Camera::logical_viewport_rect));
+        let mut state = CameraInputState {
+            scroll_accumulator: input_state.scroll_accumulator,
+            scroll_multiplier: input_state.scroll_multiplier,
+            last_scroll_secs: input_state.last_scroll_secs,
+            ..default()
+        };
+        if let Some(orbit_controller) =
+            orbit_controller.filter(|controller| controller.is_enabled)
+        {
+            let is_orbit_pressed =
+                orbit_pressed(orbit_controller, &mouse_input, &key_input);
+            let is_pan_pressed =
+                pan_pressed(orbit_controller, &mouse_input, &key_input);
+            let is_zoom_pressed =
+                zoom_pressed(orbit_controller, &mouse_input, &key_input);
+            let mouse_delta = drag_motion(
+                &mut state,
+                &input_state,
+                is_orbit_pressed || is_pan_pressed || is_zoom_pressed,
+                orbit_just_pressed(orbit_controller, &mouse_input, &key_input)
+                    || pan_just_pressed(
+                        orbit_controller,
+                        &mouse_input,
+                        &key_input,
+                    )
+                    || zoom_just_pressed(
+                        orbit_controller,
+                        &mouse_input,
+                        &key_input,
+                    ),
+                orbit_controller.drag_start_threshold_px,
+                mouse_delta,
+            );
+
+            // Orbit and pan
+            if is_orbit_pressed {
+                state.orbit += mouse_delta;
+            } else if is_pan_pressed {
+                // Pan only if we're not rotating at the moment
+                state.pan += mouse_delta;
+            } else if is_zoom_pressed {
+                // Dragging up zooms in
+                state.zoom_drag -= mouse_delta.y * 0.005;
+            }
+
+            // Zoom
+            let mut scroll_line = scroll_line_delta.y;
+            let mut scroll_pixel = scroll_pixel_delta.y * 0.005;
+
+            // Horizontal scroll
+            match orbit_controller.horizontal_scroll_action {
+                HorizontalScrollAction::Pan => {
+                    state.pan.x += scroll_line_delta.x * SCROLL_LINE_PIXELS
+                        + scroll_pixel_delta.x;
+                }
+                HorizontalScrollAction::Zoom => {
+                    scroll_line += scroll_line_delta.x;
+                    scroll_pixel += scroll_pixel_delta.x * 0.005;
                 }
+                HorizontalScrollAction::Ignore => {}
+            }
 
-                // Zoom
-                scroll_line += scroll_line_delta;
-                scroll_pixel += scroll_pixel_delta;
+            // Zoom acceleration
+            if let Some(acceleration) = orbit_controller.zoom_acceleration {
+                let lines = (scroll_line + scroll_pixel).abs();
+                if lines > 0.0 {
+                    let now = time.elapsed_secs();
+                    let is_consecutive =
+                        state.last_scroll_secs.is_some_and(|last| {
+                            now - last <= acceleration.reset_delay
+                        });
+                    state.scroll_multiplier = if is_consecutive {
+                        (state.scroll_multiplier.max(1.0)
+                            * acceleration.factor.powf(lines))
+                        .min(acceleration.max_multiplier)
+                    } else {
+                        1.0
+                    };
+                    state.last_scroll_secs = Some(now);
+                    scroll_line *= state.scroll_multiplier;
+                    scroll_pixel *= state.scroll_multiplier;
+                }
+            }
 
-                // Other
-                if orbit_just_pressed(
-                    orbit_controller,
-                    &mouse_input,
-                    &key_input,
-                ) || orbit_just_released(
+            // Smooth scroll
+            if orbit_controller.smooth_scroll {
+                let accumulator =
+                    state.scroll_accumulator + scroll_line + scroll_pixel;
+                let mut applied = accumulator
+                    * (1.0 - (-time.delta_secs() / SMOOTH_SCROLL_TIME).exp());
+                if (accumulator - applied).abs() < 0.0001 {
+                    applied = accumulator;
+                }
+                state.scroll_accumulator = accumulator - applied;
+                scroll_line = 0.0;
+                scroll_pixel = applied;
+            } else {
+                state.scroll_accumulator = 0.0;
+            }
+            state.scroll_line += scroll_line;
+            state.scroll_pixel += scroll_pixel;
+
+            // External input
+            state.orbit += external.orbit;
+            state.pan += external.pan;
+            state.scroll_line += external.zoom;
+
+            // Other
+            if orbit_just_pressed(orbit_controller, &mouse_input, &key_input)
+                || orbit_just_released(
                     orbit_controller,
                     &mouse_input,
                     &key_input,
-                ) {
-                    orbit_button_changed = true;
+                )
+            {
+                state.orbit_button_changed = true;
+            }
+
+            // Touch gestures
+            let gestures = touch_gestures(&touches, &gesture_touches);
+            state.orbit +=
+                gestures.orbit * orbit_controller.touch_orbit_sensitivity;
+            state.pan += gestures.pan * orbit_controller.touch_pan_sensitivity;
+            state.scroll_pixel += gestures.pinch
+                * 0.005
+                * orbit_controller.touch_zoom_sensitivity;
+            state.orbit_button_changed |= gestures.just_started;
+            state.touch_position = gestures.position;
+            state.touch_just_started = gestures.just_started;
+
+            interaction = if gestures.orbit != Vec2::ZERO
+                || orbit_controller.is_orbit_pressed(&mouse_input, &key_input)
+            {
+                Some(CameraInteraction::Orbit)
+            } else if gestures.pan != Vec2::ZERO
+                || orbit_controller.is_pan_pressed(&mouse_input, &key_input)
+                || (!keyboard_blocked
+                    && key_pan_direction(orbit_controller, &key_input)
+                        != Vec2::ZERO)
+            {
+                Some(CameraInteraction::Pan)
+            } else if gestures.pinch != 0.0
+                || orbit_controller.is_zoom_pressed(&mouse_input, &key_input)
+                || (!keyboard_blocked
+                    && key_zoom_direction(orbit_controller, &key_input) != 0.0)
+            {
+                Some(CameraInteraction::Zoom)
+            } else {
+                None
+            };
+        }
+        if let Some(fly_controller) =
+            fly_controller.filter(|controller| controller.is_enabled)
+        {
+            // Rotate
+            let is_rotate_pressed =
+                rotate_pressed(fly_controller, &mouse_input, &key_input);
+            let mouse_delta = drag_motion(
+                &mut state,
+                &input_state,
+                is_rotate_pressed,
+                rotate_just_pressed(fly_controller, &mouse_input, &key_input),
+                fly_controller.drag_start_threshold_px,
+                mouse_delta,
+            );
+            if is_rotate_pressed {
+                state.rotate += mouse_delta;
+            }
+
+            // Speed
+            state.scroll_line += scroll_line_delta.y;
+            state.scroll_pixel += scroll_pixel_delta.y * 0.005;
+
+            // External input
+            state.rotate += external.rotate;
+            state.translate += external.translate;
+            state.scroll_line += external.zoom;
+
+            // Horizontal scroll, panning strafes sideways
+            match fly_controller.horizontal_scroll_action {
+                HorizontalScrollAction::Pan => {
+                    state.pan.x += scroll_line_delta.x * SCROLL_LINE_PIXELS
+                        + scroll_pixel_delta.x;
                 }
+                HorizontalScrollAction::Zoom => {
+                    state.scroll_line += scroll_line_delta.x;
+                    state.scroll_pixel += scroll_pixel_delta.x * 0.005;
+                }
+                HorizontalScrollAction::Ignore => {}
+            }
 
-                camera_movement.orbit = orbit;
-                camera_movement.pan = pan;
-                camera_movement.scroll_line = scroll_line;
-                camera_movement.scroll_pixel = scroll_pixel;
-                camera_movement.orbit_button_changed = orbit_button_changed;
-                camera_movement.rotate = rotate;
+            if fly_controller.is_rotate_pressed(&mouse_input, &key_input) {
+                interaction = Some(CameraInteraction::FlyRotate);
+            } else if !keyboard_blocked
+                && fly_controller.is_move_pressed(&key_input)
+            {
+                interaction = interaction.or(Some(CameraInteraction::FlyMove));
             }
         }
-        if let Ok(fly_controller) = fly_cameras.get(active_entity) {
-            if fly_controller.is_enabled {
-                let orbit = Vec2::ZERO;
-                let pan = Vec2::ZERO;
-                let mut scroll_line = 0.0;
-                let mut scroll_pixel = 0.0;
-                let orbit_button_changed = false;
-                let mut rotate = Vec2::ZERO;
-
-                // Collect input deltas
-                let mouse_delta =
-                    mouse_motion.read().map(|event| event.delta).sum::<Vec2>();
-                let (scroll_line_delta, scroll_pixel_delta) = scroll_events
-                    .read()
-                    .map(|event| match event.unit {
-                        MouseScrollUnit::Line => (event.y, 0.0),
-                        MouseScrollUnit::Pixel => (0.0, event.y * 0.005),
-                    })
-                    .fold((0.0, 0.0), |acc, item| {
-                        (acc.0 + item.0, acc.1 + item.1)
-                    });
-
-                // Rotate
-                if rotate_pressed(fly_controller, &mouse_input, &key_input) {
-                    rotate += mouse_delta;
-                }
+        if let Some(pan2d_controller) =
+            pan2d_controller.filter(|controller| controller.is_enabled)
+        {
+            // Pan
+            if pan2d_controller.is_pan_pressed(&mouse_input, &key_input) {
+                state.pan += mouse_delta;
+            }
 
-                // Speed
-                scroll_line += scroll_line_delta;
-                scroll_pixel += scroll_pixel_delta;
+            // Zoom
+            state.scroll_line += scroll_line_delta.y;
+            state.scroll_pixel += scroll_pixel_delta.y * 0.005;
 
-                camera_movement.orbit = orbit;
-                camera_movement.pan = pan;
-                camera_movement.scroll_line = scroll_line;
-                camera_movement.scroll_pixel = scroll_pixel;
-                camera_movement.orbit_button_changed = orbit_button_changed;
-                camera_movement.rotate = rotate;
+            // External input
+            state.pan += external.pan;
+            state.scroll_line += external.zoom;
+
+            // Horizontal scroll
+            match pan2d_controller.horizontal_scroll_action {
+                HorizontalScrollAction::Pan => {
+                    state.pan.x += scroll_line_delta.x * SCROLL_LINE_PIXELS
+                        + scroll_pixel_delta.x;
+                }
+                HorizontalScrollAction::Zoom => {
+                    state.scroll_line += scroll_line_delta.x;
+                    state.scroll_pixel += scroll_pixel_delta.x * 0.005;
+                }
+                HorizontalScrollAction::Ignore => {}
             }
+
+            // Touch gestures, the orbit gesture pans as there is no rotation
+            let gestures = touch_gestures(&touches, &gesture_touches);
+            state.pan += (gestures.orbit + gestures.pan)
+                * pan2d_controller.touch_pan_sensitivity;
+            state.scroll_pixel += gestures.pinch
+                * 0.005
+                * pan2d_controller.touch_zoom_sensitivity;
+            state.touch_position = gestures.position;
+            state.touch_just_started = gestures.just_started;
+
+            interaction = if gestures.orbit != Vec2::ZERO
+                || gestures.pan != Vec2::ZERO
+                || pan2d_controller.is_pan_pressed(&mouse_input, &key_input)
+            {
+                Some(CameraInteraction::Pan)
+            } else if gestures.pinch != 0.0 {
+                Some(CameraInteraction::Zoom)
+            } else {
+                interaction
+            };
         }
+        active_state = state.clone();
+        input_state.set_if_neq(state);
+    }
+    if !has_active_camera && !gesture_touches.0.is_empty() {
+        gesture_touches.0.clear();
+    }
+    mouse_key_tracker.0 = active_state;
+    if active_cam.interaction != interaction {
+        active_cam.interaction = interaction;
+    }
+}
+
+/// Mouse motion of a drag to apply during this frame. The motion is
+/// accumulated in `state` while its total since the drag started is below
+/// `threshold`, in logical pixels, then applied all at once so that none is
+/// lost. Without threshold, the motion is applied right away and the drag
+/// starts when the binding is just pressed.
+fn drag_motion(
+    state: &mut CameraInputState,
+    previous_state: &CameraInputState,
+    is_pressed: bool,
+    is_just_pressed: bool,
+    threshold: f32,
+    mouse_delta: Vec2,
+) -> Vec2 {
+    if !is_pressed {
+        return mouse_delta;
+    }
+    if threshold <= 0.0 {
+        state.is_drag_started = true;
+        state.drag_just_started |= is_just_pressed;
+        return mouse_delta;
+    }
+    if previous_state.is_drag_started {
+        state.is_drag_started = true;
+        return mouse_delta;
+    }
+    let pending = previous_state.pending_drag_motion + mouse_delta;
+    if pending.length() > threshold {
+        state.is_drag_started = true;
+        state.drag_just_started = true;
+        pending
+    } else {
+        state.pending_drag_motion = pending;
+        Vec2::ZERO
     }
 }
 
@@ -135,15 +1185,7 @@ pub(crate) fn orbit_pressed(
     mouse_input: &Res<ButtonInput<MouseButton>>,
     key_input: &Res<ButtonInput<KeyCode>>,
 ) -> bool {
-    let is_pressed = pan_orbit
-        .modifier_orbit
-        .map_or(true, |modifier| key_input.pressed(modifier))
-        && mouse_input.pressed(pan_orbit.button_orbit);
-
-    is_pressed
-        && pan_orbit
-            .modifier_pan
-            .map_or(true, |modifier| !key_input.pressed(modifier))
+    pan_orbit.is_orbit_pressed(mouse_input, key_input)
 }
 
 pub(crate) fn orbit_just_pressed(
@@ -151,14 +1193,7 @@ pub(crate) fn orbit_just_pressed(
     mouse_input: &Res<ButtonInput<MouseButton>>,
     key_input: &Res<ButtonInput<KeyCode>>,
 ) -> bool {
-    let just_pressed = pan_orbit
-        .modifier_orbit
-        .map_or(true, |modifier| key_input.pressed(modifier))
-        && (mouse_input.just_pressed(pan_orbit.button_orbit));
-    just_pressed
-        && pan_orbit
-            .modifier_pan
-            .map_or(true, |modifier| !key_input.pressed(modifier))
+    pan_orbit.is_orbit_just_pressed(mouse_input, key_input)
 }
 
 pub(crate) fn orbit_just_released(
@@ -166,15 +1201,7 @@ pub(crate) fn orbit_just_released(
     mouse_input: &Res<ButtonInput<MouseButton>>,
     key_input: &Res<ButtonInput<KeyCode>>,
 ) -> bool {
-    let just_released = pan_orbit
-        .modifier_orbit
-        .map_or(true, |modifier| key_input.pressed(modifier))
-        && (mouse_input.just_released(pan_orbit.button_orbit));
-
-    just_released
-        && pan_orbit
-            .modifier_pan
-            .map_or(true, |modifier| !key_input.pressed(modifier))
+    pan_orbit.is_orbit_just_released(mouse_input, key_input)
 }
 
 pub(crate) fn pan_pressed(
@@ -182,15 +1209,7 @@ pub(crate) fn pan_pressed(
     mouse_input: &Res<ButtonInput<MouseButton>>,
     key_input: &Res<ButtonInput<KeyCode>>,
 ) -> bool {
-    let is_pressed = pan_orbit
-        .modifier_pan
-        .map_or(true, |modifier| key_input.pressed(modifier))
-        && mouse_input.pressed(pan_orbit.button_pan);
-
-    is_pressed
-        && pan_orbit
-            .modifier_orbit
-            .map_or(true, |modifier| !key_input.pressed(modifier))
+    pan_orbit.is_pan_pressed(mouse_input, key_input)
 }
 
 pub(crate) fn pan_just_pressed(
@@ -198,15 +1217,7 @@ pub(crate) fn pan_just_pressed(
     mouse_input: &Res<ButtonInput<MouseButton>>,
     key_input: &Res<ButtonInput<KeyCode>>,
 ) -> bool {
-    let just_pressed = pan_orbit
-        .modifier_pan
-        .map_or(true, |modifier| key_input.pressed(modifier))
-        && (mouse_input.just_pressed(pan_orbit.button_pan));
-
-    just_pressed
-        && pan_orbit
-            .modifier_orbit
-            .map_or(true, |modifier| !key_input.pressed(modifier))
+    pan_orbit.is_pan_just_pressed(mouse_input, key_input)
 }
 
 pub(crate) fn pan_just_released(
@@ -214,15 +1225,79 @@ pub(crate) fn pan_just_released(
     mouse_input: &Res<ButtonInput<MouseButton>>,
     key_input: &Res<ButtonInput<KeyCode>>,
 ) -> bool {
-    let just_released = pan_orbit
-        .modifier_pan
-        .map_or(true, |modifier| key_input.pressed(modifier))
-        && (mouse_input.just_released(pan_orbit.button_pan));
+    pan_orbit.is_pan_just_released(mouse_input, key_input)
+}
 
-    just_released
-        && pan_orbit
-            .modifier_orbit
-            .map_or(true, |modifier| !key_input.pressed(modifier))
+pub(crate) fn zoom_pressed(
+    pan_orbit: &OrbitCameraController,
+    mouse_input: &Res<ButtonInput<MouseButton>>,
+    key_input: &Res<ButtonInput<KeyCode>>,
+) -> bool {
+    pan_orbit.is_zoom_pressed(mouse_input, key_input)
+}
+
+pub(crate) fn zoom_just_pressed(
+    pan_orbit: &OrbitCameraController,
+    mouse_input: &Res<ButtonInput<MouseButton>>,
+    key_input: &Res<ButtonInput<KeyCode>>,
+) -> bool {
+    pan_orbit.is_zoom_just_pressed(mouse_input, key_input)
+}
+
+pub(crate) fn zoom_just_released(
+    pan_orbit: &OrbitCameraController,
+    mouse_input: &Res<ButtonInput<MouseButton>>,
+    key_input: &Res<ButtonInput<KeyCode>>,
+) -> bool {
+    pan_orbit.is_zoom_just_released(mouse_input, key_input)
+}
+
+/// Direction of the keyboard panning of the camera, with x to the right and
+/// y up
+pub(crate) fn key_pan_direction(
+    pan_orbit: &OrbitCameraController,
+    key_input: &Res<ButtonInput<KeyCode>>,
+) -> Vec2 {
+    let pressed =
+        |key: Option<KeyCode>| key.is_some_and(|key| key_input.pressed(key));
+    let mut direction = Vec2::ZERO;
+    if pressed(pan_orbit.key_pan_left) {
+        direction.x -= 1.0;
+    }
+    if pressed(pan_orbit.key_pan_right) {
+        direction.x += 1.0;
+    }
+    if pressed(pan_orbit.key_pan_up) {
+        direction.y += 1.0;
+    }
+    if pressed(pan_orbit.key_pan_down) {
+        direction.y -= 1.0;
+    }
+    direction
+}
+
+/// Direction of the keyboard zooming, positive to zoom in
+pub(crate) fn key_zoom_direction(
+    pan_orbit: &OrbitCameraController,
+    key_input: &Res<ButtonInput<KeyCode>>,
+) -> f32 {
+    let pressed =
+        |key: Option<KeyCode>| key.is_some_and(|key| key_input.pressed(key));
+    let mut direction = 0.0;
+    if pressed(pan_orbit.key_zoom_in) {
+        direction += 1.0;
+    }
+    if pressed(pan_orbit.key_zoom_out) {
+        direction -= 1.0;
+    }
+    direction
+}
+
+pub(crate) fn key_pan_zoom_just_pressed(
+    pan_orbit: &OrbitCameraController,
+    key_input: &Res<ButtonInput<KeyCode>>,
+) -> bool {
+    pan_orbit.is_key_pan_zoom_just_pressed(key_input)
 }
 
 pub(crate) fn rotate_pressed(
@@ -230,10 +1305,7 @@ pub(crate) fn rotate_pressed(
     mouse_input: &Res<ButtonInput<MouseButton>>,
     key_input: &Res<ButtonInput<KeyCode>>,
 ) -> bool {
-    fly_controller
-        .modifier_rotate
-        .map_or(true, |modifier| key_input.pressed(modifier))
-        && mouse_input.pressed(fly_controller.button_rotate)
+    fly_controller.is_rotate_pressed(mouse_input, key_input)
 }
 
 pub(crate) fn rotate_just_pressed(
@@ -241,10 +1313,7 @@ pub(crate) fn rotate_just_pressed(
     mouse_input: &Res<ButtonInput<MouseButton>>,
     key_input: &Res<ButtonInput<KeyCode>>,
 ) -> bool {
-    fly_controller
-        .modifier_rotate
-        .map_or(true, |modifier| key_input.pressed(modifier))
-        && (mouse_input.just_pressed(fly_controller.button_rotate))
+    fly_controller.is_rotate_just_pressed(mouse_input, key_input)
 }
 
 pub(crate) fn rotate_just_released(
@@ -252,20 +1321,377 @@ pub(crate) fn rotate_just_released(
     mouse_input: &Res<ButtonInput<MouseButton>>,
     key_input: &Res<ButtonInput<KeyCode>>,
 ) -> bool {
-    fly_controller
-        .modifier_rotate
-        .map_or(true, |modifier| key_input.pressed(modifier))
-        && (mouse_input.just_released(fly_controller.button_rotate))
+    fly_controller.is_rotate_just_released(mouse_input, key_input)
 }
 
 pub(crate) fn move_just_pressed(
     fly_controller: &FlyCameraController,
     key_input: &Res<ButtonInput<KeyCode>>,
 ) -> bool {
-    key_input.just_pressed(fly_controller.key_move_up)
-        || key_input.just_pressed(fly_controller.key_move_down)
-        || key_input.just_pressed(fly_controller.key_move_left)
-        || key_input.just_pressed(fly_controller.key_move_right)
-        || key_input.just_pressed(fly_controller.key_move_forward)
-        || key_input.just_pressed(fly_controller.key_move_backward)
+    fly_controller.is_move_just_pressed(key_input)
+}
+
+pub(crate) fn move_pressed(
+    fly_controller: &FlyCameraController,
+    key_input: &Res<ButtonInput<KeyCode>>,
+) -> bool {
+    fly_controller.is_move_pressed(key_input)
+}
+
+/// Whether a controller may have something to handle this frame: motions or
+/// scroll in `input_state`, a key held, e.g. to pan or move, or a mouse
+/// button just pressed or released, e.g. to pick the pivot of a drag. Cheap
+/// enough to skip the idle cameras early.
+pub(crate) fn has_pending_input(
+    input_state: &CameraInputState,
+    mouse_input: &ButtonInput<MouseButton>,
+    key_input: &ButtonInput<KeyCode>,
+) -> bool {
+    input_state.has_input()
+        || input_state.orbit_button_changed
+        || input_state.touch_just_started
+        || key_input.get_pressed().next().is_some()
+        || mouse_input.get_just_pressed().next().is_some()
+        || mouse_input.get_just_released().next().is_some()
+}
+
+/// Whether the active camera may have something to handle this frame, see
+/// [`has_pending_input`]. Used by the run conditions of the controller
+/// systems, together with the changes made to the controllers.
+pub(crate) fn active_camera_has_pending_input(
+    active_cam: &ActiveCameraData,
+    input_states: &Query<&CameraInputState>,
+    mouse_input: &ButtonInput<MouseButton>,
+    key_input: &ButtonInput<KeyCode>,
+) -> bool {
+    active_cam
+        .entity
+        .and_then(|entity| input_states.get(entity).ok())
+        .is_some_and(|input_state| {
+            has_pending_input(input_state, mouse_input, key_input)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::system::RunSystemOnce,
+        render::camera::{camera_system, ManualTextureViews},
+    };
+
+    use super::*;
+    use crate::testing::test_app;
+
+    fn inputs(
+        button: Option<MouseButton>,
+        keys: &[KeyCode],
+    ) -> (ButtonInput<MouseButton>, ButtonInput<KeyCode>) {
+        let mut mouse_input = ButtonInput::default();
+        if let Some(button) = button {
+            mouse_input.press(button);
+        }
+        let mut key_input = ButtonInput::default();
+        for key in keys {
+            key_input.press(*key);
+        }
+        (mouse_input, key_input)
+    }
+
+    #[test]
+    fn exclusive_modifiers_are_forbidden_except_the_own_modifier() {
+        let binding = InputBinding::from_button_and_modifiers(
+            MouseButton::Middle,
+            Some(KeyCode::ShiftLeft),
+            &[Some(KeyCode::ShiftLeft), None, Some(KeyCode::ControlLeft)],
+        );
+        assert_eq!(binding.required_modifiers, vec![KeyCode::ShiftLeft]);
+        assert_eq!(binding.forbidden_modifiers, vec![KeyCode::ControlLeft]);
+
+        let binding = InputBinding::from_button_and_modifiers(
+            MouseButton::Left,
+            None,
+            &[None, None],
+        );
+        assert_eq!(binding, InputBinding::mouse(MouseButton::Left));
+    }
+
+    #[test]
+    fn modifiers_must_be_all_required_and_none_forbidden() {
+        let binding = InputBinding::mouse(MouseButton::Left)
+            .with_modifier(KeyCode::ShiftLeft)
+            .with_modifier(KeyCode::AltLeft)
+            .without_modifier(KeyCode::ControlLeft);
+        let matches = |keys: &[KeyCode]| {
+            let (mouse_input, key_input) =
+                inputs(Some(MouseButton::Left), keys);
+            binding.pressed(&mouse_input, &key_input)
+        };
+        assert!(!matches(&[]));
+        assert!(!matches(&[KeyCode::ShiftLeft]));
+        assert!(matches(&[KeyCode::ShiftLeft, KeyCode::AltLeft]));
+        assert!(matches(&[
+            KeyCode::ShiftLeft,
+            KeyCode::AltLeft,
+            KeyCode::KeyA
+        ]));
+        assert!(!matches(&[
+            KeyCode::ShiftLeft,
+            KeyCode::AltLeft,
+            KeyCode::ControlLeft
+        ]));
+    }
+
+    #[test]
+    fn orbit_does_not_fire_while_the_pan_or_zoom_modifier_is_held() {
+        let controller = OrbitCameraController::default();
+        let orbit = controller.orbit_bindings();
+        let pan = controller.pan_bindings();
+        let zoom = controller.zoom_bindings();
+        let active = |keys: &[KeyCode]| {
+            let (mouse_input, key_input) =
+                inputs(Some(MouseButton::Middle), keys);
+            [&orbit, &pan, &zoom].map(|bindings| {
+                bindings_pressed(bindings, None, &mouse_input, &key_input)
+            })
+        };
+        assert_eq!(active(&[]), [true, false, false]);
+        assert_eq!(active(&[KeyCode::ShiftLeft]), [false, true, false]);
+        assert_eq!(active(&[KeyCode::ControlLeft]), [false, false, true]);
+        // Both exclusive modifiers held: nothing fires rather than two
+        // actions at once
+        assert_eq!(
+            active(&[KeyCode::ShiftLeft, KeyCode::ControlLeft]),
+            [false, false, false]
+        );
+    }
+
+    #[test]
+    fn just_pressed_and_released_respect_the_modifiers() {
+        let binding = InputBinding::from_button_and_modifiers(
+            MouseButton::Middle,
+            None,
+            &[Some(KeyCode::ShiftLeft)],
+        );
+        let (mouse_input, key_input) =
+            inputs(Some(MouseButton::Middle), &[KeyCode::ShiftLeft]);
+        assert!(!binding.just_pressed(&mouse_input, &key_input));
+
+        let (mut mouse_input, key_input) =
+            inputs(Some(MouseButton::Middle), &[]);
+        assert!(binding.just_pressed(&mouse_input, &key_input));
+        mouse_input.clear();
+        mouse_input.release(MouseButton::Middle);
+        assert!(binding.just_released(&mouse_input, &key_input));
+        assert!(!binding.pressed(&mouse_input, &key_input));
+    }
+
+    /// Inputs from -50 to 50, including 0 and the values below 1
+    fn sample_inputs() -> impl Iterator<Item = f32> {
+        (-200..=200).map(|index| index as f32 * 0.25)
+    }
+
+    fn assert_keeps_sign_and_order(curve: &SensitivityCurve) {
+        let outputs = sample_inputs()
+            .map(|input| (input, curve.apply_value(input)))
+            .collect::<Vec<_>>();
+        for &(input, output) in &outputs {
+            assert_eq!(output.signum(), input.signum(), "{curve:?} {input}");
+            assert_eq!(output == 0.0, input == 0.0, "{curve:?} {input}");
+        }
+        for pair in outputs.windows(2) {
+            assert!(pair[1].1 > pair[0].1, "{curve:?} {pair:?}");
+        }
+        let motion = curve.apply_motion(Vec2::new(3.0, -4.0));
+        assert!(motion.normalize().abs_diff_eq(Vec2::new(0.6, -0.8), 1e-6));
+    }
+
+    #[test]
+    fn linear_settings_of_the_curves_are_the_identity() {
+        for curve in [
+            SensitivityCurve::Linear,
+            SensitivityCurve::Power(1.0),
+            SensitivityCurve::Table(vec![Vec2::ONE]),
+            SensitivityCurve::Table(vec![Vec2::splat(2.0), Vec2::splat(10.0)]),
+        ] {
+            for input in sample_inputs() {
+                let output = curve.apply_value(input);
+                assert!((output - input).abs() < 1e-5, "{curve:?} {input}");
+            }
+            let motion = Vec2::new(3.0, -4.0);
+            assert!(curve.apply_motion(motion).abs_diff_eq(motion, 1e-5));
+        }
+    }
+
+    #[test]
+    fn power_curve_keeps_the_sign_and_the_order() {
+        for exponent in [0.5, 1.5, 2.0] {
+            assert_keeps_sign_and_order(&SensitivityCurve::Power(exponent));
+        }
+        // Small motions damped, large ones amplified
+        let curve = SensitivityCurve::Power(2.0);
+        assert!(curve.apply_value(0.5) < 0.5);
+        assert!(curve.apply_value(-4.0) < -4.0);
+    }
+
+    #[test]
+    fn table_curve_keeps_the_sign_and_the_order() {
+        let curve = SensitivityCurve::Table(vec![
+            Vec2::new(1.0, 0.25),
+            Vec2::new(4.0, 2.0),
+            Vec2::new(10.0, 20.0),
+        ]);
+        assert_keeps_sign_and_order(&curve);
+        assert_eq!(curve.apply_value(-0.5), -0.125);
+        assert_eq!(curve.apply_value(4.0), 2.0);
+        // Past the last point, scaled like the last point
+        assert_eq!(curve.apply_value(20.0), 40.0);
+    }
+
+    /// Orbit camera and fly camera rendering to the primary window, without
+    /// an active camera
+    fn app_with_cameras() -> (App, Entity, Entity) {
+        let mut app = test_app();
+        app.init_asset::<Image>()
+            .init_resource::<ManualTextureViews>();
+        app.world_mut().spawn((Window::default(), PrimaryWindow));
+        let orbit = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 0.0, 5.0),
+                OrbitCameraController::default(),
+            ))
+            .id();
+        let fly = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 0.0, 5.0),
+                FlyCameraController::default(),
+            ))
+            .id();
+        // Compute the viewports, which are only computed by the render
+        // plugins
+        app.world_mut()
+            .run_system_once(camera_system::<Projection>)
+            .unwrap();
+        app.update();
+        (app, orbit, fly)
+    }
+
+    /// Send `input` to `camera` during one update
+    fn send_external_input(
+        app: &mut App,
+        camera: Entity,
+        input: ExternalCameraInput,
+    ) {
+        app.insert_resource(ExternalCameraInput {
+            target_camera: Some(camera),
+            ..input
+        });
+        app.update();
+        assert_eq!(
+            app.world().resource::<ActiveCameraData>().entity,
+            Some(camera)
+        );
+        assert!(!app.world().resource::<ExternalCameraInput>().has_input());
+    }
+
+    fn orbit_state(app: &App, camera: Entity) -> (Vec3, f32, f32, f32) {
+        let controller =
+            app.world().get::<OrbitCameraController>(camera).unwrap();
+        (
+            controller.focus,
+            controller.yaw.unwrap(),
+            controller.pitch.unwrap(),
+            controller.radius.unwrap(),
+        )
+    }
+
+    #[test]
+    fn external_input_moves_the_orbit_camera() {
+        let (mut app, camera, _) = app_with_cameras();
+        let (focus, yaw, pitch, radius) = orbit_state(&app, camera);
+        assert_eq!(app.world().resource::<ActiveCameraData>().entity, None);
+
+        send_external_input(
+            &mut app,
+            camera,
+            ExternalCameraInput {
+                orbit: Vec2::new(50.0, 0.0),
+                ..default()
+            },
+        );
+        let (new_focus, new_yaw, new_pitch, new_radius) =
+            orbit_state(&app, camera);
+        assert_ne!(new_yaw, yaw);
+        assert_eq!((new_focus, new_pitch, new_radius), (focus, pitch, radius));
+
+        let yaw = new_yaw;
+        send_external_input(
+            &mut app,
+            camera,
+            ExternalCameraInput {
+                pan: Vec2::new(50.0, 0.0),
+                ..default()
+            },
+        );
+        let (new_focus, new_yaw, _, new_radius) = orbit_state(&app, camera);
+        assert_ne!(new_focus, focus);
+        assert_eq!((new_yaw, new_radius), (yaw, radius));
+
+        send_external_input(
+            &mut app,
+            camera,
+            ExternalCameraInput {
+                zoom: 1.0,
+                ..default()
+            },
+        );
+        let (_, new_yaw, _, new_radius) = orbit_state(&app, camera);
+        assert!(new_radius < radius);
+        assert_eq!(new_yaw, yaw);
+    }
+
+    #[test]
+    fn external_input_moves_the_fly_camera() {
+        let (mut app, orbit, camera) = app_with_cameras();
+        // The target camera replaces the active camera
+        send_external_input(
+            &mut app,
+            orbit,
+            ExternalCameraInput {
+                orbit: Vec2::new(50.0, 0.0),
+                ..default()
+            },
+        );
+        let start = *app.world().get::<Transform>(camera).unwrap();
+        send_external_input(
+            &mut app,
+            camera,
+            ExternalCameraInput {
+                rotate: Vec2::new(50.0, 0.0),
+                ..default()
+            },
+        );
+        let rotated = *app.world().get::<Transform>(camera).unwrap();
+        assert_ne!(rotated.rotation, start.rotation);
+        assert_eq!(rotated.translation, start.translation);
+
+        send_external_input(
+            &mut app,
+            camera,
+            ExternalCameraInput {
+                translate: Vec3::NEG_Z,
+                ..default()
+            },
+        );
+        let moved = *app.world().get::<Transform>(camera).unwrap();
+        assert_eq!(moved.rotation, rotated.rotation);
+        let offset = moved.translation - rotated.translation;
+        assert!(offset.length() > 0.0);
+        assert!(offset.normalize().abs_diff_eq(*rotated.forward(), 1e-4));
+    }
 }
@@ -10,72 +10,419 @@
 //! - Frame entities into view: Allowing to do things like framing the whole
 //!   scene, or the selected objects.
 //! - Switch between orthographic and perspective camera projection
+//! - Pan/Zoom camera controls for 2D cameras, with "Zoom to mouse position"
 
+#[cfg(feature = "winit")]
+use bevy::winit::WinitWindows;
 use bevy::{
-    input::{keyboard::KeyCode, mouse::MouseWheel, ButtonInput},
+    ecs::schedule::{InternedScheduleLabel, ScheduleLabel},
+    input::{keyboard::KeyCode, mouse::MouseWheel, touch::Touch, ButtonInput},
     prelude::*,
-    render::camera::{CameraUpdateSystem, RenderTarget},
+    render::camera::{CameraUpdateSystem, RenderTarget, ScalingMode},
     transform::TransformSystem,
-    window::{CursorGrabMode, PrimaryWindow, WindowRef},
-    winit::WinitWindows,
+    window::{CursorGrabMode, PrimaryWindow, WindowFocused, WindowRef},
 };
 #[cfg(feature = "bevy_egui")]
 use bevy_egui::EguiSet;
 
+#[cfg(feature = "depth_buffer")]
+use crate::depth::{update_depth_probes_system, DepthReadbackPlugin};
 #[cfg(feature = "bevy_egui")]
-pub use crate::egui::EguiWantsFocus;
+pub use crate::egui::{
+    controller_toolbar_ui, navigation_gizmo_ui, EguiFocusConfig, EguiWantsFocus,
+};
+#[allow(deprecated)]
+pub use crate::input::MouseKeyTracker;
+#[cfg(feature = "navigation_gizmo")]
+use crate::navigation_gizmo::{
+    draw_navigation_gizmos_system, navigation_gizmo_input_system,
+};
+#[cfg(feature = "navigation_gizmo")]
+pub use crate::navigation_gizmo::{
+    NavigationGizmoConfigGroup, ShowNavigationGizmo,
+};
+#[cfg(feature = "serialize")]
+use crate::view_state::{persist_views_system, restore_persisted_views_system};
+#[cfg(feature = "serialize")]
+pub use crate::view_state::{
+    CameraViewSnapshot, PersistCameraView, PersistedCameraViews,
+    ProjectionSnapshot,
+};
 use crate::{
-    fly::fly_camera_controller_system,
-    frame::frame_system,
-    input::{mouse_key_tracker_system, MouseKeyTracker},
-    orbit::orbit_camera_controller_system,
-    viewpoints::viewpoint_system,
+    auto_rotate::auto_rotate_system,
+    bookmarks::bookmark_system,
+    cancel_drag::cancel_drag_system,
+    control_target::validate_control_targets_system,
+    default_settings::apply_default_controller_settings_system,
+    depth::DepthSamples,
+    dolly_zoom::dolly_zoom_system,
+    fly::{fly_camera_controller_system, fly_controllers_need_update},
+    frame::{
+        frame_all_cameras_system, frame_region_system, frame_system,
+        frame_under_cursor_system, invalidate_scene_bounds_system,
+    },
+    history::{view_history_event_system, view_history_system},
+    input::{
+        external_input_target_system, mouse_key_tracker_system,
+        reset_camera_input_blocked_system, validate_bindings_system,
+        GestureTouches,
+    },
+    orbit::{
+        add_missing_other_projection_system,
+        initialize_orbit_controllers_system, orbit_camera_controller_system,
+        orbit_controllers_need_update,
+    },
+    orbit_state::set_orbit_state_system,
+    pan2d::{pan2d_camera_controller_system, pan2d_controllers_need_update},
+    raycast::update_viewport_cursor_system,
+    synchronized_views::synchronize_views_system,
+    transition::view_transition_system,
+    viewpoints::{align_view_to_surface_system, viewpoint_system},
 };
 pub use crate::{
-    fly::FlyCameraController,
-    frame::FrameEvent,
-    orbit::OrbitCameraController,
-    viewpoints::{Viewpoint, ViewpointEvent},
+    auto_rotate::{AutoRotate, AutoRotateDirection},
+    bookmarks::{
+        CameraBookmarks, RestoreViewBookmark, SaveViewBookmark, ViewBookmark,
+    },
+    control_target::ControlTarget,
+    default_settings::{
+        spawn_blendy_camera, ApplyDefaultControllerSettings,
+        DefaultControllerSettings,
+    },
+    dolly_zoom::{DollyZoomCompleted, DollyZoomEvent},
+    fly::{FlyCameraController, FlyRotationMode, TranslationFrame},
+    frame::{
+        FrameAllCamerasEvent, FrameEvent, FrameMode, FrameRegionEvent,
+        FrameSettings, FrameUnderCursorEvent, FrameUnderCursorTarget,
+        FramedBounds, FramingCompleted, FramingFailed, FramingRoot,
+        IgnoreWhenFraming, SceneBounds,
+    },
+    history::{ViewHistory, ViewHistoryRedo, ViewHistoryUndo},
+    hotkeys::{BlendyCamerasHotkeysPlugin, FrameTarget, HotkeyConfig},
+    input::{
+        BindingAction, BindingConflict, BindingConflictEvent,
+        CameraInputBlocked, CameraInputState, ExternalCameraInput,
+        HorizontalScrollAction, InputBinding, InputTrigger, SensitivityCurve,
+        ZoomAcceleration,
+    },
+    orbit::{
+        AutoDepthSource, CursorWrapMode, OrbitCameraController,
+        OrbitPivotOverride, OrthoPlacement, PanMode, ZoomMode,
+    },
+    orbit_state::{OrbitStateError, SetOrbitStateEvent, SetOrbitStateFailed},
+    pan2d::Pan2dCameraController,
+    raycast::{CursorHit, RaycastExclude, RaycastIncludeOnly, ViewportCursor},
+    synchronized_views::SynchronizedViews,
+    viewpoints::{
+        AlignViewError, AlignViewToSurfaceEvent, AlignViewToSurfaceFailed,
+        IsoCorner, SnapToNearestViewpointEvent, Viewpoint,
+        ViewpointDetectionConfig, ViewpointEvent,
+    },
 };
 
+mod auto_rotate;
+mod bookmarks;
+mod cancel_drag;
+mod control_target;
+mod default_settings;
+mod depth;
+mod dolly_zoom;
 #[cfg(feature = "bevy_egui")]
 mod egui;
 mod fly;
 mod frame;
+mod history;
+mod hotkeys;
 mod input;
+/// Orbit math used by the [`OrbitCameraController`], to place cameras
+/// consistently with it from custom code, e.g. cutscenes
+pub mod math;
+#[cfg(feature = "navigation_gizmo")]
+mod navigation_gizmo;
 mod orbit;
-/// Raycast utilities
+mod orbit_state;
+mod pan2d;
+/// Raycast utilities, based on the `MeshRayCast` system parameter of
+/// `bevy_picking`, used on demand for the auto-depth, the zoom to the mouse
+/// position and the framing under the cursor
 pub mod raycast;
+mod synchronized_views;
+/// Helpers to drive the camera controllers deterministically in tests,
+/// without windows or input devices
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod transition;
 mod utils;
+#[cfg(feature = "serialize")]
+mod view_state;
 mod viewpoints;
 
 /// Event to switch between perspective and ortographic camera projections
-#[derive(Event)]
+#[derive(Event, Reflect)]
 pub struct SwitchProjection {
     /// The camera entity for switch to change the view projection
     pub camera_entity: Entity,
 }
 
 /// Event to enable the [`OrbitCameraController`] and disable the
-/// [`FlyCameraController`] if present
-#[derive(Event)]
+/// [`FlyCameraController`] if present. The [`OrbitCameraController`] is
+/// added with default settings if missing.
+#[derive(Event, Reflect)]
 pub struct SwitchToOrbitController {
     /// The camera entity to switch to pan/orbit/zoom control mode
     pub camera_entity: Entity,
 }
 
 /// Event to enable the [`FlyCameraController`] and disable the
-/// [`OrbitCameraController`] if present
-#[derive(Event)]
+/// [`OrbitCameraController`] if present. The [`FlyCameraController`] is
+/// added with default settings if missing.
+#[derive(Event, Reflect)]
 pub struct SwitchToFlyController {
     /// The camera entity to switch to fly control mode
     pub camera_entity: Entity,
 }
 
+/// Controller mode to switch to with [`SetCameraControllerEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum ControllerMode {
+    /// Enable the [`OrbitCameraController`] and disable the
+    /// [`FlyCameraController`]
+    Orbit,
+    /// Enable the [`FlyCameraController`] and disable the
+    /// [`OrbitCameraController`]
+    Fly,
+    /// Switch to the controller that is currently disabled
+    Toggle,
+}
+
+/// Event to switch the controller of a camera. This is the same as sending
+/// [`SwitchToOrbitController`] or [`SwitchToFlyController`] depending on
+/// `mode`.
+#[derive(Event, Reflect)]
+pub struct SetCameraControllerEvent {
+    /// The camera entity to switch the controller of
+    pub camera_entity: Entity,
+    /// The controller mode to switch to
+    pub mode: ControllerMode,
+}
+
+/// Event sent after the projection of a camera was switched, either by a
+/// [`SwitchProjection`] event or automatically
+#[derive(Event, Debug, Clone, Copy, Reflect)]
+pub struct ProjectionSwitched {
+    /// The camera entity whose projection was switched
+    pub camera_entity: Entity,
+    /// Whether the new projection is orthographic
+    pub now_orthographic: bool,
+}
+
+impl ProjectionSwitched {
+    pub(crate) fn new(camera_entity: Entity, projection: &Projection) -> Self {
+        Self {
+            camera_entity,
+            now_orthographic: matches!(projection, Projection::Orthographic(_)),
+        }
+    }
+}
+
+/// What caused a [`CameraViewChanged`] event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum ViewChangeCause {
+    /// The user orbited the camera
+    UserOrbit,
+    /// The user panned the camera
+    UserPan,
+    /// The user zoomed the camera
+    UserZoom,
+    /// The user moved or rotated the camera in fly mode
+    FlyMove,
+    /// A [`ViewpointEvent`] or a [`SnapToNearestViewpointEvent`] was handled
+    ViewpointEvent,
+    /// A [`FrameEvent`] was handled
+    FrameEvent,
+    /// The values of the [`OrbitCameraController`] were applied with
+    /// `force_update`, e.g. during an animated transition
+    Forced,
+}
+
+/// Event sent when a controller, or the handling of an event, actually
+/// changes the view of a camera. Sent at most once per frame and per cause
+/// for each camera.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct CameraViewChanged {
+    /// The camera entity whose view changed
+    pub camera_entity: Entity,
+    /// What changed the view
+    pub cause: ViewChangeCause,
+    /// Rotation in radian around the global Y axis
+    pub yaw: f32,
+    /// Rotation in radian around a global horizontal axis perpendicular to
+    /// the view direction
+    pub pitch: f32,
+    /// The distance between the camera and the `focus`, or the scale of the
+    /// projection if orthographic
+    pub radius: f32,
+    /// The point the camera looks at
+    pub focus: Vec3,
+}
+
+impl CameraViewChanged {
+    pub(crate) fn new(
+        camera_entity: Entity,
+        cause: ViewChangeCause,
+        view: &ViewBookmark,
+    ) -> Self {
+        Self {
+            camera_entity,
+            cause,
+            yaw: view.yaw,
+            pitch: view.pitch,
+            radius: view.radius,
+            focus: view.focus,
+        }
+    }
+
+    /// Event for the change from `pre_view` to `view`, if they differ
+    pub(crate) fn between(
+        camera_entity: Entity,
+        cause: ViewChangeCause,
+        pre_view: Option<ViewBookmark>,
+        view: Option<ViewBookmark>,
+    ) -> Option<Self> {
+        view.filter(|view| pre_view.as_ref() != Some(view))
+            .map(|view| Self::new(camera_entity, cause, &view))
+    }
+}
+
 /// Component that contains the saved camera projection (orthographic,
-/// perspective) to be switched to when switching camera projection
+/// perspective) to be switched to when switching camera projection.
+/// It is added along the [`OrbitCameraController`], derived from the camera
+/// [`Projection`], and re-derived when the [`Projection`] component is
+/// replaced by one of the same kind.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct OtherProjection(Projection);
+
+impl OtherProjection {
+    /// Projection to switch to from `projection`, keeping its clipping planes
+    /// where it makes sense. The orthographic projection is created from
+    /// `config`.
+    pub(crate) fn default_for(
+        projection: &Projection,
+        config: &DefaultOrthographicConfig,
+    ) -> Self {
+        Self(match projection {
+            Projection::Perspective(p) => {
+                Projection::Orthographic(OrthographicProjection {
+                    scaling_mode: config.scaling_mode,
+                    near: config.near,
+                    far: config.far.unwrap_or(p.far),
+                    ..OrthographicProjection::default_3d()
+                })
+            }
+            // The near plane of an orthographic projection is often zero or
+            // negative, which is not valid for a perspective projection
+            Projection::Orthographic(p) => {
+                Projection::Perspective(PerspectiveProjection {
+                    far: p.far,
+                    ..default()
+                })
+            }
+        })
+    }
+
+    /// The projection that is not active and will be used on the next
+    /// projection switch
+    pub fn get(&self) -> &Projection {
+        &self.0
+    }
+
+    /// Mutable access to the projection that is not active, to tweak it
+    /// before switching
+    pub fn get_mut(&mut self) -> &mut Projection {
+        &mut self.0
+    }
+
+    /// Replace the projection that is not active. It should be of the other
+    /// kind than the active camera [`Projection`].
+    pub fn set(&mut self, projection: Projection) {
+        self.0 = projection;
+    }
+}
+
+/// Settings of the orthographic projection created for the cameras with a
+/// perspective projection, to be switched to with [`SwitchProjection`]. Only
+/// used when the [`OtherProjection`] is created, edit it to change the
+/// projection of a camera afterward.
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct DefaultOrthographicConfig {
+    /// How the orthographic projection scales with the viewport. The
+    /// framing at the focus is kept when switching projection whatever the
+    /// mode. Defaults to a fixed vertical size of `1.0`, in which case the
+    /// scale is the height of the view.
+    pub scaling_mode: ScalingMode,
+    /// Near clipping plane. Defaults to `0.0`.
+    pub near: f32,
+    /// Far clipping plane, the one of the perspective projection if `None`.
+    /// Defaults to `None`.
+    pub far: Option<f32>,
+}
+
+impl Default for DefaultOrthographicConfig {
+    fn default() -> Self {
+        Self {
+            scaling_mode: ScalingMode::FixedVertical {
+                viewport_height: 1.0,
+            },
+            near: 0.0,
+            far: None,
+        }
+    }
+}
+
+/// Re-derive the [`OtherProjection`] when the camera [`Projection`] is
+/// replaced by one of the same kind, which would otherwise desynchronize the
+/// pair
+fn projection_replaced_observer(
+    trigger: Trigger<OnInsert, Projection>,
+    mut query: Query<(&Projection, &mut OtherProjection)>,
+    config: Res<DefaultOrthographicConfig>,
+) {
+    let Ok((projection, mut other_projection)) =
+        query.get_mut(trigger.entity())
+    else {
+        return;
+    };
+    let is_same_kind = matches!(
+        (projection, &other_projection.0),
+        (Projection::Perspective(_), Projection::Perspective(_))
+            | (Projection::Orthographic(_), Projection::Orthographic(_))
+    );
+    if is_same_kind {
+        *other_projection = OtherProjection::default_for(projection, &config);
+    }
+}
+
+/// Marker component freezing a camera: it is never selected as the active
+/// camera and the plugin never changes it, neither to handle the input and
+/// the events nor to initialize the controllers. Unlike the `is_enabled`
+/// field of the controllers, this affects everything the plugin does with the
+/// camera. Removing the marker resumes where things left off.
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[reflect(Component, Default)]
+pub struct BlendyCamerasDisabled;
+
+/// Marker for cameras whose projection was automatically switched to
+/// orthographic when reaching an axis viewpoint, see
+/// [`OrbitCameraController::auto_ortho_on_viewpoint`]
 #[derive(Component)]
-pub(crate) struct OtherProjection(Projection);
+pub(crate) struct AutoOrthographic;
 
 /// System set to allow ordering
 #[derive(Debug, Clone, Copy, SystemSet, PartialEq, Eq, Hash)]
@@ -83,75 +430,401 @@ pub enum BlendyCamerasSystemSet {
     /// Check if egui has the focus
     #[cfg(feature = "bevy_egui")]
     CheckEguiWantsFocus,
-    /// Process the input and check which camera is active
+    /// Process the input and check which camera is active, unless the input
+    /// is blocked with [`CameraInputBlocked`]
     ProcessInput,
     /// Handle the [`SwitchProjection`], [`SwitchToOrbitController`],
-    /// [`SwitchToFlyController`], [`ViewpointEvent`] and [`FrameEvent`]
-    /// events
+    /// [`SwitchToFlyController`], [`SetCameraControllerEvent`],
+    /// [`ViewpointEvent`],
+    /// [`SnapToNearestViewpointEvent`], [`FrameEvent`],
+    /// [`FrameAllCamerasEvent`], [`FrameRegionEvent`] and
+    /// [`FrameUnderCursorEvent`] events
     HandleEvents,
     /// Handle the [`OrbitCameraController`] and [`FlyCameraController`] only
-    /// if egui has not the focus
+    /// if egui has not the focus, and animate the view transitions
     Controllers,
 }
 
-/// Bevy pluging that contains all the systems necessarty to this crate
-pub struct BlendyCamerasPlugin;
+/// Bevy pluging that contains all the systems necessarty to this crate. By
+/// default, all the systems run in `PostUpdate`.
+#[derive(Debug, Clone)]
+pub struct BlendyCamerasPlugin {
+    /// Schedule of the [`BlendyCamerasSystemSet::ProcessInput`] set
+    pub process_input_schedule: InternedScheduleLabel,
+    /// Schedule of the [`BlendyCamerasSystemSet::HandleEvents`] set
+    pub handle_events_schedule: InternedScheduleLabel,
+    /// Schedule of the [`BlendyCamerasSystemSet::Controllers`] set. The
+    /// controllers run before the camera and transform updates when this is
+    /// `PostUpdate`.
+    pub controllers_schedule: InternedScheduleLabel,
+}
+
+impl Default for BlendyCamerasPlugin {
+    fn default() -> Self {
+        Self {
+            process_input_schedule: PostUpdate.intern(),
+            handle_events_schedule: PostUpdate.intern(),
+            controllers_schedule: PostUpdate.intern(),
+        }
+    }
+}
+
+impl BlendyCamerasPlugin {
+    /// Run all the systems in `schedule`
+    pub fn in_schedule(self, schedule: impl ScheduleLabel) -> Self {
+        let schedule = schedule.intern();
+        Self {
+            process_input_schedule: schedule,
+            handle_events_schedule: schedule,
+            controllers_schedule: schedule,
+        }
+    }
+
+    /// Run the [`BlendyCamerasSystemSet::ProcessInput`] set in `schedule`
+    pub fn with_process_input_schedule(
+        mut self,
+        schedule: impl ScheduleLabel,
+    ) -> Self {
+        self.process_input_schedule = schedule.intern();
+        self
+    }
+
+    /// Run the [`BlendyCamerasSystemSet::HandleEvents`] set in `schedule`
+    pub fn with_handle_events_schedule(
+        mut self,
+        schedule: impl ScheduleLabel,
+    ) -> Self {
+        self.handle_events_schedule = schedule.intern();
+        self
+    }
+
+    /// Run the [`BlendyCamerasSystemSet::Controllers`] set in `schedule`
+    pub fn with_controllers_schedule(
+        mut self,
+        schedule: impl ScheduleLabel,
+    ) -> Self {
+        self.controllers_schedule = schedule.intern();
+        self
+    }
+}
 
 impl Plugin for BlendyCamerasPlugin {
+    #[allow(deprecated)]
     fn build(&self, app: &mut App) {
         app.init_resource::<ActiveCameraData>()
+            .init_resource::<BlendyCamerasConfig>()
+            .init_resource::<FrameSettings>()
+            .init_resource::<ViewpointDetectionConfig>()
+            .init_resource::<DefaultOrthographicConfig>()
             .init_resource::<MouseKeyTracker>()
+            .init_resource::<CameraInputBlocked>()
+            .init_resource::<ExternalCameraInput>()
+            .init_resource::<DepthSamples>()
+            .init_resource::<GestureTouches>()
             .add_event::<SwitchProjection>()
+            .add_event::<ProjectionSwitched>()
             .add_event::<SwitchToOrbitController>()
             .add_event::<SwitchToFlyController>()
+            .add_event::<SetCameraControllerEvent>()
             .add_event::<ViewpointEvent>()
+            .add_event::<SnapToNearestViewpointEvent>()
+            .add_event::<AlignViewToSurfaceEvent>()
+            .add_event::<AlignViewToSurfaceFailed>()
             .add_event::<FrameEvent>()
+            .add_event::<FrameAllCamerasEvent>()
+            .add_event::<FrameRegionEvent>()
+            .add_event::<FrameUnderCursorEvent>()
+            .add_event::<FramingCompleted>()
+            .add_event::<DollyZoomEvent>()
+            .add_event::<DollyZoomCompleted>()
+            .add_event::<BindingConflictEvent>()
+            .add_event::<CameraViewChanged>()
+            .add_event::<SaveViewBookmark>()
+            .add_event::<RestoreViewBookmark>()
+            .add_event::<ViewHistoryUndo>()
+            .add_event::<ViewHistoryRedo>()
+            .add_event::<ApplyDefaultControllerSettings>()
+            .add_event::<SetOrbitStateEvent>()
+            .add_event::<SetOrbitStateFailed>()
+            .register_type::<OrbitCameraController>()
+            .register_type::<FlyCameraController>()
+            .register_type::<Pan2dCameraController>()
+            .register_type::<OtherProjection>()
+            .register_type::<DefaultOrthographicConfig>()
+            .register_type::<BlendyCamerasDisabled>()
+            .register_type::<BlendyCamerasConfig>()
+            .register_type::<FrameSettings>()
+            .register_type::<ViewpointDetectionConfig>()
+            .register_type::<IgnoreWhenFraming>()
+            .register_type::<FramingRoot>()
+            .register_type::<RaycastExclude>()
+            .register_type::<RaycastIncludeOnly>()
+            .register_type::<ViewportCursor>()
+            .register_type::<OrbitPivotOverride>()
+            .register_type::<SynchronizedViews>()
+            .register_type::<Viewpoint>()
+            .register_type::<CameraBookmarks>()
+            .register_type::<ViewHistory>()
+            .register_type::<SwitchProjection>()
+            .register_type::<ProjectionSwitched>()
+            .register_type::<SwitchToOrbitController>()
+            .register_type::<SwitchToFlyController>()
+            .register_type::<SetCameraControllerEvent>()
+            .register_type::<ViewpointEvent>()
+            .register_type::<SnapToNearestViewpointEvent>()
+            .register_type::<AlignViewToSurfaceEvent>()
+            .register_type::<AlignViewToSurfaceFailed>()
+            .register_type::<FrameEvent>()
+            .register_type::<FrameAllCamerasEvent>()
+            .register_type::<FrameRegionEvent>()
+            .register_type::<FrameUnderCursorEvent>()
+            .register_type::<FramingCompleted>()
+            .register_type::<DollyZoomEvent>()
+            .register_type::<DollyZoomCompleted>()
+            .register_type::<BindingConflictEvent>()
+            .register_type::<CameraViewChanged>()
+            .register_type::<SaveViewBookmark>()
+            .register_type::<RestoreViewBookmark>()
+            .register_type::<ViewHistoryUndo>()
+            .register_type::<ViewHistoryRedo>()
+            .register_type::<DefaultControllerSettings>()
+            .register_type::<ApplyDefaultControllerSettings>()
+            .register_type::<SetOrbitStateEvent>()
+            .register_type::<SetOrbitStateFailed>()
+            .register_type::<ExternalCameraInput>()
+            .add_observer(projection_replaced_observer)
             .add_systems(
-                PostUpdate,
+                self.process_input_schedule,
                 (
-                    active_viewport_data_system.run_if(
-                        |active_cam: Res<ActiveCameraData>| !active_cam.manual,
-                    ),
-                    (mouse_key_tracker_system, wrap_grab_center_cursor_system),
+                    clear_removed_active_camera_system,
+                    active_viewport_data_system
+                        .run_if(|active_cam: Res<ActiveCameraData>| {
+                            !active_cam.manual
+                        })
+                        .run_if(is_plugin_enabled),
+                    cancel_drag_system.run_if(is_plugin_enabled),
+                    external_input_target_system.run_if(is_plugin_enabled),
+                    (
+                        mouse_key_tracker_system.run_if(is_plugin_enabled),
+                        // Also runs when disabled to release the cursor
+                        wrap_grab_center_cursor_system,
+                    )
+                        .chain(),
                 )
                     .chain()
                     .in_set(BlendyCamerasSystemSet::ProcessInput),
             )
             .add_systems(
-                PostUpdate,
+                self.handle_events_schedule,
                 (
                     switch_camera_projection_system,
                     (
-                        switch_to_fly_camera_controller_system,
-                        switch_to_orbit_camera_controller_system,
+                        set_camera_controller_system,
+                        (
+                            switch_to_fly_camera_controller_system,
+                            switch_to_orbit_camera_controller_system,
+                        ),
                     )
+                        .chain()
                         .after(switch_camera_projection_system),
                     viewpoint_system,
-                    frame_system,
+                    align_view_to_surface_system,
+                    bookmark_system,
+                    view_history_event_system,
+                    (
+                        frame_under_cursor_system,
+                        frame_all_cameras_system,
+                        frame_system,
+                    )
+                        .chain(),
+                    frame_region_system,
+                    dolly_zoom_system,
+                    apply_default_controller_settings_system,
+                    set_orbit_state_system,
                 )
                     .in_set(BlendyCamerasSystemSet::HandleEvents)
-                    .after(BlendyCamerasSystemSet::ProcessInput),
+                    .after(BlendyCamerasSystemSet::ProcessInput)
+                    .run_if(should_handle_events),
             )
             .add_systems(
-                PostUpdate,
-                (orbit_camera_controller_system, fly_camera_controller_system)
+                self.controllers_schedule,
+                (
+                    initialize_orbit_controllers_system,
+                    view_transition_system,
+                    auto_rotate_system,
+                    (
+                        // Skipped entirely while all the cameras are idle
+                        orbit_camera_controller_system
+                            .run_if(orbit_controllers_need_update),
+                        fly_camera_controller_system
+                            .run_if(fly_controllers_need_update),
+                        pan2d_camera_controller_system
+                            .run_if(pan2d_controllers_need_update),
+                    ),
+                    synchronize_views_system,
+                )
+                    .chain()
                     .in_set(BlendyCamerasSystemSet::Controllers)
                     .after(BlendyCamerasSystemSet::HandleEvents)
+                    .run_if(is_plugin_enabled),
+            )
+            .add_systems(
+                self.handle_events_schedule,
+                // Also runs when the events are not handled, not to miss any
+                // change
+                invalidate_scene_bounds_system
+                    .before(BlendyCamerasSystemSet::HandleEvents)
+                    .run_if(resource_exists::<SceneBounds>),
+            )
+            .add_systems(
+                self.process_input_schedule,
+                (
+                    validate_bindings_system,
+                    validate_control_targets_system,
+                    add_missing_other_projection_system,
+                )
+                    .before(BlendyCamerasSystemSet::ProcessInput),
+            )
+            .add_systems(
+                self.process_input_schedule,
+                update_viewport_cursor_system
+                    .after(BlendyCamerasSystemSet::ProcessInput)
+                    .run_if(is_plugin_enabled),
+            )
+            .add_systems(
+                self.controllers_schedule,
+                (
+                    reset_camera_input_blocked_system,
+                    view_history_system.run_if(is_plugin_enabled),
+                )
+                    .after(BlendyCamerasSystemSet::Controllers),
+            );
+        if self.controllers_schedule == PostUpdate.intern() {
+            app.configure_sets(
+                PostUpdate,
+                BlendyCamerasSystemSet::Controllers
                     .before(CameraUpdateSystem)
                     .before(TransformSystem::TransformPropagate),
             );
-        #[cfg(feature = "bevy_egui")]
+        }
+        #[cfg(feature = "depth_buffer")]
         {
-            app.init_resource::<EguiWantsFocus>().add_systems(
-                PreUpdate,
-                egui::check_egui_wants_focus
-                    .in_set(BlendyCamerasSystemSet::CheckEguiWantsFocus)
-                    .after(EguiSet::BeginPass),
+            app.add_plugins(DepthReadbackPlugin).add_systems(
+                self.process_input_schedule,
+                update_depth_probes_system
+                    .after(BlendyCamerasSystemSet::ProcessInput)
+                    .run_if(is_plugin_enabled),
             );
         }
+        #[cfg(feature = "bevy_egui")]
+        {
+            app.init_resource::<EguiWantsFocus>()
+                .init_resource::<EguiFocusConfig>()
+                .register_type::<EguiFocusConfig>()
+                .add_systems(
+                    PreUpdate,
+                    egui::check_egui_wants_focus
+                        .in_set(BlendyCamerasSystemSet::CheckEguiWantsFocus)
+                        .after(EguiSet::BeginPass),
+                );
+        }
+        #[cfg(feature = "serialize")]
+        {
+            app.register_type::<PersistCameraView>()
+                .register_type::<PersistedCameraViews>()
+                .add_systems(
+                    self.handle_events_schedule,
+                    restore_persisted_views_system
+                        .in_set(BlendyCamerasSystemSet::HandleEvents)
+                        .after(BlendyCamerasSystemSet::ProcessInput)
+                        .run_if(should_handle_events),
+                )
+                .add_systems(
+                    self.controllers_schedule,
+                    persist_views_system
+                        .after(BlendyCamerasSystemSet::Controllers)
+                        .run_if(is_plugin_enabled),
+                );
+        }
+        #[cfg(feature = "navigation_gizmo")]
+        {
+            app.init_gizmo_group::<NavigationGizmoConfigGroup>()
+                .register_type::<ShowNavigationGizmo>()
+                .add_systems(
+                    self.process_input_schedule,
+                    navigation_gizmo_input_system
+                        .before(BlendyCamerasSystemSet::ProcessInput)
+                        .run_if(is_plugin_enabled),
+                )
+                .add_systems(
+                    PostUpdate,
+                    draw_navigation_gizmos_system
+                        .after(TransformSystem::TransformPropagate),
+                );
+        }
+    }
+}
+
+/// Global configuration of [`BlendyCamerasPlugin`]
+#[derive(Resource, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Resource, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct BlendyCamerasConfig {
+    /// Handle the input and move the cameras if `true`. Set to `false` to
+    /// switch off the camera navigation, e.g. while the game is paused or a
+    /// modal dialog is open.
+    pub enabled: bool,
+    /// Keep handling the events, like [`ViewpointEvent`] or [`FrameEvent`],
+    /// while `enabled` is `false`. Otherwise the events sent while disabled
+    /// are dropped. Animated transitions only progress while enabled.
+    pub handle_events_while_disabled: bool,
+    /// When the camera under the cursor becomes the active camera
+    pub activation_mode: ActivationMode,
+}
+
+impl Default for BlendyCamerasConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            handle_events_while_disabled: false,
+            activation_mode: ActivationMode::OnInput,
+        }
     }
 }
 
+/// When the camera under the cursor becomes the active camera, see
+/// [`BlendyCamerasConfig::activation_mode`]. A drag always keeps controlling
+/// the camera it started on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum ActivationMode {
+    /// When a button or key of a controller is pressed, or when scrolling
+    /// or touching the viewport
+    #[default]
+    OnInput,
+    /// Every frame, so that e.g. the keys of the fly controller move the
+    /// camera under the cursor without clicking its viewport first. The last
+    /// hovered camera stays active when the cursor leaves the viewports.
+    OnHover,
+}
+
+fn is_plugin_enabled(config: Res<BlendyCamerasConfig>) -> bool {
+    config.enabled
+}
+
+fn should_handle_events(config: Res<BlendyCamerasConfig>) -> bool {
+    config.enabled || config.handle_events_while_disabled
+}
+
 /// Tracks which `PanOrbitCamera` is active (should handle input events),
 /// along with the window and viewport dimensions, which are used for scaling
 /// mouse motion.
@@ -176,6 +849,10 @@ pub struct ActiveCameraData {
     /// `PanOrbitCamera::orbit_sensitivity` to adjust the sensitivity if
     /// required.
     pub window_size: Option<Vec2>,
+    /// The scale factor of the window, used to convert the mouse motion to
+    /// logical pixels so that the sensitivity does not depend on the scale
+    /// factor of the window. `None` is the same as a scale factor of 1.
+    pub scale_factor: Option<f32>,
     /// Indicates to `BevyCamerasPlugin` that it should not update/overwrite
     /// this resource. If you are manually updating this resource you should
     /// set this to `true`. Note that setting this to `true` will effectively
@@ -186,6 +863,73 @@ pub struct ActiveCameraData {
     /// or wrap around the cursor while controlling the camera with mouse
     /// movements.
     pub window_entity: Option<Entity>,
+    /// What the user is doing with the active camera, if anything. Updated
+    /// by `BlendyCamerasPlugin` even if `manual` is `true`.
+    pub interaction: Option<CameraInteraction>,
+}
+
+impl ActiveCameraData {
+    /// Make `entity` the active camera, with the viewport size of `camera`
+    /// and the size of `window`, and set `manual` to `true` so that the
+    /// resource is not overwritten. The window entity is only known if the
+    /// camera renders to a window given by its entity, set `window_entity`
+    /// otherwise to grab or wrap the cursor.
+    ///
+    /// For example, to control a camera rendering to a texture displayed in
+    /// the window:
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_blendy_cameras::ActiveCameraData;
+    /// fn activate_texture_camera(
+    ///     mut active_cam: ResMut<ActiveCameraData>,
+    ///     cameras: Query<(Entity, &Camera)>,
+    ///     window: Single<&Window>,
+    /// ) {
+    ///     if let Ok((entity, camera)) = cameras.get_single() {
+    ///         *active_cam =
+    ///             ActiveCameraData::for_camera(entity, camera, &window);
+    ///     }
+    /// }
+    /// ```
+    pub fn for_camera(
+        entity: Entity,
+        camera: &Camera,
+        window: &Window,
+    ) -> Self {
+        let window_entity = match camera.target {
+            RenderTarget::Window(WindowRef::Entity(window_entity)) => {
+                Some(window_entity)
+            }
+            _ => None,
+        };
+        Self {
+            entity: Some(entity),
+            viewport_size: camera.logical_viewport_size(),
+            window_size: Some(Vec2::new(window.width(), window.height())),
+            scale_factor: Some(window.scale_factor()),
+            manual: true,
+            window_entity,
+            interaction: None,
+        }
+    }
+}
+
+/// What the user is doing with the active camera, see
+/// [`ActiveCameraData::interaction`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraInteraction {
+    /// Orbiting with [`OrbitCameraController`]
+    Orbit,
+    /// Panning with [`OrbitCameraController`]
+    Pan,
+    /// Zooming by dragging or with the keyboard with
+    /// [`OrbitCameraController`]
+    Zoom,
+    /// Rotating with [`FlyCameraController`]
+    FlyRotate,
+    /// Moving with the keyboard with [`FlyCameraController`]
+    FlyMove,
 }
 
 // TODO: Rename
@@ -195,6 +939,26 @@ fn get_window_if_cursor_in_camera_viewport<'q>(
     primary_window: &'q Query<(Entity, &Window), With<PrimaryWindow>>,
     other_windows: &'q Query<(Entity, &Window), Without<PrimaryWindow>>,
 ) -> Option<(Entity, &'q Window)> {
+    get_input_position_in_camera_viewport(
+        camera,
+        touches.map(|touches| &**touches),
+        primary_window,
+        other_windows,
+    )
+    .map(|(window_entity, window, _)| (window_entity, window))
+}
+
+/// The window of the camera and the position, relative to the viewport of
+/// the camera, of the cursor or of a touch that just began in this viewport
+fn get_input_position_in_camera_viewport<'q>(
+    camera: &Camera,
+    touches: Option<&Touches>,
+    primary_window: &'q Query<(Entity, &Window), With<PrimaryWindow>>,
+    other_windows: &'q Query<(Entity, &Window), Without<PrimaryWindow>>,
+) -> Option<(Entity, &'q Window, Vec2)> {
+    if !is_camera_selectable(camera) {
+        return None;
+    }
     // First check if cursor is in the same window as this camera
     if let RenderTarget::Window(win_ref) = camera.target {
         let Some((window_entity, window)) = (match win_ref {
@@ -210,35 +974,50 @@ fn get_window_if_cursor_in_camera_viewport<'q>(
         // `window.cursor_position()` to return a `Some` value even if
         // the cursor is not in this window, in very specific cases.
         // See: https://github.com/Plonq/bevy_panorbit_camera/issues/22
-        if let Some(input_position) = window.cursor_position().or(touches
-            .map_or_else(
-                || None,
-                |v| {
-                    v.iter_just_pressed()
-                        .collect::<Vec<_>>()
-                        .first()
-                        .map(|touch| touch.position())
-                },
-            ))
-        {
-            // Now check if cursor is within this camera's viewport
-            if let Some(Rect { min, max }) = camera.logical_viewport_rect() {
-                // Window coordinates have Y starting at the bottom, so
-                // we need to reverse the y component before comparing
-                // with the viewport rect
-                let cursor_in_vp = input_position.x > min.x
-                    && input_position.x < max.x
-                    && input_position.y > min.y
-                    && input_position.y < max.y;
-                if cursor_in_vp {
-                    return Some((window_entity, window));
-                }
+        // Any touch that just began can activate the camera, even when
+        // other fingers are already down
+        let mut input_positions = window.cursor_position().into_iter().chain(
+            touches
+                .into_iter()
+                .flat_map(Touches::iter_just_pressed)
+                .map(Touch::position),
+        );
+        // Now check if cursor is within this camera's viewport
+        if let Some(Rect { min, max }) = camera.logical_viewport_rect() {
+            let position_in_vp = input_positions.find(|position| {
+                position.x > min.x
+                    && position.x < max.x
+                    && position.y > min.y
+                    && position.y < max.y
+            });
+            if let Some(position) = position_in_vp {
+                return Some((window_entity, window, position - min));
             }
         }
     }
     None
 }
 
+/// Whether the camera can become the active camera, i.e. it is active and
+/// its viewport is not empty, e.g. while its UI tab is hidden
+fn is_camera_selectable(camera: &Camera) -> bool {
+    camera.is_active
+        && utils::non_empty_size(camera.logical_viewport_size()).is_some()
+}
+
+/// Camera found under the cursor or a touch, see
+/// [`get_camera_under_cursor`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraUnderCursor {
+    /// The camera entity
+    pub entity: Entity,
+    /// The window the camera renders to
+    pub window_entity: Entity,
+    /// Position of the cursor or touch in logical pixels, relative to the
+    /// top left corner of the viewport of the camera
+    pub viewport_position: Vec2,
+}
+
 /// Get the camera entity that renders to the viewport under the mouse
 /// cursor with highest rendering order.
 pub fn get_camera_entity_from_cursor_position(
@@ -246,33 +1025,50 @@ pub fn get_camera_entity_from_cursor_position(
     primary_window: &Query<(Entity, &Window), With<PrimaryWindow>>,
     other_windows: &Query<(Entity, &Window), Without<PrimaryWindow>>,
 ) -> Option<Entity> {
-    let mut camera_entity = None;
+    get_camera_under_cursor(cameras_query, None, primary_window, other_windows)
+        .map(|camera_under_cursor| camera_under_cursor.entity)
+}
+
+/// Get the camera that renders to the viewport under the mouse cursor, or
+/// under a touch that just began if `touches` is given, with highest
+/// rendering order, along with its window and the position in its viewport.
+pub fn get_camera_under_cursor(
+    cameras_query: &Query<(Entity, &Camera)>,
+    touches: Option<&Touches>,
+    primary_window: &Query<(Entity, &Window), With<PrimaryWindow>>,
+    other_windows: &Query<(Entity, &Window), Without<PrimaryWindow>>,
+) -> Option<CameraUnderCursor> {
+    let mut camera_under_cursor = None;
     let mut max_cam_order = 0;
     for (entity, camera) in cameras_query.iter() {
-        if get_window_if_cursor_in_camera_viewport(
-            camera,
-            None,
-            primary_window,
-            other_windows,
-        )
-        .is_some()
+        if let Some((window_entity, _, viewport_position)) =
+            get_input_position_in_camera_viewport(
+                camera,
+                touches,
+                primary_window,
+                other_windows,
+            )
         {
             // Only set if camera order is higher. This may
             // overwrite a previous value in the case the viewport
             // is overlapping another viewport.
             if camera.order >= max_cam_order {
-                camera_entity = Some(entity);
+                camera_under_cursor = Some(CameraUnderCursor {
+                    entity,
+                    window_entity,
+                    viewport_position,
+                });
                 max_cam_order = camera.order;
             }
         }
     }
-    camera_entity
+    camera_under_cursor
 }
 
 /// Gather data about the active viewport, i.e. the viewport the user is
-/// interacting with.
+/// interacting with. The active camera does not change during a drag.
 /// Enables multiple viewports/windows.
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn active_viewport_data_system(
     mut active_cam: ResMut<ActiveCameraData>,
     mouse_input: Res<ButtonInput<MouseButton>>,
@@ -281,21 +1077,68 @@ fn active_viewport_data_system(
     touches: Res<Touches>,
     primary_window: Query<(Entity, &Window), With<PrimaryWindow>>,
     other_windows: Query<(Entity, &Window), Without<PrimaryWindow>>,
-    orbit_fly_cameras: Query<(
-        Entity,
-        &Camera,
-        Option<&OrbitCameraController>,
-        Option<&FlyCameraController>,
-    )>,
+    orbit_fly_cameras: Query<
+        (
+            Entity,
+            &Camera,
+            Option<&OrbitCameraController>,
+            Option<&FlyCameraController>,
+            Option<&Pan2dCameraController>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+    input_blocked: Res<CameraInputBlocked>,
+    gesture_touches: Res<GestureTouches>,
+    config: Res<BlendyCamerasConfig>,
     #[cfg(feature = "bevy_egui")] egui_wants_focus: Res<EguiWantsFocus>,
 ) {
+    if input_blocked.is_blocked() {
+        return;
+    }
+    // Keep the active camera until the end of a drag, even if the cursor
+    // moves over another viewport
+    let is_active_camera_dragging = active_cam
+        .entity
+        .and_then(|entity| orbit_fly_cameras.get(entity).ok())
+        .is_some_and(
+            |(
+                _,
+                camera,
+                orbit_controller_opt,
+                fly_controller_opt,
+                pan2d_opt,
+            )| {
+                is_camera_selectable(camera)
+                    && is_dragging(
+                        orbit_controller_opt,
+                        fly_controller_opt,
+                        pan2d_opt,
+                        &mouse_input,
+                        &key_input,
+                    )
+            },
+        );
+    // Also keep it while at least one finger of the touch gesture is down
+    if is_active_camera_dragging
+        || (active_cam.entity.is_some() && gesture_touches.is_active(&touches))
+    {
+        return;
+    }
     let mut new_resource = ActiveCameraData::default();
     let mut max_cam_order = 0;
     let mut has_input = false;
-    for (entity, camera, orbit_controller_opt, fly_controller_opt) in
-        orbit_fly_cameras.iter()
+    for (
+        entity,
+        camera,
+        orbit_controller_opt,
+        fly_controller_opt,
+        pan2d_controller_opt,
+    ) in orbit_fly_cameras.iter()
     {
-        if orbit_controller_opt.is_none() && fly_controller_opt.is_none() {
+        if orbit_controller_opt.is_none()
+            && fly_controller_opt.is_none()
+            && pan2d_controller_opt.is_none()
+        {
             continue;
         }
 
@@ -311,7 +1154,15 @@ fn active_viewport_data_system(
                         orbit_controller,
                         &mouse_input,
                         &key_input,
-                    ));
+                    ) || input::zoom_just_pressed(
+                        orbit_controller,
+                        &mouse_input,
+                        &key_input,
+                    ) || (!input_blocked.is_keyboard_blocked()
+                        && input::key_pan_zoom_just_pressed(
+                            orbit_controller,
+                            &key_input,
+                        )));
             }
         }
         if let Some(fly_controller) = fly_controller_opt {
@@ -322,15 +1173,26 @@ fn active_viewport_data_system(
                         &mouse_input,
                         &key_input,
                     )
-                    || input::move_just_pressed(fly_controller, &key_input);
+                    || (!input_blocked.is_keyboard_blocked()
+                        && input::move_just_pressed(
+                            fly_controller,
+                            &key_input,
+                        ));
+            }
+        }
+        if let Some(pan2d_controller) = pan2d_controller_opt {
+            if pan2d_controller.is_enabled {
+                drag_just_activated = drag_just_activated
+                    || pan2d_controller
+                        .is_pan_just_pressed(&mouse_input, &key_input);
             }
         }
 
-        let input_just_activated = drag_just_activated
+        let input_just_activated = config.activation_mode
+            == ActivationMode::OnHover
+            || drag_just_activated
             || !scroll_events.is_empty()
-            || (touches.iter_just_pressed().count() > 0
-                && touches.iter_just_pressed().count()
-                    == touches.iter().count());
+            || touches.iter_just_pressed().next().is_some();
         if input_just_activated {
             has_input = true;
 
@@ -338,8 +1200,15 @@ fn active_viewport_data_system(
             let mut should_get_input = true;
             #[cfg(feature = "bevy_egui")]
             {
-                should_get_input =
-                    !egui_wants_focus.prev && !egui_wants_focus.curr;
+                let is_scroll_only = !drag_just_activated
+                    && !scroll_events.is_empty()
+                    && touches.iter_just_pressed().next().is_none();
+                should_get_input = if is_scroll_only {
+                    !egui_wants_focus.scroll_prev
+                        && !egui_wants_focus.scroll_curr
+                } else {
+                    !egui_wants_focus.prev && !egui_wants_focus.curr
+                };
             }
             if should_get_input {
                 if let Some((window_entity, window)) =
@@ -361,8 +1230,10 @@ fn active_viewport_data_system(
                                 window.width(),
                                 window.height(),
                             )),
+                            scale_factor: Some(window.scale_factor()),
                             manual: false,
                             window_entity: Some(window_entity),
+                            interaction: None,
                         };
                         max_cam_order = camera.order;
                     }
@@ -371,12 +1242,93 @@ fn active_viewport_data_system(
         }
     }
 
-    if has_input {
+    // When hovering, keep the previous camera once the cursor leaves the
+    // viewports, e.g. to keep moving with the keys of the fly controller
+    let is_hovering_nothing = config.activation_mode == ActivationMode::OnHover
+        && new_resource.entity.is_none();
+    if has_input && !is_hovering_nothing {
+        if new_resource.entity == active_cam.entity {
+            // Keep the interaction, e.g. the keyboard movement in fly mode,
+            // when the active camera does not change
+            new_resource.interaction = active_cam.interaction;
+        }
         active_cam.set_if_neq(new_resource);
     }
 }
 
-/// Grap, wrap around and center cursor when needed
+/// Stop using a camera as the active camera once its controllers are removed,
+/// e.g. when it is despawned, even in the middle of a drag. The cursor of its
+/// window is then restored by `wrap_grab_center_cursor_system`.
+#[allow(clippy::type_complexity, deprecated)]
+fn clear_removed_active_camera_system(
+    mut active_cam: ResMut<ActiveCameraData>,
+    mut mouse_key_tracker: ResMut<MouseKeyTracker>,
+    mut removed_orbit_controllers: RemovedComponents<OrbitCameraController>,
+    mut removed_fly_controllers: RemovedComponents<FlyCameraController>,
+    mut removed_pan2d_controllers: RemovedComponents<Pan2dCameraController>,
+    controllers: Query<
+        (),
+        Or<(
+            With<OrbitCameraController>,
+            With<FlyCameraController>,
+            With<Pan2dCameraController>,
+        )>,
+    >,
+) {
+    let Some(active_entity) = active_cam.entity else {
+        removed_orbit_controllers.clear();
+        removed_fly_controllers.clear();
+        removed_pan2d_controllers.clear();
+        return;
+    };
+    let is_removed = removed_orbit_controllers
+        .read()
+        .chain(removed_fly_controllers.read())
+        .chain(removed_pan2d_controllers.read())
+        .any(|entity| entity == active_entity);
+    if is_removed && !controllers.contains(active_entity) {
+        *active_cam = ActiveCameraData {
+            manual: active_cam.manual,
+            ..default()
+        };
+        // Not updated while the plugin is disabled
+        mouse_key_tracker.0 = default();
+    }
+}
+
+/// Cursor state to restore at the end of a drag
+#[derive(Debug, Clone, Copy)]
+struct DragCursor {
+    window_entity: Entity,
+    is_grabbed: bool,
+    is_hidden: bool,
+    restore_position: Option<Vec2>,
+}
+
+/// Whether the enabled controllers of a camera are dragging
+fn is_dragging(
+    orbit_controller_opt: Option<&OrbitCameraController>,
+    fly_controller_opt: Option<&FlyCameraController>,
+    pan2d_controller_opt: Option<&Pan2dCameraController>,
+    mouse_input: &ButtonInput<MouseButton>,
+    key_input: &ButtonInput<KeyCode>,
+) -> bool {
+    orbit_controller_opt.is_some_and(|controller| {
+        controller.is_enabled
+            && (controller.is_orbit_pressed(mouse_input, key_input)
+                || controller.is_pan_pressed(mouse_input, key_input)
+                || controller.is_zoom_pressed(mouse_input, key_input))
+    }) || fly_controller_opt.is_some_and(|controller| {
+        controller.is_enabled
+            && controller.is_rotate_pressed(mouse_input, key_input)
+    }) || pan2d_controller_opt.is_some_and(|controller| {
+        controller.is_enabled
+            && controller.is_pan_pressed(mouse_input, key_input)
+    })
+}
+
+/// Grap, wrap around, center, hide and restore cursor when needed
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn wrap_grab_center_cursor_system(
     active_cam: Res<ActiveCameraData>,
     mouse_input: Res<ButtonInput<MouseButton>>,
@@ -386,14 +1338,72 @@ fn wrap_grab_center_cursor_system(
         &Camera,
         Option<&OrbitCameraController>,
         Option<&FlyCameraController>,
+        Option<&CameraInputState>,
     )>,
     mut cursor_start_pos: Local<Option<Vec2>>,
-    winit_windows: NonSendMut<WinitWindows>,
+    mut drag_cursor: Local<Option<DragCursor>>,
+    mut focus_events: EventReader<WindowFocused>,
+    // Missing in headless apps
+    #[cfg(feature = "winit")] winit_windows: Option<NonSend<WinitWindows>>,
+    input_blocked: Res<CameraInputBlocked>,
+    config: Res<BlendyCamerasConfig>,
 ) {
-    let Some(window_entity) = active_cam.window_entity else {
+    let unfocused_windows = focus_events
+        .read()
+        .filter(|event| !event.focused)
+        .map(|event| event.window)
+        .collect::<Vec<_>>();
+    // Restore the cursor when the drag ends, including when the controller
+    // is disabled or removed, or the window loses focus, before the drag is
+    // released
+    if let Some(drag) = *drag_cursor {
+        let is_still_dragging = active_cam.window_entity
+            == Some(drag.window_entity)
+            && active_cam
+                .entity
+                .and_then(|entity| orbit_fly_cameras.get(entity).ok())
+                .is_some_and(
+                    |(_, orbit_controller_opt, fly_controller_opt, _)| {
+                        is_dragging(
+                            orbit_controller_opt,
+                            fly_controller_opt,
+                            None,
+                            &mouse_input,
+                            &key_input,
+                        )
+                    },
+                );
+        let has_lost_focus = unfocused_windows.contains(&drag.window_entity);
+        // Also when the drag is cancelled, blocking the input until released
+        if !is_still_dragging
+            || has_lost_focus
+            || !config.enabled
+            || input_blocked.until_released
+        {
+            if let Ok(mut window) = windows.get_mut(drag.window_entity) {
+                if drag.is_grabbed {
+                    window.cursor_options.grab_mode = CursorGrabMode::None;
+                }
+                if drag.is_hidden {
+                    window.cursor_options.visible = true;
+                }
+                if drag.restore_position.is_some() {
+                    window.set_cursor_position(drag.restore_position);
+                }
+            }
+            *drag_cursor = None;
+            *cursor_start_pos = None;
+        }
+    }
+    if !config.enabled {
         return;
-    };
-    let Some(winit_window) = winit_windows.get_window(window_entity) else {
+    }
+    if input_blocked.until_released {
+        *cursor_start_pos = None;
+        return;
+    }
+
+    let Some(window_entity) = active_cam.window_entity else {
         return;
     };
     let Ok(mut window) = windows.get_mut(window_entity) else {
@@ -402,7 +1412,7 @@ fn wrap_grab_center_cursor_system(
     let Some(camera_entity) = active_cam.entity else {
         return;
     };
-    let Ok((camera, orbit_controller_opt, fly_controller_opt)) =
+    let Ok((camera, orbit_controller_opt, fly_controller_opt, input_state_opt)) =
         orbit_fly_cameras.get(camera_entity)
     else {
         return;
@@ -411,22 +1421,19 @@ fn wrap_grab_center_cursor_system(
         return;
     }
 
-    let mut drag_just_activated = false;
+    // Set by `mouse_key_tracker_system` once the mouse moved past the drag
+    // start threshold of the controllers
+    let drag_just_activated = input_state_opt
+        .is_some_and(|input_state| input_state.drag_just_started);
     let mut drag_just_released = false;
-    let mut wrap_cursor = false;
+    let mut wrap_mode = CursorWrapMode::None;
     let mut center_cursor = false;
+    let mut hide_cursor = false;
+    let mut restore_cursor = false;
     if let Some(orbit_controller) = orbit_controller_opt {
         if orbit_controller.is_enabled {
-            drag_just_activated = drag_just_activated
-                || (input::orbit_just_pressed(
-                    orbit_controller,
-                    &mouse_input,
-                    &key_input,
-                ) || input::pan_just_pressed(
-                    orbit_controller,
-                    &mouse_input,
-                    &key_input,
-                ));
+            hide_cursor |= orbit_controller.hide_cursor_during_drag;
+            restore_cursor |= orbit_controller.restore_cursor_on_release;
             let drag_pressed = input::orbit_pressed(
                 orbit_controller,
                 &mouse_input,
@@ -435,6 +1442,10 @@ fn wrap_grab_center_cursor_system(
                 orbit_controller,
                 &mouse_input,
                 &key_input,
+            ) || input::zoom_pressed(
+                orbit_controller,
+                &mouse_input,
+                &key_input,
             );
             drag_just_released = drag_just_released
                 || (input::orbit_just_released(
@@ -445,19 +1456,20 @@ fn wrap_grab_center_cursor_system(
                     orbit_controller,
                     &mouse_input,
                     &key_input,
+                ) || input::zoom_just_released(
+                    orbit_controller,
+                    &mouse_input,
+                    &key_input,
                 ));
-            wrap_cursor =
-                wrap_cursor || (orbit_controller.wrap_cursor && drag_pressed);
+            if drag_pressed {
+                wrap_mode = orbit_controller.wrap_cursor;
+            }
         }
     }
     if let Some(fly_controller) = fly_controller_opt {
         if fly_controller.is_enabled {
-            drag_just_activated = drag_just_activated
-                || input::rotate_just_pressed(
-                    fly_controller,
-                    &mouse_input,
-                    &key_input,
-                );
+            hide_cursor |= fly_controller.hide_cursor_during_drag;
+            restore_cursor |= fly_controller.restore_cursor_on_release;
             drag_just_released = drag_just_released
                 || input::rotate_just_released(
                     fly_controller,
@@ -472,8 +1484,27 @@ fn wrap_grab_center_cursor_system(
     }
 
     let viewport_rect = camera.logical_viewport_rect().unwrap();
-    if drag_just_activated {
+    let wrap_cursor = wrap_mode != CursorWrapMode::None;
+    let wrap_rect = match wrap_mode {
+        CursorWrapMode::Window => {
+            Rect::new(0.0, 0.0, window.width(), window.height())
+        }
+        _ => viewport_rect,
+    };
+    // A drag that starts while the input is blocked is not for the camera
+    if drag_just_activated && !input_blocked.is_blocked() {
         *cursor_start_pos = window.cursor_position();
+        if hide_cursor {
+            window.cursor_options.visible = false;
+        }
+        if wrap_cursor || center_cursor || hide_cursor || restore_cursor {
+            *drag_cursor = Some(DragCursor {
+                window_entity,
+                is_grabbed: wrap_cursor || center_cursor,
+                is_hidden: hide_cursor,
+                restore_position: cursor_start_pos.filter(|_| restore_cursor),
+            });
+        }
         if wrap_cursor {
             // HACK: No need to grab/lock cursor if warp worked with all
             // window manager on all platforms
@@ -491,8 +1522,15 @@ fn wrap_grab_center_cursor_system(
         if center_cursor {
             let center = viewport_rect.center();
             // HACK: Avoid Wayland error message
-            let _ = winit_window
-                .set_cursor_grab(winit::window::CursorGrabMode::Locked);
+            #[cfg(feature = "winit")]
+            if let Some(winit_window) =
+                winit_windows.as_ref().and_then(|winit_windows| {
+                    winit_windows.get_window(window_entity)
+                })
+            {
+                let _ = winit_window
+                    .set_cursor_grab(winit::window::CursorGrabMode::Locked);
+            }
             // End of hack
             window.cursor_options.grab_mode = CursorGrabMode::Locked;
             // window.cursor_options.visible = false;
@@ -513,15 +1551,15 @@ fn wrap_grab_center_cursor_system(
     {
         if wrap_cursor {
             if let Some(mut pos) = window.cursor_position() {
-                if pos.x <= viewport_rect.min.x {
-                    pos.x = viewport_rect.max.x;
-                } else if pos.x >= viewport_rect.max.x {
-                    pos.x = viewport_rect.min.x;
+                if pos.x <= wrap_rect.min.x {
+                    pos.x = wrap_rect.max.x;
+                } else if pos.x >= wrap_rect.max.x {
+                    pos.x = wrap_rect.min.x;
                 }
-                if pos.y <= viewport_rect.min.y {
-                    pos.y = viewport_rect.max.y;
-                } else if pos.y >= viewport_rect.max.y {
-                    pos.y = viewport_rect.min.y;
+                if pos.y <= wrap_rect.min.y {
+                    pos.y = wrap_rect.max.y;
+                } else if pos.y >= wrap_rect.max.y {
+                    pos.y = wrap_rect.min.y;
                 }
                 window.set_cursor_position(Some(pos));
             } else {
@@ -537,128 +1575,849 @@ fn wrap_grab_center_cursor_system(
     }
 }
 
+fn set_camera_controller_system(
+    mut ev_read: EventReader<SetCameraControllerEvent>,
+    mut orbit_ev_writer: EventWriter<SwitchToOrbitController>,
+    mut fly_ev_writer: EventWriter<SwitchToFlyController>,
+    query: Query<
+        (Option<&OrbitCameraController>, Option<&FlyCameraController>),
+        Without<BlendyCamerasDisabled>,
+    >,
+) {
+    for SetCameraControllerEvent {
+        camera_entity,
+        mode,
+    } in ev_read.read()
+    {
+        let camera_entity = *camera_entity;
+        let Ok((orbit_controller_opt, fly_controller_opt)) =
+            query.get(camera_entity)
+        else {
+            warn!("Camera not found while trying to set camera controller");
+            continue;
+        };
+        let to_orbit = match mode {
+            ControllerMode::Orbit => true,
+            ControllerMode::Fly => false,
+            // Switch to orbit if neither controller is enabled
+            ControllerMode::Toggle => !orbit_controller_opt
+                .is_some_and(|controller| controller.is_enabled),
+        };
+        if fly_controller_opt.is_none() && orbit_controller_opt.is_none() {
+            warn!(
+                "Camera has no controller while trying to set camera controller"
+            );
+            continue;
+        }
+        if to_orbit {
+            orbit_ev_writer.send(SwitchToOrbitController { camera_entity });
+        } else {
+            fly_ev_writer.send(SwitchToFlyController { camera_entity });
+        }
+    }
+}
+
+/// Distance to the focus of an [`OrbitCameraController`] created or
+/// initialized when switching from fly mode
+pub(crate) const DEFAULT_ORBIT_RADIUS: f32 = 5.0;
+
+#[allow(clippy::type_complexity)]
 fn switch_to_orbit_camera_controller_system(
+    mut commands: Commands,
     mut ev_read: EventReader<SwitchToOrbitController>,
-    mut query: Query<(
-        &Transform,
-        &mut OrbitCameraController,
-        &mut FlyCameraController,
-    )>,
+    mut query: Query<
+        (
+            &Transform,
+            Option<&mut OrbitCameraController>,
+            Option<&mut FlyCameraController>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+    pan2d_cameras: Query<(), With<Pan2dCameraController>>,
+    settings_opt: Option<Res<DefaultControllerSettings>>,
 ) {
     for SwitchToOrbitController { camera_entity } in ev_read.read() {
-        if let Ok((transform, mut orbit_controller, mut fly_controller)) =
+        // The 2D cameras have no other controller or projection
+        if pan2d_cameras.contains(*camera_entity) {
+            continue;
+        }
+        let Ok((transform, orbit_controller_opt, fly_controller_opt)) =
             query.get_mut(*camera_entity)
-        {
-            if fly_controller.is_enabled {
-                fly_controller.is_enabled = false;
-                orbit_controller.is_enabled = true;
-                let (yaw, pitch, _roll) =
-                    transform.rotation.to_euler(EulerRot::YXZ);
-                orbit_controller.yaw = Some(yaw);
-                orbit_controller.pitch = Some(-pitch);
-                orbit_controller.focus = transform.translation
-                    + (transform.forward() * orbit_controller.radius.unwrap());
-            }
-        } else {
+        else {
             warn!(
                 "Camera not found while trying to swith to OrbitCameraController"
             );
+            continue;
+        };
+        if orbit_controller_opt
+            .as_ref()
+            .is_some_and(|controller| controller.is_enabled)
+        {
+            continue;
+        }
+        if let Some(mut fly_controller) = fly_controller_opt {
+            fly_controller.is_enabled = false;
+        }
+        let (yaw, pitch, _roll) = transform.rotation.to_euler(EulerRot::YXZ);
+        if let Some(mut orbit_controller) = orbit_controller_opt {
+            orbit_controller.is_enabled = true;
+            let radius =
+                orbit_controller.radius.unwrap_or(DEFAULT_ORBIT_RADIUS);
+            orbit_controller.yaw = Some(yaw);
+            orbit_controller.pitch = Some(-pitch);
+            orbit_controller.focus =
+                transform.translation + (transform.forward() * radius);
+        } else {
+            // Missing controller, create one looking at the same point
+            commands
+                .entity(*camera_entity)
+                .insert(OrbitCameraController {
+                    focus: transform.translation
+                        + (transform.forward() * DEFAULT_ORBIT_RADIUS),
+                    radius: Some(DEFAULT_ORBIT_RADIUS),
+                    yaw: Some(yaw),
+                    pitch: Some(-pitch),
+                    is_enabled: true,
+                    ..settings_opt.as_deref().map_or_else(
+                        OrbitCameraController::default,
+                        OrbitCameraController::from_defaults,
+                    )
+                });
         }
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn switch_to_fly_camera_controller_system(
+    mut commands: Commands,
     mut ev_read: EventReader<SwitchToFlyController>,
-    mut query: Query<(
-        &mut Transform,
-        &mut OrbitCameraController,
-        &mut FlyCameraController,
-        &mut Projection,
-        &mut OtherProjection,
-    )>,
+    mut query: Query<
+        (
+            &mut Transform,
+            Option<&mut OrbitCameraController>,
+            Option<&mut FlyCameraController>,
+            Option<&Camera>,
+            &mut Projection,
+            Option<&mut OtherProjection>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+    pan2d_cameras: Query<(), With<Pan2dCameraController>>,
+    settings_opt: Option<Res<DefaultControllerSettings>>,
 ) {
     for SwitchToFlyController { camera_entity } in ev_read.read() {
-        if let Ok((
+        // The 2D cameras have no other controller or projection
+        if pan2d_cameras.contains(*camera_entity) {
+            continue;
+        }
+        let Ok((
             mut transform,
-            mut orbit_controller,
-            mut fly_controller,
+            mut orbit_controller_opt,
+            fly_controller_opt,
+            camera_opt,
             mut projection,
-            mut next_projection,
+            other_projection_opt,
         )) = query.get_mut(*camera_entity)
+        else {
+            warn!(
+                "Camera not found while trying to swith to FlyCameraController"
+            );
+            continue;
+        };
+        if fly_controller_opt
+            .as_ref()
+            .is_some_and(|controller| controller.is_enabled)
         {
-            if orbit_controller.is_enabled {
-                orbit_controller.is_enabled = false;
-                fly_controller.is_enabled = true;
-                // FIXME: commenting this makes fly mode works with ortho too
-                // but zoom and sensitivity behave wierdly
-                if let Projection::Orthographic(_) = *projection {
+            continue;
+        }
+        if let Some(ref mut orbit_controller) = orbit_controller_opt {
+            orbit_controller.is_enabled = false;
+        }
+        match fly_controller_opt {
+            Some(mut fly_controller) => fly_controller.is_enabled = true,
+            None => {
+                commands.entity(*camera_entity).insert(FlyCameraController {
+                    is_enabled: true,
+                    ..settings_opt.as_deref().map_or_else(
+                        FlyCameraController::default,
+                        FlyCameraController::from_defaults,
+                    )
+                });
+            }
+        }
+        // FIXME: commenting this makes fly mode works with ortho too
+        // but zoom and sensitivity behave wierdly
+        if let Projection::Orthographic(_) = *projection {
+            match (orbit_controller_opt.as_deref_mut(), other_projection_opt) {
+                (Some(orbit_controller), Some(mut next_projection))
+                    if orbit_controller.is_initialized =>
+                {
                     switch_camera_projection(
-                        &orbit_controller,
+                        orbit_controller,
+                        camera_opt.and_then(Camera::logical_viewport_size),
                         &mut transform,
                         &mut next_projection.0,
                         &mut projection,
                     );
                 }
+                (_, Some(mut next_projection)) => {
+                    std::mem::swap(&mut next_projection.0, &mut *projection);
+                }
+                (_, None) => {
+                    // Create the saved projection on the fly
+                    let orthographic = std::mem::replace(
+                        &mut *projection,
+                        Projection::Perspective(default()),
+                    );
+                    commands
+                        .entity(*camera_entity)
+                        .insert(OtherProjection(orthographic));
+                }
             }
-        } else {
-            warn!(
-                "Camera not found while trying to swith to FlyCameraController"
-            );
+            commands.send_event(ProjectionSwitched::new(
+                *camera_entity,
+                &projection,
+            ));
         }
+        commands.entity(*camera_entity).remove::<AutoOrthographic>();
     }
 }
 
-fn switch_camera_projection(
-    orbit_controller: &OrbitCameraController,
+/// Switch to `next_projection`, converting the radius of the orbit
+/// controller so that the framing at the focus does not change
+pub(crate) fn switch_camera_projection(
+    orbit_controller: &mut OrbitCameraController,
+    viewport_size: Option<Vec2>,
     transform: &mut Transform,
     next_projection: &mut Projection,
     projection: &mut Projection,
 ) {
+    let radius = utils::switched_projection_radius(
+        projection,
+        next_projection,
+        orbit_controller.radius.unwrap(),
+        viewport_size,
+    );
+    orbit_controller.radius = Some(radius);
     // Need to update transform/projection
-    utils::update_orbit_transform(
+    math::update_orbit_transform(
         orbit_controller.yaw.unwrap(),
         orbit_controller.pitch.unwrap(),
-        orbit_controller.radius.unwrap(),
+        radius,
         orbit_controller.focus,
         transform,
         next_projection,
+        orbit_controller.ortho_placement,
     );
     std::mem::swap(next_projection, projection);
 }
 
+#[allow(clippy::type_complexity)]
 fn switch_camera_projection_system(
+    mut commands: Commands,
     mut ev_read: EventReader<SwitchProjection>,
-    mut query: Query<(
-        &mut Transform,
-        &OrbitCameraController,
-        &mut Projection,
-        &mut OtherProjection,
-    )>,
+    config: Res<DefaultOrthographicConfig>,
+    mut query: Query<
+        (
+            &mut Transform,
+            Option<&mut OrbitCameraController>,
+            Option<&Camera>,
+            &mut Projection,
+            Option<&mut OtherProjection>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+    pan2d_cameras: Query<(), With<Pan2dCameraController>>,
 ) {
     for SwitchProjection { camera_entity } in ev_read.read() {
+        // The 2D cameras have no other controller or projection
+        if pan2d_cameras.contains(*camera_entity) {
+            continue;
+        }
         trace!("Camera projection switch");
-        if let Ok((
+        let Ok((
             mut transform,
-            orbit_controller,
+            orbit_controller_opt,
+            camera_opt,
             mut projection,
-            mut next_projection,
+            next_projection_opt,
         )) = query.get_mut(*camera_entity)
-        {
-            // Do not switch if in fly mode, which only work in perspective
-            // for now
-            // FIXME: We probably need to swicth even if orbit is not enabled
-            // this functionality is not really related to the orbit controller
-            // appart from the point in the previous commentary
-            if orbit_controller.is_enabled {
+        else {
+            warn!("Camera not found while trying to swith to Projection");
+            continue;
+        };
+        let viewport_size = camera_opt.and_then(Camera::logical_viewport_size);
+        let mut new_next_projection = None;
+        let next_projection = match next_projection_opt {
+            Some(next_projection) => &mut next_projection.into_inner().0,
+            None => new_next_projection
+                .insert(OtherProjection::default_for(&projection, &config).0),
+        };
+        match orbit_controller_opt {
+            Some(mut orbit_controller)
+                if orbit_controller.is_enabled
+                    && orbit_controller.is_initialized =>
+            {
                 switch_camera_projection(
-                    orbit_controller,
+                    &mut orbit_controller,
+                    viewport_size,
                     &mut transform,
-                    &mut next_projection.0,
+                    next_projection,
                     &mut projection,
                 );
             }
-        } else {
-            warn!("Camera not found while trying to swith to Projection");
+            orbit_controller_opt => {
+                // Orbit controller disabled (fly mode) or missing, derive the
+                // orbit from the current transform to keep the same view
+                let radius = orbit_controller_opt
+                    .as_ref()
+                    .and_then(|orbit_controller| orbit_controller.radius)
+                    .unwrap_or(DEFAULT_ORBIT_RADIUS);
+                let ortho_placement = orbit_controller_opt
+                    .map(|orbit_controller| orbit_controller.ortho_placement)
+                    .unwrap_or_default();
+                let (distance, radius) = match *projection {
+                    Projection::Perspective(_) => (radius, radius),
+                    Projection::Orthographic(ref p) => {
+                        (ortho_placement.distance(p), p.scale)
+                    }
+                };
+                let radius = utils::switched_projection_radius(
+                    &projection,
+                    next_projection,
+                    radius,
+                    viewport_size,
+                );
+                let focus =
+                    transform.translation + transform.forward() * distance;
+                let (yaw, pitch, _roll) =
+                    transform.rotation.to_euler(EulerRot::YXZ);
+                math::update_orbit_transform(
+                    yaw,
+                    -pitch,
+                    radius,
+                    focus,
+                    &mut transform,
+                    next_projection,
+                    ortho_placement,
+                );
+                std::mem::swap(next_projection, &mut *projection);
+            }
+        }
+        if let Some(next_projection) = new_next_projection {
+            commands
+                .entity(*camera_entity)
+                .insert(OtherProjection(next_projection));
         }
+        // A deliberate switch must not be reverted automatically
+        commands.entity(*camera_entity).remove::<AutoOrthographic>();
+        commands
+            .send_event(ProjectionSwitched::new(*camera_entity, &projection));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{
+        ecs::system::RunSystemOnce,
+        input::{
+            mouse::MouseScrollUnit,
+            touch::{TouchInput, TouchPhase},
+        },
+        render::camera::{camera_system, ManualTextureViews, Viewport},
+        time::TimeUpdateStrategy,
+    };
+
+    use super::*;
+    use crate::{
+        testing::{
+            assert_transform_approx_eq, set_active_camera, test_app,
+            SimulatedInput,
+        },
+        transition::ViewTransition,
+    };
+
+    /// Spawn the 800x600 primary window
+    fn spawn_window(app: &mut App) -> Entity {
+        app.init_asset::<Image>()
+            .init_resource::<ManualTextureViews>();
+        app.world_mut()
+            .spawn((
+                Window {
+                    resolution: (800.0, 600.0).into(),
+                    ..default()
+                },
+                PrimaryWindow,
+            ))
+            .id()
+    }
+
+    /// Spawn an orbit camera rendering to a viewport of the primary window
+    fn spawn_camera(app: &mut App, rect: URect, order: isize) -> Entity {
+        app.world_mut()
+            .spawn((
+                Camera {
+                    viewport: Some(Viewport {
+                        physical_position: rect.min,
+                        physical_size: rect.size(),
+                        ..default()
+                    }),
+                    order,
+                    ..default()
+                },
+                Projection::default(),
+                Transform::from_xyz(0.0, 0.0, 5.0),
+                OrbitCameraController::default(),
+            ))
+            .id()
+    }
+
+    /// Compute the viewports of the cameras, which are only computed by the
+    /// render plugins
+    fn update_viewports(app: &mut App) {
+        app.world_mut()
+            .run_system_once(camera_system::<Projection>)
+            .unwrap();
+    }
+
+    fn set_cursor_position(app: &mut App, window: Entity, position: Vec2) {
+        app.world_mut()
+            .get_mut::<Window>(window)
+            .unwrap()
+            .set_cursor_position(Some(position));
+    }
+
+    /// The camera under the cursor, or a touch that just began, and the
+    /// camera entity under the cursor
+    fn cameras_under_cursor(
+        app: &mut App,
+    ) -> (Option<CameraUnderCursor>, Option<Entity>) {
+        app.world_mut()
+            .run_system_once(
+                |cameras: Query<(Entity, &Camera)>,
+                 touches: Res<Touches>,
+                 primary_window: Query<
+                    (Entity, &Window),
+                    With<PrimaryWindow>,
+                >,
+                 other_windows: Query<
+                    (Entity, &Window),
+                    Without<PrimaryWindow>,
+                >| {
+                    (
+                        get_camera_under_cursor(
+                            &cameras,
+                            Some(&touches),
+                            &primary_window,
+                            &other_windows,
+                        ),
+                        get_camera_entity_from_cursor_position(
+                            &cameras,
+                            &primary_window,
+                            &other_windows,
+                        ),
+                    )
+                },
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn higher_order_camera_wins_in_overlapping_viewports() {
+        let mut app = test_app();
+        let window = spawn_window(&mut app);
+        // Spawned first, so that it is not the last one checked
+        let inset = spawn_camera(&mut app, URect::new(200, 100, 600, 400), 1);
+        let full = spawn_camera(&mut app, URect::new(0, 0, 800, 600), 0);
+        update_viewports(&mut app);
+
+        set_cursor_position(&mut app, window, Vec2::new(300.0, 200.0));
+        let (camera_under_cursor, entity) = cameras_under_cursor(&mut app);
+        assert_eq!(
+            camera_under_cursor,
+            Some(CameraUnderCursor {
+                entity: inset,
+                window_entity: window,
+                viewport_position: Vec2::new(100.0, 100.0),
+            })
+        );
+        assert_eq!(entity, Some(inset));
+
+        set_cursor_position(&mut app, window, Vec2::new(50.0, 500.0));
+        let (camera_under_cursor, entity) = cameras_under_cursor(&mut app);
+        assert_eq!(
+            camera_under_cursor,
+            Some(CameraUnderCursor {
+                entity: full,
+                window_entity: window,
+                viewport_position: Vec2::new(50.0, 500.0),
+            })
+        );
+        assert_eq!(entity, Some(full));
+    }
+
+    #[test]
+    fn touch_selects_the_higher_order_camera() {
+        let mut app = test_app();
+        let window = spawn_window(&mut app);
+        let inset = spawn_camera(&mut app, URect::new(200, 100, 600, 400), 1);
+        spawn_camera(&mut app, URect::new(0, 0, 800, 600), 0);
+        update_viewports(&mut app);
+        app.world_mut().send_event(TouchInput {
+            phase: TouchPhase::Started,
+            position: Vec2::new(550.0, 350.0),
+            window,
+            force: None,
+            id: 0,
+        });
+        app.update();
+        let (camera_under_cursor, entity) = cameras_under_cursor(&mut app);
+        assert_eq!(
+            camera_under_cursor,
+            Some(CameraUnderCursor {
+                entity: inset,
+                window_entity: window,
+                viewport_position: Vec2::new(350.0, 250.0),
+            })
+        );
+        // Without a cursor in the window, only the touch finds a camera
+        assert_eq!(entity, None);
+    }
+
+    /// Click with the orbit button and return the active camera
+    fn click(app: &mut App) -> Option<Entity> {
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_button(MouseButton::Middle);
+        app.update();
+        let entity = app.world().resource::<ActiveCameraData>().entity;
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .release_button(MouseButton::Middle);
+        app.update();
+        entity
+    }
+
+    #[test]
+    fn inactive_cameras_never_become_the_active_camera() {
+        let mut app = test_app();
+        let window = spawn_window(&mut app);
+        let inset = spawn_camera(&mut app, URect::new(200, 100, 600, 400), 1);
+        let full = spawn_camera(&mut app, URect::new(0, 0, 800, 600), 0);
+        update_viewports(&mut app);
+        set_cursor_position(&mut app, window, Vec2::new(300.0, 200.0));
+        assert_eq!(click(&mut app), Some(inset));
+
+        // Like the hidden tabs of the egui_dock example
+        app.world_mut().get_mut::<Camera>(inset).unwrap().is_active = false;
+        let (camera_under_cursor, entity) = cameras_under_cursor(&mut app);
+        assert_eq!(camera_under_cursor.map(|camera| camera.entity), Some(full));
+        assert_eq!(entity, Some(full));
+        assert_eq!(click(&mut app), Some(full));
+        app.world_mut().insert_resource(ActiveCameraData::default());
+        app.world_mut().send_event(MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x: 0.0,
+            y: 1.0,
+            window,
+        });
+        app.update();
+        assert_eq!(
+            app.world().resource::<ActiveCameraData>().entity,
+            Some(full)
+        );
+        app.world_mut().insert_resource(ActiveCameraData::default());
+        app.world_mut().send_event(TouchInput {
+            phase: TouchPhase::Started,
+            position: Vec2::new(550.0, 350.0),
+            window,
+            force: None,
+            id: 0,
+        });
+        app.update();
+        assert_eq!(
+            app.world().resource::<ActiveCameraData>().entity,
+            Some(full)
+        );
+        app.world_mut().send_event(TouchInput {
+            phase: TouchPhase::Ended,
+            position: Vec2::new(550.0, 350.0),
+            window,
+            force: None,
+            id: 0,
+        });
+        app.update();
+
+        // No camera at all once they are all inactive
+        app.world_mut().get_mut::<Camera>(full).unwrap().is_active = false;
+        assert_eq!(cameras_under_cursor(&mut app), (None, None));
+        assert_eq!(click(&mut app), None);
+    }
+
+    /// Orbit camera with the plugin disabled, and the `yaw` and `pitch` of
+    /// its controller
+    fn spawn_disabled_camera(
+        app: &mut App,
+        handle_events_while_disabled: bool,
+    ) -> (Entity, (f32, f32)) {
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            Duration::from_millis(100),
+        ));
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 0.0, 5.0),
+                OrbitCameraController::default(),
+            ))
+            .id();
+        set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+        app.update();
+        app.insert_resource(BlendyCamerasConfig {
+            enabled: false,
+            handle_events_while_disabled,
+            ..default()
+        });
+        (camera, yaw_pitch(app, camera))
+    }
+
+    fn yaw_pitch(app: &App, camera: Entity) -> (f32, f32) {
+        let controller =
+            app.world().get::<OrbitCameraController>(camera).unwrap();
+        (controller.yaw.unwrap(), controller.pitch.unwrap())
+    }
+
+    fn send_viewpoint(
+        app: &mut App,
+        camera: Entity,
+        viewpoint: Viewpoint,
+        duration: Option<Duration>,
+    ) {
+        app.world_mut().send_event(ViewpointEvent {
+            camera_entity: Some(camera),
+            viewpoint,
+            duration,
+            relative_to: None,
+        });
+    }
+
+    fn set_enabled(app: &mut App, enabled: bool) {
+        app.world_mut()
+            .resource_mut::<BlendyCamerasConfig>()
+            .enabled = enabled;
+    }
+
+    #[test]
+    fn events_sent_while_disabled_are_dropped() {
+        let mut app = test_app();
+        let (camera, start) = spawn_disabled_camera(&mut app, false);
+        let transform = *app.world().get::<Transform>(camera).unwrap();
+        send_viewpoint(
+            &mut app,
+            camera,
+            Viewpoint::User {
+                yaw: 1.0,
+                pitch: 0.5,
+            },
+            None,
+        );
+        app.world_mut().send_event(SwitchProjection {
+            camera_entity: camera,
+        });
+        app.update();
+        app.update();
+        set_enabled(&mut app, true);
+        app.update();
+        app.update();
+        let world = app.world();
+        assert_eq!(yaw_pitch(&app, camera), start);
+        assert_eq!(*world.get::<Transform>(camera).unwrap(), transform);
+        assert!(matches!(
+            world.get::<Projection>(camera).unwrap(),
+            Projection::Perspective(_)
+        ));
+    }
+
+    #[test]
+    fn events_sent_while_disabled_are_handled_if_configured() {
+        let mut app = test_app();
+        let (camera, start) = spawn_disabled_camera(&mut app, true);
+        // Applied immediately, without the controllers
+        send_viewpoint(
+            &mut app,
+            camera,
+            Viewpoint::User {
+                yaw: 1.0,
+                pitch: 0.5,
+            },
+            None,
+        );
+        app.update();
+        assert_eq!(yaw_pitch(&app, camera), (1.0, 0.5));
+
+        // Transitions wait until the plugin is enabled again
+        send_viewpoint(
+            &mut app,
+            camera,
+            Viewpoint::User {
+                yaw: start.0,
+                pitch: start.1,
+            },
+            Some(Duration::from_millis(300)),
+        );
+        let transform = *app.world().get::<Transform>(camera).unwrap();
+        for _ in 0..5 {
+            app.update();
+        }
+        assert!(app.world().get::<ViewTransition>(camera).is_some());
+        assert_eq!(yaw_pitch(&app, camera), (1.0, 0.5));
+        assert_eq!(*app.world().get::<Transform>(camera).unwrap(), transform);
+        set_enabled(&mut app, true);
+        for _ in 0..5 {
+            app.update();
+        }
+        assert!(app.world().get::<ViewTransition>(camera).is_none());
+        let (yaw, pitch) = yaw_pitch(&app, camera);
+        assert!((yaw - start.0).abs() < 1e-5 && (pitch - start.1).abs() < 1e-5);
+        assert_transform_approx_eq(
+            app.world().get::<Transform>(camera).unwrap(),
+            &Transform::from_xyz(0.0, 0.0, 5.0),
+            1e-4,
+        );
+    }
+
+    /// App activating the cameras on hover, with an orbit camera in the left
+    /// half of the window and a fly camera in the right half
+    fn spawn_hover_cameras(app: &mut App) -> (Entity, Entity, Entity) {
+        app.insert_resource(BlendyCamerasConfig {
+            activation_mode: ActivationMode::OnHover,
+            ..default()
+        });
+        let window = spawn_window(app);
+        let left = spawn_camera(app, URect::new(0, 0, 400, 600), 0);
+        let right = spawn_camera(app, URect::new(400, 0, 800, 600), 0);
+        app.world_mut()
+            .entity_mut(right)
+            .remove::<OrbitCameraController>()
+            .insert(FlyCameraController::default());
+        update_viewports(app);
+        (window, left, right)
+    }
+
+    fn active_camera(app: &App) -> &ActiveCameraData {
+        app.world().resource::<ActiveCameraData>()
+    }
+
+    #[test]
+    fn hovered_camera_becomes_the_active_camera() {
+        let mut app = test_app();
+        let (window, left, right) = spawn_hover_cameras(&mut app);
+        set_cursor_position(&mut app, window, Vec2::new(100.0, 300.0));
+        app.update();
+        assert_eq!(active_camera(&app).entity, Some(left));
+        set_cursor_position(&mut app, window, Vec2::new(700.0, 300.0));
+        app.update();
+        assert_eq!(active_camera(&app).entity, Some(right));
+    }
+
+    #[test]
+    fn leaving_the_viewports_keeps_the_active_camera() {
+        let mut app = test_app();
+        let (window, _, right) = spawn_hover_cameras(&mut app);
+        set_cursor_position(&mut app, window, Vec2::new(700.0, 300.0));
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_key(KeyCode::KeyE);
+        app.update();
+        assert_eq!(active_camera(&app).entity, Some(right));
+        assert_eq!(
+            active_camera(&app).interaction,
+            Some(CameraInteraction::FlyMove)
+        );
+        app.world_mut()
+            .get_mut::<Window>(window)
+            .unwrap()
+            .set_cursor_position(None);
+        let transform = *app.world().get::<Transform>(right).unwrap();
+        app.update();
+        app.update();
+        assert_eq!(active_camera(&app).entity, Some(right));
+        assert_eq!(
+            active_camera(&app).interaction,
+            Some(CameraInteraction::FlyMove)
+        );
+        // Still moving with the keys
+        assert_ne!(*app.world().get::<Transform>(right).unwrap(), transform);
+    }
+
+    /// Switch a perspective camera at `radius` from the focus to the
+    /// orthographic projection created from `scaling_mode`, and return the
+    /// orthographic scale and the view size at the focus before and after
+    fn switch_to_orthographic(
+        scaling_mode: ScalingMode,
+        radius: f32,
+        viewport_size: Vec2,
+    ) -> (f32, Vec2, Vec2) {
+        let projection = Projection::default();
+        let config = DefaultOrthographicConfig {
+            scaling_mode,
+            ..default()
+        };
+        let other_projection =
+            OtherProjection::default_for(&projection, &config);
+        let Projection::Orthographic(ref ortho) = *other_projection.get()
+        else {
+            panic!("the other projection is not orthographic");
+        };
+        // `ScalingMode` is not `PartialEq`
+        assert_eq!(
+            format!("{:?}", ortho.scaling_mode),
+            format!("{scaling_mode:?}")
+        );
+        let scale = utils::switched_projection_radius(
+            &projection,
+            other_projection.get(),
+            radius,
+            Some(viewport_size),
+        );
+        let Projection::Perspective(ref perspective) = projection else {
+            unreachable!();
+        };
+        let height = 2.0 * radius * (perspective.fov / 2.0).tan();
+        let aspect_ratio = viewport_size.x / viewport_size.y;
+        let perspective_size = Vec2::new(height * aspect_ratio, height);
+        let ortho_size =
+            utils::orthographic_view_size(ortho, scale, Some(viewport_size));
+        (scale, perspective_size, ortho_size)
+    }
+
+    #[test]
+    fn switch_to_window_size_orthographic_keeps_the_view_size() {
+        let viewport_size = Vec2::new(1024.0, 512.0);
+        let (scale, perspective_size, ortho_size) = switch_to_orthographic(
+            ScalingMode::WindowSize,
+            10.0,
+            viewport_size,
+        );
+        // One world unit per pixel at a scale of 1
+        assert!((scale - perspective_size.y / viewport_size.y).abs() < 1e-5);
+        assert!(ortho_size.abs_diff_eq(perspective_size, 1e-3));
+    }
+
+    #[test]
+    fn switch_to_fixed_horizontal_orthographic_keeps_the_view_size() {
+        let viewport_size = Vec2::new(1024.0, 512.0);
+        let (scale, perspective_size, ortho_size) = switch_to_orthographic(
+            ScalingMode::FixedHorizontal {
+                viewport_width: 4.0,
+            },
+            10.0,
+            viewport_size,
+        );
+        assert!((scale - perspective_size.x / 4.0).abs() < 1e-5);
+        assert!(ortho_size.abs_diff_eq(perspective_size, 1e-3));
     }
 }
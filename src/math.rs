@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+
+use crate::orbit::OrthoPlacement;
+
+/// Radius never goes under this value, a null radius causes problems
+const MIN_RADIUS: f32 = 0.05;
+
+/// Yaw, pitch and radius of a camera at `translation` orbiting around
+/// `focus`, assuming it looks at the focus.
+///
+/// The yaw is the rotation in radian around the global Y axis, `0.0` when the
+/// camera is on the positive Z side of the focus, increasing toward the
+/// positive X side. The yaw is in `(-PI, PI]`, negative on the negative X
+/// side of the focus. The pitch is the rotation in radian around the local X
+/// axis, positive when the camera is above the focus and looks down. The
+/// radius is the distance to the focus, never under `0.05`.
+pub fn yaw_pitch_radius_from_translation(
+    translation: Vec3,
+    focus: Vec3,
+) -> (f32, f32, f32) {
+    let comp_vec = translation - focus;
+    let radius = comp_vec.length().max(MIN_RADIUS);
+    let yaw = if comp_vec.x == 0.0 && comp_vec.z >= 0.0 {
+        0.0
+    } else {
+        comp_vec.x.atan2(comp_vec.z)
+    };
+    let pitch = (comp_vec.y / radius).asin();
+    (yaw, pitch, radius)
+}
+
+/// Yaw, pitch and radius of a camera with `transform` orbiting around
+/// `focus`, with the same conventions as
+/// [`yaw_pitch_radius_from_translation`]. The yaw and pitch come from the
+/// rotation of the camera, which may not look at the focus, and any roll is
+/// ignored.
+pub fn yaw_pitch_radius_from_transform(
+    transform: &Transform,
+    focus: Vec3,
+) -> (f32, f32, f32) {
+    let (yaw, pitch, _roll) = transform.rotation.to_euler(EulerRot::YXZ);
+    let radius = transform.translation.distance(focus).max(MIN_RADIUS);
+    (yaw, -pitch, radius)
+}
+
+/// Transform of a camera orbiting around `focus` at `radius`, looking at the
+/// focus, with the yaw and pitch conventions of
+/// [`yaw_pitch_radius_from_translation`]. This is the inverse of
+/// [`yaw_pitch_radius_from_transform`].
+pub fn camera_transform_from_orbit(
+    yaw: f32,
+    pitch: f32,
+    radius: f32,
+    focus: Vec3,
+) -> Transform {
+    let mut transform = Transform::IDENTITY;
+    transform.rotation =
+        Quat::from_rotation_y(yaw) * Quat::from_rotation_x(-pitch);
+    transform.translation = focus + transform.back() * radius;
+    transform
+}
+
+/// Update `transform` based on yaw, pitch, and the camera's focus and radius,
+/// like the [`OrbitCameraController`](crate::OrbitCameraController) does.
+/// If the projection is orthographic, the radius is its scale and the camera
+/// is placed according to `ortho_placement`.
+pub fn update_orbit_transform(
+    yaw: f32,
+    pitch: f32,
+    mut radius: f32,
+    focus: Vec3,
+    transform: &mut Transform,
+    projection: &mut Projection,
+    ortho_placement: OrthoPlacement,
+) {
+    if let Projection::Orthographic(ref mut p) = *projection {
+        p.scale = radius;
+        radius = ortho_placement.distance(p);
+    }
+    *transform = camera_transform_from_orbit(yaw, pitch, radius, focus);
+}
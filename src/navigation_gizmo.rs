@@ -0,0 +1,353 @@
+use std::{f32::consts::PI, time::Duration};
+
+use bevy::{
+    color::palettes::css::WHITE, prelude::*,
+    render::camera::NormalizedRenderTarget, window::PrimaryWindow,
+};
+
+use crate::{
+    input::CameraInputBlocked, orbit::OrbitCameraController,
+    BlendyCamerasDisabled, Viewpoint, ViewpointDetectionConfig, ViewpointEvent,
+};
+
+/// Component to show a navigation gizmo in a corner of the viewport of the
+/// camera, like the one of Blender's viewport. Clicking an axis handle moves
+/// the camera to the viewpoint looking from this axis, or to the opposite
+/// viewpoint if already there. Dragging the gizmo orbits the camera if it
+/// has an enabled [`OrbitCameraController`].
+///
+/// The gizmo is drawn with the [`NavigationGizmoConfigGroup`] gizmos, just in
+/// front of the camera. Requires the `navigation_gizmo` cargo feature.
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+#[require(NavigationGizmoState)]
+pub struct ShowNavigationGizmo {
+    /// Diameter of the gizmo, in logical pixels
+    pub size: f32,
+    /// Distance between the gizmo and the top right corner of the viewport,
+    /// in logical pixels
+    pub margin: f32,
+    /// Mouse button used to click and drag the gizmo
+    pub button: MouseButton,
+    /// Duration of the animated transition to the clicked viewpoint. The
+    /// camera is moved immediately if `None`
+    pub transition_duration: Option<Duration>,
+}
+
+impl Default for ShowNavigationGizmo {
+    fn default() -> Self {
+        Self {
+            size: 80.0,
+            margin: 8.0,
+            button: MouseButton::Left,
+            transition_duration: Some(Duration::from_millis(250)),
+        }
+    }
+}
+
+/// Gizmos used to draw the navigation gizmo, see [`ShowNavigationGizmo`].
+/// Change its `render_layers` to hide it from other cameras.
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct NavigationGizmoConfigGroup;
+
+/// Hovered handle and current drag of the navigation gizmo of a camera
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub(crate) struct NavigationGizmoState {
+    hovered: Option<Vec3>,
+    drag: Option<GizmoDrag>,
+}
+
+/// Press of the mouse button on the navigation gizmo
+#[derive(Debug, Clone, Copy)]
+struct GizmoDrag {
+    /// Cursor position when pressed, in logical viewport coordinates
+    start: Vec2,
+    /// Last cursor position, in logical viewport coordinates
+    last: Vec2,
+    /// Handle under the cursor when pressed
+    axis: Option<Vec3>,
+    /// Whether the cursor moved far enough to orbit instead of clicking
+    is_dragging: bool,
+}
+
+/// Axes of the gizmo and their colors. The negative axes are dimmed.
+const GIZMO_AXES: [(Vec3, Srgba); 6] = [
+    (Vec3::X, Srgba::rgb(0.9, 0.27, 0.31)),
+    (Vec3::Y, Srgba::rgb(0.43, 0.75, 0.16)),
+    (Vec3::Z, Srgba::rgb(0.24, 0.51, 0.9)),
+    (Vec3::NEG_X, Srgba::rgb(0.45, 0.14, 0.16)),
+    (Vec3::NEG_Y, Srgba::rgb(0.22, 0.37, 0.08)),
+    (Vec3::NEG_Z, Srgba::rgb(0.12, 0.25, 0.45)),
+];
+
+/// Distance, in logical pixels, the cursor must move for a press to become
+/// a drag
+const DRAG_THRESHOLD: f32 = 3.0;
+
+/// Radius of the axis handles, relative to the size of the gizmo
+const HANDLE_RADIUS_FACTOR: f32 = 0.1;
+
+/// Layout of the gizmo in the viewport of a camera
+struct GizmoLayout {
+    /// Center, in logical viewport coordinates
+    center: Vec2,
+    /// Distance between the center and the center of the handles, in
+    /// logical pixels
+    axis_length: f32,
+    /// Radius of the handles, in logical pixels
+    handle_radius: f32,
+}
+
+impl GizmoLayout {
+    fn new(gizmo: &ShowNavigationGizmo, camera: &Camera) -> Option<Self> {
+        let viewport_size = camera.logical_viewport_size()?;
+        let radius = gizmo.size / 2.0;
+        let handle_radius = gizmo.size * HANDLE_RADIUS_FACTOR;
+        Some(Self {
+            center: Vec2::new(
+                viewport_size.x - gizmo.margin - radius,
+                gizmo.margin + radius,
+            ),
+            axis_length: radius - handle_radius,
+            handle_radius,
+        })
+    }
+
+    /// Position of the handle of `axis`, in logical viewport coordinates,
+    /// and its distance toward the viewer
+    fn handle(&self, camera_rotation: Quat, axis: Vec3) -> (Vec2, f32) {
+        // In view space, `z` points toward the viewer
+        let view_axis = camera_rotation.inverse() * axis;
+        let position = self.center
+            + Vec2::new(view_axis.x, -view_axis.y) * self.axis_length;
+        (position, view_axis.z)
+    }
+
+    /// The front most axis whose handle is at `position`, in logical
+    /// viewport coordinates
+    fn hovered_axis(
+        &self,
+        camera_rotation: Quat,
+        position: Vec2,
+    ) -> Option<Vec3> {
+        GIZMO_AXES
+            .iter()
+            .map(|(axis, _)| (*axis, self.handle(camera_rotation, *axis)))
+            .filter(|(_, (handle_position, _))| {
+                handle_position.distance(position) <= self.handle_radius
+            })
+            .max_by(|(_, (_, a)), (_, (_, b))| a.total_cmp(b))
+            .map(|(axis, _)| axis)
+    }
+
+    /// Whether `position`, in logical viewport coordinates, is over the gizmo
+    fn contains(&self, position: Vec2) -> bool {
+        self.center.distance(position) <= self.axis_length + self.handle_radius
+    }
+}
+
+/// Viewpoint looking from `axis`
+fn axis_viewpoint(axis: Vec3) -> Viewpoint {
+    match axis {
+        Vec3::X => Viewpoint::Right,
+        Vec3::NEG_X => Viewpoint::Left,
+        Vec3::Y => Viewpoint::Top,
+        Vec3::NEG_Y => Viewpoint::Bottom,
+        Vec3::NEG_Z => Viewpoint::Back,
+        _ => Viewpoint::Front,
+    }
+}
+
+/// Handle the hovering, clicking and dragging of the navigation gizmos
+#[allow(clippy::type_complexity)]
+pub(crate) fn navigation_gizmo_input_system(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut input_blocked: ResMut<CameraInputBlocked>,
+    detection_config: Res<ViewpointDetectionConfig>,
+    mut viewpoint_ev_write: EventWriter<ViewpointEvent>,
+    mut cameras: Query<
+        (
+            Entity,
+            &ShowNavigationGizmo,
+            &mut NavigationGizmoState,
+            &Camera,
+            &Transform,
+            Option<&mut OrbitCameraController>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    windows: Query<&Window>,
+) {
+    let primary_window = primary_window.get_single().ok();
+    for (entity, gizmo, mut state, camera, transform, controller_opt) in
+        cameras.iter_mut()
+    {
+        let cursor_position = match camera.target.normalize(primary_window) {
+            Some(NormalizedRenderTarget::Window(window_ref)) => windows
+                .get(window_ref.entity())
+                .ok()
+                .and_then(Window::cursor_position),
+            _ => None,
+        };
+        let viewport_min = camera
+            .logical_viewport_rect()
+            .map(|rect| rect.min)
+            .unwrap_or_default();
+        let (Some(layout), Some(position)) = (
+            GizmoLayout::new(gizmo, camera).filter(|_| camera.is_active),
+            cursor_position.map(|position| position - viewport_min),
+        ) else {
+            if state.hovered.is_some() || state.drag.is_some() {
+                *state = NavigationGizmoState::default();
+            }
+            continue;
+        };
+        let hovered = layout.hovered_axis(transform.rotation, position);
+        if state.hovered != hovered {
+            state.hovered = hovered;
+        }
+        if mouse_input.just_pressed(gizmo.button) && layout.contains(position) {
+            state.drag = Some(GizmoDrag {
+                start: position,
+                last: position,
+                axis: hovered,
+                is_dragging: false,
+            });
+        }
+        let Some(mut drag) = state.drag else {
+            continue;
+        };
+        // The cameras must not react to a press on the gizmo
+        input_blocked.block_until_released();
+        if mouse_input.pressed(gizmo.button) {
+            drag.is_dragging |= drag.start.distance(position) > DRAG_THRESHOLD;
+            if drag.is_dragging {
+                if let Some(mut controller) = controller_opt
+                    .filter(|controller| controller.is_enabled)
+                    .filter(|controller| controller.is_initialized)
+                {
+                    let delta = position - drag.last;
+                    controller.yaw = controller
+                        .yaw
+                        .map(|yaw| yaw - delta.x / gizmo.size * PI);
+                    controller.pitch = controller
+                        .pitch
+                        .map(|pitch| pitch + delta.y / gizmo.size * PI);
+                    controller.force_update = true;
+                }
+            }
+            drag.last = position;
+            state.drag = Some(drag);
+            continue;
+        }
+        state.drag = None;
+        if drag.is_dragging {
+            continue;
+        }
+        let Some(axis) = drag.axis.filter(|axis| hovered == Some(*axis)) else {
+            continue;
+        };
+        // Clicking the axis the camera already looks from flips the view
+        let viewpoint =
+            Viewpoint::from_transform(transform, detection_config.epsilon);
+        let axis = if viewpoint == axis_viewpoint(axis) {
+            -axis
+        } else {
+            axis
+        };
+        viewpoint_ev_write.send(ViewpointEvent {
+            camera_entity: Some(entity),
+            viewpoint: axis_viewpoint(axis),
+            duration: gizmo.transition_duration,
+            relative_to: None,
+        });
+    }
+}
+
+/// Draw the navigation gizmos just in front of their cameras, once the
+/// transforms are propagated
+pub(crate) fn draw_navigation_gizmos_system(
+    mut gizmos: Gizmos<NavigationGizmoConfigGroup>,
+    cameras: Query<
+        (
+            &ShowNavigationGizmo,
+            &NavigationGizmoState,
+            &Camera,
+            &GlobalTransform,
+            &Projection,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+) {
+    for (gizmo, state, camera, global_transform, projection) in cameras.iter() {
+        if !camera.is_active {
+            continue;
+        }
+        let Some(layout) = GizmoLayout::new(gizmo, camera) else {
+            continue;
+        };
+        let camera_position = global_transform.translation();
+        let forward = global_transform.forward();
+        // World position of a point of the viewport at `depth` in front of
+        // the camera
+        let world_point = |position: Vec2, depth: f32| {
+            let ray =
+                camera.viewport_to_world(global_transform, position).ok()?;
+            let distance = (depth
+                - (ray.origin - camera_position).dot(*forward))
+                / ray.direction.dot(*forward);
+            Some(ray.origin + *ray.direction * distance)
+        };
+        // Size of a logical pixel at a depth of 1
+        let Some(pixel_size) = world_point(layout.center, 1.0)
+            .zip(world_point(layout.center + Vec2::X, 1.0))
+            .map(|(a, b)| a.distance(b))
+        else {
+            continue;
+        };
+        // Stay in front of the near plane, including the back handles
+        let extent = layout.axis_length + layout.handle_radius;
+        let (depth, scale) = match projection {
+            Projection::Perspective(p) => {
+                let relative_extent = (extent * pixel_size).min(0.9);
+                let depth = p.near * 1.5 / (1.0 - relative_extent);
+                (depth, pixel_size * depth)
+            }
+            Projection::Orthographic(p) => {
+                (p.near + extent * pixel_size * 1.5, pixel_size)
+            }
+        };
+        let Some(center) = world_point(layout.center, depth) else {
+            continue;
+        };
+        let rotation = global_transform.to_scale_rotation_translation().1;
+        let mut handles = GIZMO_AXES.map(|(axis, color)| {
+            let (_, view_depth) = layout.handle(rotation, axis);
+            (axis, color, view_depth)
+        });
+        handles.sort_by(|(_, _, a), (_, _, b)| a.total_cmp(b));
+        if state.drag.is_some_and(|drag| drag.is_dragging) {
+            gizmos.circle(
+                Isometry3d::new(center, rotation),
+                extent * scale,
+                WHITE.with_alpha(0.3),
+            );
+        }
+        for (axis, color, _) in handles {
+            let handle = center + axis * layout.axis_length * scale;
+            if axis.max_element() > 0.0 {
+                gizmos.line(center, handle, color);
+            }
+            let isometry = Isometry3d::new(handle, rotation);
+            gizmos.circle(isometry, layout.handle_radius * scale, color);
+            if state.hovered == Some(axis) {
+                gizmos.circle(
+                    isometry,
+                    layout.handle_radius * scale * 0.6,
+                    WHITE,
+                );
+            }
+        }
+    }
+}
@@ -1,26 +1,56 @@
-use std::f32::consts::PI;
-
-use bevy::{
-    ecs::component::StorageType, prelude::*, render::camera::ScalingMode,
+use std::{
+    borrow::Cow,
+    f32::consts::{FRAC_PI_4, PI},
 };
 
+use bevy::{ecs::component::StorageType, prelude::*};
+
 use crate::{
-    input::{self, MouseKeyTracker},
-    raycast::{get_cursor_ray, get_nearest_intersection},
-    utils, ActiveCameraData, OtherProjection,
+    auto_rotate::AutoRotate,
+    bookmarks::ViewBookmark,
+    control_target::{ControlTarget, ControlTargetArm, ControlTargets},
+    default_settings::DefaultControllerSettings,
+    depth::DepthSamples,
+    input::{
+        self, BindingAction, BindingConflict, CameraInputBlocked,
+        CameraInputState, HorizontalScrollAction, InputBinding,
+        SensitivityCurve, ZoomAcceleration,
+    },
+    math,
+    raycast::{
+        get_cursor_ray, get_nearest_included_intersection, get_position_ray,
+        RaycastFilter, ViewportCursor,
+    },
+    switch_camera_projection,
+    synchronized_views::SynchronizedViews,
+    transition::ViewTransition,
+    utils, ActiveCameraData, AutoOrthographic, BlendyCamerasDisabled,
+    CameraViewChanged, DefaultOrthographicConfig, OtherProjection,
+    ProjectionSwitched, ViewChangeCause, Viewpoint, DEFAULT_ORBIT_RADIUS,
 };
 
 /// Component to tag an entiy as able to be controlled by orbiting, panning
 /// and zooming.
 /// The entity must have `Transform` and `Projection` components. Typically
 /// you would add `Camera3d` to this entity.
+#[derive(Clone, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
 pub struct OrbitCameraController {
     /// The point the camera looks at. The camera also orbit around and zoom
     /// to that point if `auto_depth` and `zoom_to_mouse_position` are not set.
     /// This is updated when panning or when zooming to the mouse position
     /// or when zooming or orbiting when `auto_depth` is set.
+    /// In world space, also when the camera is the child of a stationary
+    /// entity.
     pub focus: Vec3,
-    /// The distance between the camera and the `focus`.
+    /// The distance between the camera and the `focus`, or the projection's
+    /// scale in the case of using an orthographic camera. Converted when
+    /// switching the projection to keep the same framing at the `focus`.
     /// If set to `None`, it will be calculated from the camera's current
     /// position during intialization.
     /// Automatically updated
@@ -42,20 +72,139 @@ pub struct OrbitCameraController {
     /// get stuck at 0.
     /// Defaults to `0.05`.
     pub zoom_lower_limit: f32,
+    /// What zooming changes. Defaults to moving the camera toward the focus.
+    pub zoom_mode: ZoomMode,
+    /// Lower limit, in radian, on the field of view of the perspective
+    /// projection when zooming with [`ZoomMode::Fov`].
+    /// Defaults to 5 degrees.
+    pub fov_lower_limit: f32,
+    /// Upper limit, in radian, on the field of view of the perspective
+    /// projection when zooming with [`ZoomMode::Fov`].
+    /// Defaults to 120 degrees.
+    pub fov_upper_limit: f32,
+    /// Where the camera is placed when using an orthographic projection,
+    /// which does not change the view but what is clipped by the near plane.
+    /// Defaults to halfway between the clipping planes.
+    pub ortho_placement: OrthoPlacement,
     /// Sentitivity of the orbiting motion
     pub orbit_sensitivity: f32,
     /// Sentitivity of the panning motion
     pub pan_sensitivity: f32,
+    /// Plane the focus moves along when panning
+    pub pan_mode: PanMode,
+    /// Entity moved by the controller, the camera itself by default
+    pub control_target: ControlTarget,
     /// Sentitivity of the zooming motion
     pub zoom_sensitivity: f32,
-    /// Mouse button used to orbit the camera
+    /// Fraction of the radius, or of the field of view with
+    /// [`ZoomMode::Fov`], zoomed for each line of scroll. Also scales the
+    /// zoom by dragging and with the keys. Must be between 0 and 1.
+    pub zoom_step: f32,
+    /// Accelerate the zoom when scrolling several lines in a row, like many
+    /// CAD applications. Disabled by default.
+    pub zoom_acceleration: Option<ZoomAcceleration>,
+    /// Sentitivity of the zooming motion when dragging to zoom
+    pub zoom_drag_sensitivity: f32,
+    /// Sentitivity of the orbiting motion with a one finger drag on a touch
+    /// screen
+    pub touch_orbit_sensitivity: f32,
+    /// Sentitivity of the panning motion with a two fingers drag on a touch
+    /// screen
+    pub touch_pan_sensitivity: f32,
+    /// Sentitivity of the zooming motion when pinching on a touch screen
+    pub touch_zoom_sensitivity: f32,
+    /// Response to the orbiting motion, applied before `orbit_sensitivity`
+    pub orbit_curve: SensitivityCurve,
+    /// Response to the panning motion, applied before `pan_sensitivity`
+    pub pan_curve: SensitivityCurve,
+    /// Response to the scroll and to the zoom by dragging, applied before
+    /// `zoom_sensitivity` and `zoom_drag_sensitivity`
+    pub zoom_curve: SensitivityCurve,
+    /// Mouse button used to orbit the camera. Ignored if `orbit_bindings` is
+    /// not empty.
     pub button_orbit: MouseButton,
-    /// Key that must be pressed for the `button_orbit` to work
+    /// Key that must be pressed for the `button_orbit` to work. Ignored if
+    /// `orbit_bindings` is not empty.
     pub modifier_orbit: Option<KeyCode>,
-    /// Mouse button used to pan the camera
+    /// Mouse button used to pan the camera. Ignored if `pan_bindings` is not
+    /// empty.
     pub button_pan: MouseButton,
-    /// Key that must be pressed for the `button_pan` to work
+    /// Key that must be pressed for the `button_pan` to work. Ignored if
+    /// `pan_bindings` is not empty.
     pub modifier_pan: Option<KeyCode>,
+    /// Mouse button used to zoom the camera by dragging vertically. Ignored
+    /// if `zoom_bindings` is not empty.
+    pub button_zoom: MouseButton,
+    /// Key that must be pressed for the `button_zoom` to work. Ignored if
+    /// `zoom_bindings` is not empty.
+    pub modifier_zoom: Option<KeyCode>,
+    /// Key used to pan the camera to the left
+    pub key_pan_left: Option<KeyCode>,
+    /// Key used to pan the camera to the right
+    pub key_pan_right: Option<KeyCode>,
+    /// Key used to pan the camera up
+    pub key_pan_up: Option<KeyCode>,
+    /// Key used to pan the camera down
+    pub key_pan_down: Option<KeyCode>,
+    /// Key used to zoom in
+    pub key_zoom_in: Option<KeyCode>,
+    /// Key used to zoom out
+    pub key_zoom_out: Option<KeyCode>,
+    /// Speed of the keyboard panning, in fraction of the distance to the
+    /// `focus` per second
+    pub key_pan_sensitivity: f32,
+    /// Speed of the keyboard zooming, in mouse wheel lines per second
+    pub key_zoom_sensitivity: f32,
+    /// Smooth the zoom when scrolling: each mouse wheel detent is animated
+    /// and the pixel scroll of trackpads is integrated over time, instead of
+    /// zooming in discrete steps each frame
+    pub smooth_scroll: bool,
+    /// What to do with the horizontal scroll of trackpads and tilt wheels.
+    /// Defaults to panning.
+    pub horizontal_scroll_action: HorizontalScrollAction,
+    /// Bindings used to orbit the camera, any of them works. If empty,
+    /// `button_orbit` and `modifier_orbit` are used instead.
+    pub orbit_bindings: Vec<InputBinding>,
+    /// Bindings used to pan the camera, any of them works. If empty,
+    /// `button_pan` and `modifier_pan` are used instead.
+    pub pan_bindings: Vec<InputBinding>,
+    /// Bindings used to zoom the camera by dragging vertically, any of them
+    /// works. If empty, `button_zoom` and `modifier_zoom` are used instead.
+    pub zoom_bindings: Vec<InputBinding>,
+    /// Key that, while held, keeps the bindings of the controller from
+    /// matching, e.g. while a tool of the application uses the same mouse
+    /// button. Pressing it during a drag ends the drag as if the button was
+    /// released. Defaults to `None`.
+    pub suppress_modifier: Option<KeyCode>,
+    /// Key that, while held during an orbit drag, snaps the yaw and pitch
+    /// to the nearest 45 degrees increment. No snapping if `None`.
+    pub axis_snap_while_orbiting: Option<KeyCode>,
+    /// Switch to the orthographic projection when a [`ViewpointEvent`] or
+    /// [`SnapToNearestViewpointEvent`] moves the camera to an axis viewpoint,
+    /// and back to perspective when orbiting away from it. A manual
+    /// [`SwitchProjection`] cancels the automatic switch back.
+    ///
+    /// [`ViewpointEvent`]: crate::ViewpointEvent
+    /// [`SnapToNearestViewpointEvent`]: crate::SnapToNearestViewpointEvent
+    /// [`SwitchProjection`]: crate::SwitchProjection
+    pub auto_ortho_on_viewpoint: bool,
+    /// Viewpoint the rotation of the camera is locked to, e.g. for the side
+    /// views of a quad view. The orbit input is ignored, or pans with
+    /// `pan_on_locked_orbit`, and the camera keeps looking from this
+    /// viewpoint while panning and zooming. A [`ViewpointEvent`] or
+    /// [`SnapToNearestViewpointEvent`] targeting the camera changes the
+    /// locked viewpoint. Setting it back to `None` leaves the camera where
+    /// it is.
+    ///
+    /// [`ViewpointEvent`]: crate::ViewpointEvent
+    /// [`SnapToNearestViewpointEvent`]: crate::SnapToNearestViewpointEvent
+    pub locked_viewpoint: Option<Viewpoint>,
+    /// Pan with the orbit input when the viewpoint is locked, see
+    /// `locked_viewpoint`
+    pub pan_on_locked_orbit: bool,
+    /// Slowly orbit around the focus when the user does not interact with
+    /// the camera, e.g. in a product viewer. Disabled by default.
+    pub auto_rotate: Option<AutoRotate>,
     /// Do not control the camera if `false`
     pub is_enabled: bool,
     /// Whether [`OrbitCameraController`] has been initialized
@@ -66,22 +215,249 @@ pub struct OrbitCameraController {
     /// mouse cursor while moving the camera. This will cause the camera to
     /// orbit around the geometry under the mouse cursor and zoom speed beeing
     /// relative to the distance to this geometry point.
+    ///
+    /// The geometry is only raycasted when a drag starts or when scrolling,
+    /// not on every frame, and the entities used can be chosen with
+    /// [`RaycastExclude`](crate::RaycastExclude) and
+    /// [`RaycastIncludeOnly`](crate::RaycastIncludeOnly).
     pub auto_depth: bool,
-    /// Wrap the mouse cursor while rotating or panning if `true`.
-    /// Because wrapping is not working on all platfrom or with all windowing
-    /// system, this will also cause a mouse grab/lock.
-    pub wrap_cursor: bool,
+    /// Make the panning speed relative to the depth of the geometry under the
+    /// mouse cursor when the pan starts, instead of the distance to the
+    /// focus, so that panning across a nearby object while zoomed far out is
+    /// not too fast. Falls back to the distance to the focus when there is no
+    /// geometry under the cursor. Only applies to perspective projections.
+    pub auto_depth_pan: bool,
+    /// Stop the focus in front of the geometry while panning with a drag,
+    /// instead of moving it through walls or behind the camera. The pan
+    /// translation is raycasted from the focus with the same entities as
+    /// `auto_depth`, and the part of it along the surface that was hit is
+    /// kept so that the focus slides along it.
+    pub pan_collision: bool,
+    /// Distance kept between the focus and the geometry, see `pan_collision`
+    pub pan_collision_offset: f32,
+    /// Where the geometry under the mouse cursor used by `auto_depth`,
+    /// `auto_depth_pan` and `zoom_to_mouse_position` comes from
+    pub auto_depth_source: AutoDepthSource,
+    /// Wrap the mouse cursor while rotating or panning, at the edges of the
+    /// viewport or of the window. Because wrapping is not working on all
+    /// platfrom or with all windowing system, this will also cause a mouse
+    /// grab/lock.
+    pub wrap_cursor: CursorWrapMode,
+    /// Hide the mouse cursor while orbiting, panning or zooming by dragging
+    pub hide_cursor_during_drag: bool,
+    /// Move the mouse cursor back to where the drag started when it ends
+    pub restore_cursor_on_release: bool,
+    /// Key cancelling the current drag, moving the camera back to where it
+    /// was when the drag started. The drag ends and the input is ignored
+    /// until the mouse buttons are released.
+    pub key_cancel_drag: Option<KeyCode>,
+    /// Also cancel the current drag with a click of the right mouse button,
+    /// see `key_cancel_drag`
+    pub cancel_drag_on_right_click: bool,
+    /// Distance, in logical pixels, the mouse must move before orbiting,
+    /// panning or zooming by dragging starts, so that a click with a button
+    /// also used for selection does not move the camera. The motion is then
+    /// applied in full. The cursor is grabbed, wrapped or hidden once the
+    /// drag starts. Defaults to `0.0`, starting the drag immediately.
+    pub drag_start_threshold_px: f32,
+    /// Automatically adjust the near and far planes of the camera projections
+    /// (including the one saved for the projection switch) according to the
+    /// distance to the focus, when zooming and framing. If `false`, the
+    /// projections are only modified when framing with an orthographic
+    /// projection, extending the planes so that the framed entities are not
+    /// clipped.
+    pub auto_clip_planes: bool,
     /// Whether the camera is currently upside down. Inverting the direction
     /// of rotation to be more intuitive.
     /// Automatically updated
     pub is_upside_down: bool,
     /// Whether to update the camera's transform regardless of whether there
     /// are any changes/input.
-    /// Set this to `true` if you want to modify values directly.
-    /// This will be automatically set back to `false` after one frame.
+    /// Set this to `true` if you want to modify values directly, or use
+    /// [`Self::set_yaw_pitch`], [`Self::set_viewpoint`] or [`Self::look_at`]
+    /// which set it. This will be automatically set back to `false` after
+    /// one frame.
     pub force_update: bool,
 }
 
+/// Where the mouse cursor wraps around while dragging, see
+/// [`OrbitCameraController::wrap_cursor`]. Wrapping only happens for drags
+/// that started inside the viewport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum CursorWrapMode {
+    /// Wrap at the edges of the viewport of the camera
+    #[default]
+    Viewport,
+    /// Wrap at the edges of the window, e.g. when the viewport is surrounded
+    /// by UI panels
+    Window,
+    /// Do not wrap the cursor
+    None,
+}
+
+impl From<bool> for CursorWrapMode {
+    fn from(wrap_cursor: bool) -> Self {
+        if wrap_cursor {
+            Self::Viewport
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// Where the geometry under the mouse cursor comes from, see
+/// [`OrbitCameraController::auto_depth_source`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum AutoDepthSource {
+    /// Raycast the meshes on the CPU
+    #[default]
+    Raycast,
+    /// Read back the depth buffer of the camera under the cursor, which also
+    /// works for skinned or displaced geometry and dense meshes. Requires
+    /// the `depth_buffer` cargo feature, a `Camera3d` and `Msaa::Off`.
+    ///
+    /// The depth is read back a frame or two late, the most recent sample is
+    /// used when a drag starts or when scrolling. Raycasting is used instead
+    /// when no sample close enough to the cursor is available.
+    DepthBuffer,
+}
+
+/// What zooming changes, see [`OrbitCameraController::zoom_mode`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum ZoomMode {
+    /// Move the camera toward the focus, changing the radius
+    #[default]
+    Dolly,
+    /// Change the field of view of the perspective projection, like
+    /// Blender's "Zoom Lens", without moving the camera nor changing the
+    /// parallax. The zoom to the mouse position is ignored. Orthographic
+    /// projections change their scale as with `Dolly`.
+    Fov,
+}
+
+/// Where an orthographic camera is placed along its view direction, see
+/// [`OrbitCameraController::ortho_placement`]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum OrthoPlacement {
+    /// Halfway between the near and far planes away from the focus, so that
+    /// the objects around the focus are not clipped
+    #[default]
+    ClipPlanesMiddle,
+    /// At the radius away from the focus, i.e. at a distance equal to the
+    /// scale of the projection, moving with the zoom like a perspective
+    /// camera
+    AtRadius,
+    /// At a fixed distance from the focus
+    Fixed(f32),
+}
+
+impl OrthoPlacement {
+    /// Distance between the focus and a camera with the orthographic
+    /// `projection`
+    pub fn distance(&self, projection: &OrthographicProjection) -> f32 {
+        match *self {
+            Self::ClipPlanesMiddle => (projection.near + projection.far) / 2.0,
+            Self::AtRadius => projection.scale,
+            Self::Fixed(distance) => distance,
+        }
+    }
+}
+
+/// Component overriding the point an [`OrbitCameraController`] orbits
+/// around, e.g. to orbit around the selected entities like the "Orbit Around
+/// Selection" preference of Blender. Update it whenever the selection
+/// changes.
+///
+/// The point is read when an orbit drag starts and takes precedence over the
+/// geometry under the cursor of `auto_depth`, which takes precedence over
+/// the focus. Changing or removing the override during a drag has no effect
+/// until the next one.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct OrbitPivotOverride {
+    /// Point to orbit around, in world space
+    pub point: Vec3,
+    /// Also zoom toward the point, taking precedence over the mouse position
+    /// of `zoom_to_mouse_position`
+    pub affects_zoom: bool,
+}
+
+/// Plane the focus moves along when panning, see
+/// [`OrbitCameraController::pan_mode`]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum PanMode {
+    /// Move the focus parallel to the view plane
+    #[default]
+    ViewPlane,
+    /// Slide the focus along the world plane with the given `normal` going
+    /// through the focus, regardless of the pitch of the camera. E.g. with
+    /// `Vec3::Y`, panning stays on the ground like in architectural viewers.
+    WorldPlane {
+        /// Normal of the plane, does not need to be normalized
+        normal: Vec3,
+    },
+}
+
+impl PanMode {
+    /// Map a translation of the focus parallel to the view plane to the
+    /// pan plane, moving along the view direction `forward` so that the
+    /// focus stays under the same screen position
+    fn project(self, translation: Vec3, forward: Vec3) -> Vec3 {
+        let PanMode::WorldPlane { normal } = self else {
+            return translation;
+        };
+        let Some(normal) = normal.try_normalize() else {
+            return translation;
+        };
+        // Clamp the motion when the view direction is nearly parallel to the
+        // plane, where it would be amplified without limit
+        let forward_dot = forward.dot(normal);
+        let forward_dot = if forward_dot.abs() < MIN_PAN_PLANE_DOT {
+            MIN_PAN_PLANE_DOT.copysign(forward_dot)
+        } else {
+            forward_dot
+        };
+        let along_view =
+            translation - forward * (translation.dot(normal) / forward_dot);
+        // Remove what is left out of the plane after clamping
+        along_view - normal * along_view.dot(normal)
+    }
+}
+
+/// Minimum absolute cosine of the angle between the view direction and the
+/// normal of the pan plane, see [`PanMode::WorldPlane`]
+const MIN_PAN_PLANE_DOT: f32 = 0.1;
+
 impl Component for OrbitCameraController {
     const STORAGE_TYPE: StorageType = StorageType::Table;
 
@@ -90,29 +466,35 @@ impl Component for OrbitCameraController {
     ) {
         hooks
             .on_add(|mut world, entity, _component_id| {
-                let projection = world.get::<Projection>(entity).unwrap();
-                let other_projection = match projection {
-                    Projection::Perspective(_) => {
-                        Projection::Orthographic(OrthographicProjection {
-                            scaling_mode: ScalingMode::FixedVertical {
-                                viewport_height: 1.0,
-                            },
-                            ..OrthographicProjection::default_3d()
-                        })
-                    }
-                    Projection::Orthographic(_) => {
-                        Projection::Perspective(PerspectiveProjection {
-                            ..default()
-                        })
-                    }
+                world
+                    .commands()
+                    .entity(entity)
+                    .insert_if_new(CameraInputState::default());
+                let Some(projection) = world.get::<Projection>(entity) else {
+                    warn!(
+                        "OrbitCameraController added to {entity} without a \
+                        Projection, the projection switch is set up once the \
+                        Projection is added"
+                    );
+                    return;
                 };
+                let config = world
+                    .get_resource::<DefaultOrthographicConfig>()
+                    .cloned()
+                    .unwrap_or_default();
+                let other_projection =
+                    OtherProjection::default_for(projection, &config);
+                // Keep the saved projection when spawned from a scene
                 world
                     .commands()
                     .entity(entity)
-                    .insert(OtherProjection(other_projection));
+                    .insert_if_new(other_projection);
             })
             .on_remove(|mut world, entity, _component_id| {
-                world.commands().entity(entity).remove::<OtherProjection>();
+                world
+                    .commands()
+                    .entity(entity)
+                    .remove::<(OtherProjection, AutoOrthographic)>();
             });
     }
 }
@@ -125,18 +507,64 @@ impl Default for OrbitCameraController {
             yaw: None,
             pitch: None,
             zoom_lower_limit: 0.05,
+            zoom_mode: ZoomMode::Dolly,
+            fov_lower_limit: 5f32.to_radians(),
+            fov_upper_limit: 120f32.to_radians(),
+            ortho_placement: OrthoPlacement::ClipPlanesMiddle,
             orbit_sensitivity: 1.0,
             pan_sensitivity: 1.0,
+            pan_mode: PanMode::ViewPlane,
+            control_target: ControlTarget::SelfEntity,
             zoom_sensitivity: 1.0,
+            zoom_step: 0.2,
+            zoom_acceleration: None,
+            zoom_drag_sensitivity: 1.0,
+            touch_orbit_sensitivity: 1.0,
+            touch_pan_sensitivity: 1.0,
+            touch_zoom_sensitivity: 1.0,
+            orbit_curve: SensitivityCurve::Linear,
+            pan_curve: SensitivityCurve::Linear,
+            zoom_curve: SensitivityCurve::Linear,
             button_orbit: MouseButton::Middle,
             modifier_orbit: None,
             button_pan: MouseButton::Middle,
             modifier_pan: Some(KeyCode::ShiftLeft),
+            button_zoom: MouseButton::Middle,
+            modifier_zoom: Some(KeyCode::ControlLeft),
+            key_pan_left: None,
+            key_pan_right: None,
+            key_pan_up: None,
+            key_pan_down: None,
+            key_zoom_in: None,
+            key_zoom_out: None,
+            key_pan_sensitivity: 1.0,
+            key_zoom_sensitivity: 5.0,
+            smooth_scroll: false,
+            horizontal_scroll_action: HorizontalScrollAction::Pan,
+            orbit_bindings: Vec::new(),
+            pan_bindings: Vec::new(),
+            zoom_bindings: Vec::new(),
+            suppress_modifier: None,
+            axis_snap_while_orbiting: None,
+            auto_ortho_on_viewpoint: false,
+            locked_viewpoint: None,
+            pan_on_locked_orbit: false,
+            auto_rotate: None,
             is_enabled: true,
             is_initialized: false,
             zoom_to_mouse_position: true,
             auto_depth: true,
-            wrap_cursor: true,
+            auto_depth_pan: false,
+            pan_collision: false,
+            pan_collision_offset: 0.1,
+            auto_depth_source: AutoDepthSource::Raycast,
+            wrap_cursor: CursorWrapMode::Viewport,
+            hide_cursor_during_drag: false,
+            restore_cursor_on_release: false,
+            key_cancel_drag: Some(KeyCode::Escape),
+            cancel_drag_on_right_click: false,
+            drag_start_threshold_px: 0.0,
+            auto_clip_planes: false,
             is_upside_down: false,
             force_update: false,
         }
@@ -144,66 +572,585 @@ impl Default for OrbitCameraController {
 }
 
 impl OrbitCameraController {
+    /// Controller with the orbit settings of `settings`, to be initialized
+    /// from the transform of the camera like a default one
+    pub fn from_defaults(settings: &DefaultControllerSettings) -> Self {
+        Self {
+            radius: None,
+            yaw: None,
+            pitch: None,
+            is_initialized: false,
+            is_upside_down: false,
+            force_update: false,
+            ..settings.orbit.clone()
+        }
+    }
+
+    /// Replace the settings, e.g. the sensitivities and bindings, with the
+    /// ones of `settings`, keeping the state of the controller: the focus,
+    /// the orbit, whether it is enabled and its control target
+    pub fn apply_settings(&mut self, settings: &OrbitCameraController) {
+        *self = Self {
+            focus: self.focus,
+            radius: self.radius,
+            yaw: self.yaw,
+            pitch: self.pitch,
+            control_target: self.control_target,
+            locked_viewpoint: self.locked_viewpoint,
+            is_enabled: self.is_enabled,
+            is_initialized: self.is_initialized,
+            is_upside_down: self.is_upside_down,
+            force_update: self.force_update,
+            ..settings.clone()
+        };
+    }
+
+    /// The bindings used to orbit the camera, either `orbit_bindings` or
+    /// the binding made of `button_orbit` and `modifier_orbit`
+    pub fn orbit_bindings(&self) -> Cow<'_, [InputBinding]> {
+        if self.orbit_bindings.is_empty() {
+            // Do not orbit when the pan or zoom modifier is pressed as they
+            // usually share the same button
+            Cow::Owned(vec![InputBinding::from_button_and_modifiers(
+                self.button_orbit,
+                self.modifier_orbit,
+                &[self.modifier_pan, self.modifier_zoom],
+            )])
+        } else {
+            Cow::Borrowed(&self.orbit_bindings)
+        }
+    }
+
+    /// The bindings used to pan the camera, either `pan_bindings` or the
+    /// binding made of `button_pan` and `modifier_pan`
+    pub fn pan_bindings(&self) -> Cow<'_, [InputBinding]> {
+        if self.pan_bindings.is_empty() {
+            Cow::Owned(vec![InputBinding::from_button_and_modifiers(
+                self.button_pan,
+                self.modifier_pan,
+                &[self.modifier_orbit, self.modifier_zoom],
+            )])
+        } else {
+            Cow::Borrowed(&self.pan_bindings)
+        }
+    }
+
+    /// The bindings used to zoom the camera by dragging, either
+    /// `zoom_bindings` or the binding made of `button_zoom` and
+    /// `modifier_zoom`
+    pub fn zoom_bindings(&self) -> Cow<'_, [InputBinding]> {
+        if self.zoom_bindings.is_empty() {
+            Cow::Owned(vec![InputBinding::from_button_and_modifiers(
+                self.button_zoom,
+                self.modifier_zoom,
+                &[self.modifier_orbit, self.modifier_pan],
+            )])
+        } else {
+            Cow::Borrowed(&self.zoom_bindings)
+        }
+    }
+
+    /// Whether one of the bindings used for orbiting is active
+    pub fn is_orbit_pressed(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_pressed(
+            &self.orbit_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+
+    /// Whether one of the bindings used for orbiting has just been activated
+    pub fn is_orbit_just_pressed(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_just_pressed(
+            &self.orbit_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+
+    /// Whether one of the bindings used for orbiting has just been deactivated
+    pub fn is_orbit_just_released(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_just_released(
+            &self.orbit_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+
+    /// Whether one of the bindings used for panning is active
+    pub fn is_pan_pressed(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_pressed(
+            &self.pan_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+
+    /// Whether one of the bindings used for panning has just been activated
+    pub fn is_pan_just_pressed(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_just_pressed(
+            &self.pan_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+
+    /// Whether one of the bindings used for panning has just been deactivated
+    pub fn is_pan_just_released(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_just_released(
+            &self.pan_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+
+    /// Whether one of the bindings used for zooming by dragging is active
+    pub fn is_zoom_pressed(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_pressed(
+            &self.zoom_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+
+    /// Whether one of the bindings used for zooming by dragging has just been activated
+    pub fn is_zoom_just_pressed(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_just_pressed(
+            &self.zoom_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+
+    /// Whether one of the bindings used for zooming by dragging has just been deactivated
+    pub fn is_zoom_just_released(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_just_released(
+            &self.zoom_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+
+    /// Whether one of the keyboard panning or zooming keys is pressed
+    pub fn is_key_pan_zoom_pressed(
+        &self,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        self.key_pan_zoom_keys().any(|key| key_input.pressed(key))
+    }
+
+    /// Whether one of the keyboard panning or zooming keys has just been
+    /// pressed
+    pub fn is_key_pan_zoom_just_pressed(
+        &self,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        self.key_pan_zoom_keys()
+            .any(|key| key_input.just_pressed(key))
+    }
+
+    fn key_pan_zoom_keys(&self) -> impl Iterator<Item = KeyCode> {
+        [
+            self.key_pan_left,
+            self.key_pan_right,
+            self.key_pan_up,
+            self.key_pan_down,
+            self.key_zoom_in,
+            self.key_zoom_out,
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Find the orbit, pan and zoom bindings that can be triggered by the same
+    /// input. When both are active, orbiting takes precedence over panning,
+    /// which takes precedence over zooming.
+    pub fn validate_bindings(&self) -> Vec<BindingConflict> {
+        input::find_binding_conflicts(
+            &[
+                (BindingAction::Orbit, &self.orbit_bindings()),
+                (BindingAction::Pan, &self.pan_bindings()),
+                (BindingAction::Zoom, &self.zoom_bindings()),
+            ],
+            true,
+        )
+    }
+
+    /// Settings emulating a 3 button mouse like Blender does, for laptops
+    /// without a middle mouse button. In addition to the middle mouse
+    /// button, orbit with Alt + left mouse button, pan with Shift + Alt +
+    /// left mouse button and zoom with Ctrl + Alt + left mouse button
+    /// vertical drag.
+    pub fn blender_emulated_3_button() -> Self {
+        let middle = || InputBinding::mouse(MouseButton::Middle);
+        let alt_left = || {
+            InputBinding::mouse(MouseButton::Left)
+                .with_modifier(KeyCode::AltLeft)
+        };
+        Self {
+            orbit_bindings: vec![
+                middle()
+                    .without_modifier(KeyCode::ShiftLeft)
+                    .without_modifier(KeyCode::ControlLeft),
+                alt_left()
+                    .without_modifier(KeyCode::ShiftLeft)
+                    .without_modifier(KeyCode::ControlLeft),
+            ],
+            pan_bindings: vec![
+                middle()
+                    .with_modifier(KeyCode::ShiftLeft)
+                    .without_modifier(KeyCode::ControlLeft),
+                alt_left()
+                    .with_modifier(KeyCode::ShiftLeft)
+                    .without_modifier(KeyCode::ControlLeft),
+            ],
+            zoom_bindings: vec![
+                middle()
+                    .with_modifier(KeyCode::ControlLeft)
+                    .without_modifier(KeyCode::ShiftLeft),
+                alt_left()
+                    .with_modifier(KeyCode::ControlLeft)
+                    .without_modifier(KeyCode::ShiftLeft),
+            ],
+            ..default()
+        }
+    }
+
+    /// Orbit to `yaw` and `pitch`, in radian, with the conventions of
+    /// [`math::yaw_pitch_radius_from_translation`]. The camera transform is
+    /// updated by the controller system.
+    pub fn set_yaw_pitch(&mut self, yaw: f32, pitch: f32) {
+        self.yaw = Some(yaw);
+        self.pitch = Some(pitch);
+        self.mark_updated();
+    }
+
+    /// Orbit to look from `viewpoint`. The camera transform is updated by the
+    /// controller system.
+    pub fn set_viewpoint(&mut self, viewpoint: Viewpoint) {
+        let (yaw, pitch) = viewpoint.to_yaw_pitch();
+        self.set_yaw_pitch(yaw, pitch);
+    }
+
+    /// Look at `target`, orbiting around it. If `eye` is given, the camera
+    /// is placed there, otherwise it keeps its yaw, pitch and radius. The
+    /// radius is the scale of an orthographic projection. The camera
+    /// transform is updated by the controller system.
+    pub fn look_at(&mut self, target: Vec3, eye: Option<Vec3>) {
+        self.focus = target;
+        if let Some(eye) = eye {
+            let (yaw, pitch, radius) =
+                math::yaw_pitch_radius_from_translation(eye, target);
+            self.yaw = Some(yaw);
+            self.pitch = Some(pitch);
+            self.radius = Some(radius);
+        }
+        self.mark_updated();
+    }
+
+    /// Have the controller system apply the values, there is nothing left to
+    /// initialize once they are all known
+    fn mark_updated(&mut self) {
+        self.force_update = true;
+        if self.yaw.is_some() && self.pitch.is_some() && self.radius.is_some() {
+            self.is_initialized = true;
+        }
+    }
+
     pub(crate) fn initialize_if_necessary(
         &mut self,
         transform: &mut Transform,
         projection: &mut Projection,
     ) {
         if !self.is_initialized {
-            let (yaw, pitch, radius) =
-                utils::calculate_from_translation_and_focus(
-                    transform.translation,
-                    self.focus,
-                );
+            let (yaw, pitch, radius) = math::yaw_pitch_radius_from_translation(
+                transform.translation,
+                self.focus,
+            );
             let &mut yaw = self.yaw.get_or_insert(yaw);
             let &mut pitch = self.pitch.get_or_insert(pitch);
             let &mut radius = self.radius.get_or_insert(radius);
-            utils::update_orbit_transform(
-                yaw, pitch, radius, self.focus, transform, projection,
+            math::update_orbit_transform(
+                yaw,
+                pitch,
+                radius,
+                self.focus,
+                transform,
+                projection,
+                self.ortho_placement,
             );
             self.is_initialized = true;
         }
     }
 }
 
+/// Set up the projection switch of the orbit cameras whose `Projection` was
+/// added after the [`OrbitCameraController`]
+#[allow(clippy::type_complexity)]
+pub(crate) fn add_missing_other_projection_system(
+    mut commands: Commands,
+    cameras: Query<
+        (Entity, &Projection),
+        (With<OrbitCameraController>, Without<OtherProjection>),
+    >,
+    config: Option<Res<DefaultOrthographicConfig>>,
+) {
+    for (entity, projection) in cameras.iter() {
+        let config = config.as_deref().cloned().unwrap_or_default();
+        commands
+            .entity(entity)
+            .insert_if_new(OtherProjection::default_for(projection, &config));
+    }
+}
+
+/// Initialize the orbit state of the controllers just added to cameras
+/// controlling themselves, so that the controller system can skip them
+/// until there is input. The cameras with a parent or a control target, and
+/// the controllers reset by user code, are initialized by the controller
+/// system from their world transform.
+#[allow(clippy::type_complexity)]
+pub(crate) fn initialize_orbit_controllers_system(
+    mut cameras: Query<
+        (&mut OrbitCameraController, &mut Transform, &mut Projection),
+        (
+            Added<OrbitCameraController>,
+            Without<Parent>,
+            Without<BlendyCamerasDisabled>,
+        ),
+    >,
+) {
+    for (mut controller, mut transform, mut projection) in cameras.iter_mut() {
+        if controller.is_initialized
+            || controller.control_target != ControlTarget::SelfEntity
+        {
+            continue;
+        }
+        controller.initialize_if_necessary(&mut transform, &mut projection);
+    }
+}
+
+/// Run condition of the orbit controller system: the active camera has
+/// input, or a controller changed, e.g. it was added, reset, or updated by
+/// an event, a transition or the automatic rotation. Otherwise all the
+/// cameras are idle and the system is skipped entirely.
+#[allow(clippy::type_complexity)]
+pub(crate) fn orbit_controllers_need_update(
+    active_cam: Res<ActiveCameraData>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    key_input: Res<ButtonInput<KeyCode>>,
+    input_states: Query<&CameraInputState>,
+    changed_controllers: Query<
+        (),
+        (
+            With<OrbitCameraController>,
+            Or<(
+                Changed<OrbitCameraController>,
+                Changed<SynchronizedViews>,
+                Changed<ControlTargetArm>,
+            )>,
+        ),
+    >,
+    moved_arms: Query<
+        (),
+        (
+            With<OrbitCameraController>,
+            With<ControlTargetArm>,
+            Changed<Transform>,
+        ),
+    >,
+    removed_disabled: RemovedComponents<BlendyCamerasDisabled>,
+) -> bool {
+    input::active_camera_has_pending_input(
+        &active_cam,
+        &input_states,
+        &mouse_input,
+        &key_input,
+    ) || !changed_controllers.is_empty()
+        || !moved_arms.is_empty()
+        || !removed_disabled.is_empty()
+}
+
+/// Upper limit of [`OrbitCameraController::zoom_step`], so that zooming in
+/// never reaches the focus in one step
+const MAX_ZOOM_STEP: f32 = 0.95;
+
+/// Minimum cosine of the angle between the view direction and the direction
+/// of the pivot, about 75 degrees, for the zoom to the mouse position with a
+/// perspective projection. Beyond it, the zoom is centered.
+const MIN_ZOOM_TO_MOUSE_COS: f32 = 0.25;
+
+/// Point under the mouse cursor when a drag starts or when scrolling
+#[derive(Default)]
+pub(crate) struct DragPivot {
+    /// Point the camera orbits around and zooms to
+    point: Vec3,
+    /// Point of the [`OrbitPivotOverride`] when the orbit started, if any
+    orbit_override: Option<Vec3>,
+    /// Point of the [`OrbitPivotOverride`] when the zoom started, if any
+    zoom_override: Option<Vec3>,
+    /// Depth of the geometry under the cursor when the pan started, if any
+    pan_depth: Option<f32>,
+}
+
 #[allow(clippy::too_many_arguments)]
 fn orbit_camera(
+    entity: Entity,
     controller: &mut Mut<OrbitCameraController>,
     camera: &Camera,
     windows: &Query<&Window>,
-    transform: &Mut<Transform>,
+    transform: &Transform,
     global_transform: &GlobalTransform,
-    projection: &Mut<Projection>,
+    projection: &mut Mut<Projection>,
+    pivot_override: Option<&OrbitPivotOverride>,
+    locked_viewpoint: Option<Viewpoint>,
+    viewport_cursor: Option<&ViewportCursor>,
     active_cam: &Res<ActiveCameraData>,
     key_input: &Res<ButtonInput<KeyCode>>,
     mouse_input: &Res<ButtonInput<MouseButton>>,
-    mouse_key_tracker: &Res<MouseKeyTracker>,
-    pivot_point: &mut Local<Vec3>,
+    input_blocked: &CameraInputBlocked,
+    input_state: &CameraInputState,
+    pivot: &mut Local<DragPivot>,
+    snapped_orbit: &mut Local<Option<Vec2>>,
     ray_cast: &mut MeshRayCast,
+    raycast_filter: &RaycastFilter,
+    depth_samples: &DepthSamples,
+    delta_secs: f32,
 ) -> bool {
+    let is_pan_start =
+        input::pan_just_pressed(controller, mouse_input, key_input)
+            || input_state.touch_just_started;
+    if is_pan_start {
+        pivot.pan_depth = None;
+    }
+    let is_orbit_start =
+        input::orbit_just_pressed(controller, mouse_input, key_input)
+            || input_state.touch_just_started;
+    let is_zoom_start =
+        input::zoom_just_pressed(controller, mouse_input, key_input)
+            || input::key_pan_zoom_just_pressed(controller, key_input)
+            || input_state.touch_just_started
+            || input_state.scroll_line != 0.0
+            || input_state.scroll_pixel != 0.0;
+    // Captured when the drag starts, so that changing the override does not
+    // make the camera jump during the drag
+    if is_orbit_start {
+        pivot.orbit_override = pivot_override.map(|value| value.point);
+    }
+    if is_zoom_start {
+        pivot.zoom_override = pivot_override
+            .filter(|value| value.affects_zoom)
+            .map(|value| value.point);
+    }
     // Update pivot point when needed
-    if (controller.auto_depth || controller.zoom_to_mouse_position)
-        && (input::orbit_just_pressed(controller, mouse_input, key_input)
-            || input::pan_just_pressed(controller, mouse_input, key_input)
-            || mouse_key_tracker.scroll_line != 0.0
-            || mouse_key_tracker.scroll_pixel != 0.0)
+    if ((controller.auto_depth || controller.zoom_to_mouse_position)
+        && (is_pan_start || is_orbit_start || is_zoom_start))
+        || (controller.auto_depth_pan && is_pan_start)
     {
-        let cursor_ray = active_cam
+        let window_opt = active_cam
             .window_entity
-            .and_then(|window_entity| windows.get(window_entity).ok())
-            .and_then(|window| {
+            .and_then(|window_entity| windows.get(window_entity).ok());
+        let cursor_ray = match input_state.touch_position {
+            // Use the gesture centroid as the pivot when using touch
+            Some(touch_position) => {
+                get_position_ray(camera, global_transform, touch_position)
+            }
+            None => window_opt.and_then(|window| {
                 get_cursor_ray(camera, global_transform, window)
-            });
+            }),
+        };
         if let Some(cursor_ray) = cursor_ray {
-            if let Some((_entity, hit)) =
-                get_nearest_intersection(ray_cast, cursor_ray)
-            {
-                **pivot_point = hit.point;
+            let depth_point = match controller.auto_depth_source {
+                AutoDepthSource::DepthBuffer => window_opt
+                    .and_then(|window| {
+                        input_state
+                            .touch_position
+                            .or_else(|| window.cursor_position())
+                            .map(|position| position * window.scale_factor())
+                    })
+                    .and_then(|position| {
+                        depth_samples.world_point(
+                            entity,
+                            camera,
+                            global_transform,
+                            position,
+                        )
+                    }),
+                AutoDepthSource::Raycast => None,
+            };
+            // Reuse the raycast of the cursor made this frame, if any
+            let cursor_hit = viewport_cursor
+                .filter(|cursor| {
+                    cursor.position().is_some()
+                        && input_state.touch_position.is_none()
+                })
+                .map(|cursor| cursor.hit().map(|hit| hit.position));
+            let hit_point = depth_point.or_else(|| {
+                cursor_hit.unwrap_or_else(|| {
+                    get_nearest_included_intersection(
+                        ray_cast,
+                        cursor_ray,
+                        raycast_filter,
+                    )
+                    .map(|(_entity, hit)| hit.point)
+                })
+            });
+            if let Some(hit_point) = hit_point {
+                pivot.point = hit_point;
+                if controller.auto_depth_pan && is_pan_start {
+                    let depth = (hit_point - transform.translation)
+                        .dot(*transform.forward());
+                    pivot.pan_depth = Some(depth).filter(|depth| *depth > 0.0);
+                }
                 if controller.auto_depth {
                     let camera_transform = match **projection {
-                        Projection::Perspective(_) => **transform,
+                        Projection::Perspective(_) => *transform,
                         Projection::Orthographic(_) => {
-                            utils::camera_transform_form_orbit(
+                            math::camera_transform_from_orbit(
                                 controller.yaw.unwrap(),
                                 controller.pitch.unwrap(),
                                 controller.radius.unwrap(),
@@ -212,7 +1159,7 @@ fn orbit_camera(
                         }
                     };
                     let camera_to_pivot =
-                        **pivot_point - camera_transform.translation;
+                        pivot.point - camera_transform.translation;
                     let pivot_distance = camera_to_pivot.length();
                     let factor = camera_transform
                         .forward()
@@ -227,19 +1174,18 @@ fn orbit_camera(
                     controller.focus = new_focus;
                 }
             } else {
-                **pivot_point = match **projection {
+                pivot.point = match **projection {
                     // NOTE: cursor_ray.origin is not the camera
                     // position it is probably on the near plane
-                    Projection::Perspective(_) => {
-                        let factor = transform
-                            .forward()
-                            .dot(cursor_ray.direction.into());
-                        transform.translation
-                            + cursor_ray.direction
-                                * (controller.radius.unwrap() / factor)
-                    }
+                    Projection::Perspective(_) => focus_plane_pivot(
+                        transform,
+                        cursor_ray.direction,
+                        controller.radius.unwrap(),
+                        controller.focus,
+                    ),
                     Projection::Orthographic(ref p) => {
-                        let radius_minus_near = (p.far - p.near) / 2.0;
+                        let radius_minus_near =
+                            controller.ortho_placement.distance(p) - p.near;
                         cursor_ray.origin
                             + cursor_ray.direction * radius_minus_near
                     }
@@ -247,24 +1193,59 @@ fn orbit_camera(
             }
         }
     }
-    let orbit = mouse_key_tracker.orbit * controller.orbit_sensitivity;
-    let mut pan = mouse_key_tracker.pan * controller.pan_sensitivity;
-    let scroll_line =
-        mouse_key_tracker.scroll_line * controller.zoom_sensitivity;
-    let scroll_pixel =
-        mouse_key_tracker.scroll_pixel * controller.zoom_sensitivity;
-    let orbit_button_changed = mouse_key_tracker.orbit_button_changed;
+    let (orbit, mut pan) = match locked_viewpoint {
+        Some(_) if controller.pan_on_locked_orbit => (
+            Vec2::ZERO,
+            controller
+                .pan_curve
+                .apply_motion(input_state.pan + input_state.orbit)
+                * controller.pan_sensitivity,
+        ),
+        Some(_) => (
+            Vec2::ZERO,
+            controller.pan_curve.apply_motion(input_state.pan)
+                * controller.pan_sensitivity,
+        ),
+        None => (
+            controller.orbit_curve.apply_motion(input_state.orbit)
+                * controller.orbit_sensitivity,
+            controller.pan_curve.apply_motion(input_state.pan)
+                * controller.pan_sensitivity,
+        ),
+    };
+    let zoom_curve = &controller.zoom_curve;
+    let scroll_line = zoom_curve.apply_value(input_state.scroll_line)
+        * controller.zoom_sensitivity;
+    let scroll_pixel = zoom_curve.apply_value(input_state.scroll_pixel)
+        * controller.zoom_sensitivity;
+    // The zoom drag is scaled to lines by the input tracking
+    let zoom_drag = zoom_curve.apply_value(input_state.zoom_drag)
+        * controller.zoom_drag_sensitivity;
+    let (key_pan, key_zoom) = if input_blocked.is_keyboard_blocked() {
+        (Vec2::ZERO, 0.0)
+    } else {
+        (
+            input::key_pan_direction(controller, key_input)
+                * controller.key_pan_sensitivity
+                * delta_secs,
+            input::key_zoom_direction(controller, key_input)
+                * controller.key_zoom_sensitivity
+                * delta_secs,
+        )
+    };
+    let orbit_button_changed = input_state.orbit_button_changed;
 
     if orbit_button_changed {
         let up = transform.rotation * Vec3::Y;
         controller.is_upside_down = up.y <= 0.0;
+        **snapped_orbit = None;
     }
     let mut has_moved = false;
     // TODO: Draw a sceen space 2D disk for rotation center
     if orbit.length_squared() > 0.0 {
         // Use window size for rotation otherwise the sensitivity
         // is far too high for small viewports
-        if let Some(win_size) = active_cam.window_size {
+        if let Some(win_size) = utils::non_empty_size(active_cam.window_size) {
             let delta_yaw = {
                 let delta = orbit.x / win_size.x * PI * 2.0;
                 if controller.is_upside_down {
@@ -276,11 +1257,30 @@ fn orbit_camera(
             let delta_pitch = orbit.y / win_size.y * PI;
             let pre_yaw = controller.yaw.unwrap();
             let pre_pitch = controller.pitch.unwrap();
+            let is_snapping = controller
+                .axis_snap_while_orbiting
+                .is_some_and(|key| key_input.pressed(key));
+            let (delta_yaw, delta_pitch) = if is_snapping {
+                // Accumulate the unsnapped orientation so that small mouse
+                // motions eventually reach the next increment
+                let unsnapped =
+                    snapped_orbit.get_or_insert(Vec2::new(pre_yaw, pre_pitch));
+                *unsnapped += Vec2::new(-delta_yaw, delta_pitch);
+                let yaw = utils::snap_angle(unsnapped.x, FRAC_PI_4);
+                let pitch = utils::snap_angle(unsnapped.y, FRAC_PI_4);
+                (pre_yaw - yaw, pitch - pre_pitch)
+            } else {
+                **snapped_orbit = None;
+                (delta_yaw, delta_pitch)
+            };
             controller.yaw = controller.yaw.map(|value| value - delta_yaw);
             controller.pitch =
                 controller.pitch.map(|value| value + delta_pitch);
-            if controller.auto_depth {
-                let mut transform_tmp = utils::camera_transform_form_orbit(
+            let orbit_pivot = pivot
+                .orbit_override
+                .or(Some(pivot.point).filter(|_| controller.auto_depth));
+            if let Some(orbit_pivot) = orbit_pivot {
+                let mut transform_tmp = math::camera_transform_from_orbit(
                     pre_yaw,
                     pre_pitch,
                     controller.radius.unwrap(),
@@ -291,7 +1291,7 @@ fn orbit_camera(
                 let pitch_global = transform_tmp.rotation
                     * pitch
                     * transform_tmp.rotation.inverse();
-                transform_tmp.rotate_around(**pivot_point, yaw * pitch_global);
+                transform_tmp.rotate_around(orbit_pivot, yaw * pitch_global);
                 controller.focus = transform_tmp.translation
                     + (transform_tmp.forward() * controller.radius.unwrap());
             }
@@ -300,59 +1300,123 @@ fn orbit_camera(
     }
     if pan.length_squared() > 0.0 {
         // Make panning distance independent of resolution and FOV,
-        if let Some(vp_size) = active_cam.viewport_size {
+        if let Some(vp_size) = utils::non_empty_size(active_cam.viewport_size) {
             let mut multiplier = 1.0;
             match **projection {
                 Projection::Perspective(ref p) => {
                     pan *= Vec2::new(p.fov * p.aspect_ratio, p.fov) / vp_size;
                     // Make panning proportional to distance away from
-                    // focus point
-                    if let Some(radius) = controller.radius {
-                        multiplier = radius;
+                    // focus point, or from the geometry under the cursor
+                    let depth_opt =
+                        pivot.pan_depth.filter(|_| controller.auto_depth_pan);
+                    if let Some(depth) = depth_opt.or(controller.radius) {
+                        multiplier = depth;
                     }
                 }
                 Projection::Orthographic(ref p) => {
-                    pan *= Vec2::new(p.area.width(), p.area.height()) / vp_size;
+                    // The area of the projection may be stale, e.g. after
+                    // zooming or resizing the viewport this frame
+                    let scale = controller.radius.unwrap_or(p.scale);
+                    pan *=
+                        utils::orthographic_view_size(p, scale, Some(vp_size))
+                            / vp_size;
                 }
             }
             // Translate by local axes
             let right = transform.rotation * Vec3::X * -pan.x;
             let up = transform.rotation * Vec3::Y * pan.y;
-            let translation = (right + up) * multiplier;
+            let mut translation = controller
+                .pan_mode
+                .project((right + up) * multiplier, *transform.forward());
+            let is_pan_drag =
+                input::pan_pressed(controller, mouse_input, key_input)
+                    || (locked_viewpoint.is_some()
+                        && controller.pan_on_locked_orbit
+                        && input::orbit_pressed(
+                            controller,
+                            mouse_input,
+                            key_input,
+                        ))
+                    || input_state.touch_position.is_some();
+            if controller.pan_collision && is_pan_drag {
+                translation = collide_pan(
+                    controller.focus,
+                    translation,
+                    controller.pan_collision_offset,
+                    ray_cast,
+                    raycast_filter,
+                );
+            }
+            controller.focus += translation;
+            has_moved = true;
+        }
+    }
+    if key_pan.length_squared() > 0.0 {
+        // Pan at a rate proportional to the distance to the focus, like
+        // panning with the mouse
+        if let Some(radius) = controller.radius {
+            let right = transform.rotation * Vec3::X * key_pan.x;
+            let up = transform.rotation * Vec3::Y * key_pan.y;
+            let translation = controller
+                .pan_mode
+                .project((right + up) * radius, *transform.forward());
             controller.focus += translation;
             has_moved = true;
         }
     }
-    if (scroll_line + scroll_pixel).abs() > 0.0 {
+    let zoom = scroll_line + scroll_pixel + zoom_drag + key_zoom;
+    // Factor applied to the radius for each line of scroll
+    let zoom_factor = 1.0 - controller.zoom_step.clamp(0.0, MAX_ZOOM_STEP);
+    let fov_opt = match **projection {
+        Projection::Perspective(ref p)
+            if controller.zoom_mode == ZoomMode::Fov =>
+        {
+            Some(p.fov)
+        }
+        _ => None,
+    };
+    if let (Some(fov), true) = (fov_opt, zoom.abs() > 0.0) {
+        let new_fov = (fov * zoom_factor.powf(zoom))
+            .clamp(controller.fov_lower_limit, controller.fov_upper_limit);
+        if new_fov != fov {
+            if let Projection::Perspective(ref mut p) = **projection {
+                p.fov = new_fov;
+            }
+            has_moved = true;
+        }
+    } else if zoom.abs() > 0.0 {
         let old_radius = controller.radius.unwrap();
-        // Calculate the impact of scrolling on the reference value
-        let line_delta = -scroll_line * old_radius * 0.2;
-        let pixel_delta = -scroll_pixel * old_radius * 0.2;
-        let radius_delta = line_delta + pixel_delta;
+        // Calculate the impact of scrolling on the reference value.
+        // Zooming is exponential so that zooming in several steps, e.g. when
+        // smoothing the scroll, ends at the same radius as zooming at once.
+        let radius_delta = old_radius * (zoom_factor.powf(zoom) - 1.0);
         // Update the target value
         controller.radius = controller.radius.map(|value| {
             (value + radius_delta).max(controller.zoom_lower_limit)
         });
+        // Only move the camera by what was applied, so that it does not go
+        // past the focus when the radius is clamped
+        let radius_delta = controller.radius.unwrap() - old_radius;
         // If it is pixel-based scrolling, add it directly to the
         // current value
         // controller.radius =
         //     controller.radius.map(|value| value + pixel_delta);
-        if controller.zoom_to_mouse_position {
+        if controller.zoom_to_mouse_position || pivot.zoom_override.is_some() {
+            let zoom_pivot = pivot.zoom_override.unwrap_or(pivot.point);
             // TODO: clean
             match **projection {
                 Projection::Perspective(_) => {
-                    let old_camera_pos = transform.translation;
-                    let old_camera_to_pivot = **pivot_point - old_camera_pos;
-                    let mouse_direction = old_camera_to_pivot.normalize();
-                    let factor = transform.forward().dot(mouse_direction);
-                    let new_camera_pos = old_camera_pos
-                        + mouse_direction * (-radius_delta / factor);
-                    let new_focus = new_camera_pos
-                        + transform.forward() * controller.radius.unwrap();
-                    controller.focus = new_focus;
+                    if let Some(new_focus) = zoom_to_pivot_focus(
+                        transform,
+                        zoom_pivot,
+                        radius_delta,
+                        controller.radius.unwrap(),
+                    ) {
+                        controller.focus = new_focus;
+                    }
                 }
                 Projection::Orthographic(_) => {
-                    let focus_to_pivot = **pivot_point - controller.focus;
+                    let focus_to_pivot = zoom_pivot - controller.focus;
                     let focus_to_pivot =
                         transform.rotation.inverse() * focus_to_pivot;
                     let focus_to_pivot = focus_to_pivot.xy();
@@ -369,51 +1433,203 @@ fn orbit_camera(
     has_moved
 }
 
-#[allow(clippy::too_many_arguments)]
+/// Point of the focus plane, at `radius` in front of a perspective camera
+/// with `transform`, under the cursor ray of `ray_direction`. The point under
+/// a grazing ray is too far away, the `focus` is returned instead.
+fn focus_plane_pivot(
+    transform: &Transform,
+    ray_direction: Dir3,
+    radius: f32,
+    focus: Vec3,
+) -> Vec3 {
+    let factor = transform.forward().dot(*ray_direction);
+    if factor < MIN_ZOOM_TO_MOUSE_COS {
+        focus
+    } else {
+        transform.translation + ray_direction * (radius / factor)
+    }
+}
+
+/// Focus of a perspective camera with `transform` after zooming toward
+/// `pivot`, changing the radius by `radius_delta` to `new_radius`. Moving
+/// toward a pivot far off axis would fling the focus sideways, `None` is
+/// returned to keep zooming to the focus instead.
+fn zoom_to_pivot_focus(
+    transform: &Transform,
+    pivot: Vec3,
+    radius_delta: f32,
+    new_radius: f32,
+) -> Option<Vec3> {
+    let mouse_direction = (pivot - transform.translation).normalize_or_zero();
+    let factor = transform.forward().dot(mouse_direction);
+    (factor >= MIN_ZOOM_TO_MOUSE_COS).then(|| {
+        let new_camera_pos =
+            transform.translation + mouse_direction * (-radius_delta / factor);
+        new_camera_pos + transform.forward() * new_radius
+    })
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub(crate) fn orbit_camera_controller_system(
+    mut commands: Commands,
+    time: Res<Time>,
     active_cam: Res<ActiveCameraData>,
     key_input: Res<ButtonInput<KeyCode>>,
     mouse_input: Res<ButtonInput<MouseButton>>,
-    mouse_key_tracker: Res<MouseKeyTracker>,
-    mut orbit_cameras: Query<(
-        Entity,
-        &mut OrbitCameraController,
-        &Camera,
-        &mut Transform,
-        &GlobalTransform,
-        &mut Projection,
-    )>,
+    input_blocked: Res<CameraInputBlocked>,
+    mut orbit_cameras: Query<
+        (
+            Entity,
+            &mut OrbitCameraController,
+            &Camera,
+            &mut Transform,
+            &GlobalTransform,
+            &mut Projection,
+            &CameraInputState,
+            Option<&mut OtherProjection>,
+            Has<AutoOrthographic>,
+            Option<&Parent>,
+            Option<&ControlTargetArm>,
+            Option<&OrbitPivotOverride>,
+            Option<&SynchronizedViews>,
+            Has<ViewTransition>,
+            Option<&ViewportCursor>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+    parents_query: Query<&GlobalTransform>,
+    mut control_targets: ControlTargets,
     windows: Query<&Window>,
-    mut pivot_point: Local<Vec3>,
+    mut pivot: Local<DragPivot>,
+    mut snapped_orbit: Local<Option<Vec2>>,
     mut ray_cast: MeshRayCast,
+    raycast_filter: RaycastFilter,
+    depth_samples: Res<DepthSamples>,
+    mut changed_ev_write: EventWriter<CameraViewChanged>,
     //mut gizmos: Gizmos,
 ) {
     for (
         entity,
         mut controller,
         camera,
-        mut transform,
+        mut local_transform,
         global_transform,
         mut projection,
+        input_state,
+        mut other_projection_opt,
+        is_auto_orthographic,
+        parent_opt,
+        arm_opt,
+        pivot_override,
+        synchronized_views,
+        is_transitioning,
+        viewport_cursor,
     ) in orbit_cameras.iter_mut()
     {
+        // Go back to the locked viewpoint, unless animated toward it, e.g.
+        // after it changed or after the values were modified directly
+        let locked_viewpoint = controller
+            .locked_viewpoint
+            .or(synchronized_views.and_then(|views| views.viewpoint));
+        if let Some(viewpoint) = locked_viewpoint {
+            let (yaw, pitch) = viewpoint.to_yaw_pitch();
+            if !is_transitioning
+                && controller.is_initialized
+                && (controller.yaw != Some(yaw)
+                    || controller.pitch != Some(pitch))
+            {
+                controller.set_yaw_pitch(yaw, pitch);
+            }
+        }
+        // Skip the idle cameras without touching the components, so that
+        // they are not marked as changed
+        let has_input = controller.is_enabled
+            && active_cam.entity == Some(entity)
+            && !input_blocked.is_blocked()
+            && input::has_pending_input(input_state, &mouse_input, &key_input);
+        let is_arm_moved = arm_opt.is_some_and(|arm| arm.0 != *local_transform);
+        if controller.is_initialized
+            && !controller.force_update
+            && !has_input
+            && !is_arm_moved
+        {
+            continue;
+        }
+        let target_opt = match controller.control_target {
+            ControlTarget::Entity(target_entity) => Some((
+                target_entity,
+                arm_opt.map_or(*local_transform, |arm| arm.0),
+            )),
+            ControlTarget::SelfEntity => None,
+        };
+        // The orbit is in world space, work on the world transform when the
+        // camera is a child entity. The parent is assumed to be stationary.
+        let parent_global_opt =
+            parent_opt.and_then(|parent| parents_query.get(parent.get()).ok());
+        let mut transform = match (target_opt, parent_global_opt) {
+            (Some((target_entity, arm)), _) => {
+                // Reported by the validation of the control targets
+                let Some(transform) =
+                    control_targets.camera_transform(target_entity, &arm)
+                else {
+                    continue;
+                };
+                transform
+            }
+            (None, Some(parent_global)) => parent_global
+                .mul_transform(*local_transform)
+                .compute_transform(),
+            (None, None) => *local_transform,
+        };
+        let start_transform = transform;
         controller.initialize_if_necessary(&mut transform, &mut projection);
+        let pre_view = (transform, utils::projection_zoom(&projection));
         let mut has_moved = false;
-        if controller.is_enabled && active_cam.entity == Some(entity) {
+        if has_input {
+            let pre_orbit = (controller.yaw, controller.pitch);
             has_moved = orbit_camera(
+                entity,
                 &mut controller,
                 camera,
                 &windows,
                 &transform,
                 global_transform,
-                &projection,
+                &mut projection,
+                pivot_override,
+                locked_viewpoint,
+                viewport_cursor,
                 &active_cam,
                 &key_input,
                 &mouse_input,
-                &mouse_key_tracker,
-                &mut pivot_point,
+                &input_blocked,
+                input_state,
+                &mut pivot,
+                &mut snapped_orbit,
                 &mut ray_cast,
+                &raycast_filter,
+                &depth_samples,
+                time.delta_secs(),
             );
+            // Orbiting away from the axis viewpoint reverts the automatic
+            // switch to orthographic
+            if is_auto_orthographic
+                && pre_orbit != (controller.yaw, controller.pitch)
+            {
+                if let Some(ref mut other_projection) = other_projection_opt {
+                    if let Projection::Orthographic(_) = *projection {
+                        switch_camera_projection(
+                            &mut controller,
+                            camera.logical_viewport_size(),
+                            &mut transform,
+                            &mut other_projection.0,
+                            &mut projection,
+                        );
+                    }
+                }
+                commands.entity(entity).remove::<AutoOrthographic>();
+                commands
+                    .send_event(ProjectionSwitched::new(entity, &projection));
+            }
             //gizmos.sphere(
             //    controller.focus,
             //    Quat::IDENTITY,
@@ -432,16 +1648,897 @@ pub(crate) fn orbit_camera_controller_system(
             (controller.yaw, controller.pitch, controller.radius)
         {
             if has_moved || controller.force_update {
-                utils::update_orbit_transform(
+                math::update_orbit_transform(
                     yaw,
                     pitch,
                     radius,
                     controller.focus,
                     &mut transform,
                     &mut projection,
+                    controller.ortho_placement,
                 );
+                if controller.auto_clip_planes {
+                    utils::update_clip_planes(radius, &mut projection);
+                    if let Some(ref mut other_projection) = other_projection_opt
+                    {
+                        utils::update_clip_planes(
+                            radius,
+                            &mut other_projection.0,
+                        );
+                    }
+                }
                 controller.force_update = false;
             }
         }
+        // Never write a poisoned transform, e.g. after a degenerate framing
+        if let Some(field) = non_finite_orbit_field(&controller, &transform) {
+            error!(
+                "Non finite {field} in the orbit controller of camera \
+                {entity}, recovering from the last finite transform. Last \
+                input: orbit {}, pan {}, scroll {} lines {} pixels, zoom \
+                drag {}, viewport size {:?}",
+                input_state.orbit,
+                input_state.pan,
+                input_state.scroll_line,
+                input_state.scroll_pixel,
+                input_state.zoom_drag,
+                active_cam.viewport_size,
+            );
+            recover_orbit_state(
+                &mut controller,
+                &start_transform,
+                &mut transform,
+                &mut projection,
+            );
+        }
+        if let Some((target_entity, arm)) = target_opt {
+            if transform != start_transform {
+                control_targets.set_camera_transform(
+                    target_entity,
+                    &arm,
+                    &transform,
+                );
+            }
+            // Undo the changes made by the events on the camera itself
+            local_transform.set_if_neq(arm);
+        } else if transform != start_transform {
+            *local_transform = match parent_global_opt {
+                Some(parent_global) => GlobalTransform::from(transform)
+                    .reparented_to(parent_global),
+                None => transform,
+            };
+        }
+        // Values re-applied by `force_update` do not change the view
+        if pre_view != (transform, utils::projection_zoom(&projection)) {
+            let cause = if !has_moved {
+                ViewChangeCause::Forced
+            } else if input_state.orbit != Vec2::ZERO {
+                ViewChangeCause::UserOrbit
+            } else if input_state.pan != Vec2::ZERO
+                || input::key_pan_direction(&controller, &key_input)
+                    != Vec2::ZERO
+            {
+                ViewChangeCause::UserPan
+            } else {
+                ViewChangeCause::UserZoom
+            };
+            if let Some(view) = ViewBookmark::from_camera(
+                &transform,
+                Some(&controller),
+                None,
+                &projection,
+            ) {
+                changed_ev_write
+                    .send(CameraViewChanged::new(entity, cause, &view));
+            }
+        }
+    }
+}
+
+/// Name of the first value of the orbit state or of the camera transform
+/// that is NaN or infinite, if any
+fn non_finite_orbit_field(
+    controller: &OrbitCameraController,
+    transform: &Transform,
+) -> Option<&'static str> {
+    let is_finite = |value: Option<f32>| value.is_none_or(f32::is_finite);
+    [
+        ("yaw", is_finite(controller.yaw)),
+        ("pitch", is_finite(controller.pitch)),
+        ("radius", is_finite(controller.radius)),
+        ("focus", controller.focus.is_finite()),
+        ("translation", transform.translation.is_finite()),
+        ("rotation", transform.rotation.is_finite()),
+    ]
+    .into_iter()
+    .find(|(_, is_finite)| !is_finite)
+    .map(|(field, _)| field)
+}
+
+/// Re-derive the orbit state from `last_transform`, or from a default
+/// transform if it is not finite either. The focus is kept if finite.
+fn recover_orbit_state(
+    controller: &mut OrbitCameraController,
+    last_transform: &Transform,
+    transform: &mut Transform,
+    projection: &mut Projection,
+) {
+    *transform = if last_transform.is_finite() {
+        *last_transform
+    } else {
+        Transform::from_xyz(0.0, 0.0, DEFAULT_ORBIT_RADIUS)
+    };
+    if !controller.focus.is_finite()
+        || controller.focus.distance_squared(transform.translation)
+            < f32::EPSILON
+    {
+        controller.focus =
+            transform.translation + transform.forward() * DEFAULT_ORBIT_RADIUS;
+    }
+    // Keep the zoom of an orthographic projection if it is still valid
+    let ortho_scale = match projection {
+        Projection::Orthographic(p) if p.scale.is_finite() && p.scale > 0.0 => {
+            Some(p.scale)
+        }
+        _ => None,
+    };
+    controller.yaw = None;
+    controller.pitch = None;
+    controller.radius = None;
+    controller.is_initialized = false;
+    controller.initialize_if_necessary(transform, projection);
+    if let (Some(scale), Some(yaw), Some(pitch)) =
+        (ortho_scale, controller.yaw, controller.pitch)
+    {
+        controller.radius = Some(scale);
+        math::update_orbit_transform(
+            yaw,
+            pitch,
+            scale,
+            controller.focus,
+            transform,
+            projection,
+            controller.ortho_placement,
+        );
+    }
+}
+
+/// Limit the pan `translation` of the focus so that it stops `offset` in
+/// front of the first geometry hit, and slides along it with the rest of the
+/// translation
+fn collide_pan(
+    focus: Vec3,
+    translation: Vec3,
+    offset: f32,
+    ray_cast: &mut MeshRayCast,
+    raycast_filter: &RaycastFilter,
+) -> Vec3 {
+    let distance = translation.length();
+    let Ok(direction) = Dir3::new(translation) else {
+        return translation;
+    };
+    let Some((_entity, hit)) = get_nearest_included_intersection(
+        ray_cast,
+        Ray3d::new(focus, direction),
+        raycast_filter,
+    ) else {
+        return translation;
+    };
+    if hit.distance > distance + offset {
+        return translation;
+    }
+    let allowed = (hit.distance - offset).max(0.0);
+    let remaining = translation - direction * allowed;
+    // Keep the motion along the surface
+    let normal = hit.normal.normalize_or_zero();
+    let slide = remaining - normal * remaining.dot(normal);
+    direction * allowed + slide
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{
+        ecs::system::RunSystemOnce,
+        render::{
+            camera::{
+                camera_system, ManualTextureViews, ScalingMode, Viewport,
+            },
+            primitives::Aabb,
+        },
+        time::TimeUpdateStrategy,
+        window::PrimaryWindow,
+    };
+
+    use super::*;
+    use crate::{
+        testing::{
+            assert_transform_approx_eq, set_active_camera, test_app,
+            SimulatedInput,
+        },
+        ViewpointEvent,
+    };
+
+    fn spawn_camera(app: &mut App, transform: Transform) -> Entity {
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                transform,
+                OrbitCameraController::default(),
+            ))
+            .id();
+        set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+        app.update();
+        camera
+    }
+
+    fn controller(app: &App, camera: Entity) -> &OrbitCameraController {
+        app.world().get::<OrbitCameraController>(camera).unwrap()
+    }
+
+    #[test]
+    fn nan_radius_recovers_from_the_last_finite_transform() {
+        let mut app = test_app();
+        let start =
+            Transform::from_xyz(3.0, 4.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y);
+        let camera = spawn_camera(&mut app, start);
+        let (yaw, pitch, radius) = {
+            let controller = controller(&app, camera);
+            (controller.yaw, controller.pitch, controller.radius)
+        };
+        let mut poisoned = app
+            .world_mut()
+            .get_mut::<OrbitCameraController>(camera)
+            .unwrap();
+        poisoned.radius = Some(f32::NAN);
+        poisoned.force_update = true;
+        app.update();
+        assert_transform_approx_eq(
+            app.world().get::<Transform>(camera).unwrap(),
+            &start,
+            1e-4,
+        );
+        let controller = controller(&app, camera);
+        assert!(controller.is_initialized);
+        assert!(!controller.force_update);
+        assert!(controller.focus.abs_diff_eq(Vec3::ZERO, 1e-4));
+        assert!((controller.yaw.unwrap() - yaw.unwrap()).abs() < 1e-4);
+        assert!((controller.pitch.unwrap() - pitch.unwrap()).abs() < 1e-4);
+        assert!((controller.radius.unwrap() - radius.unwrap()).abs() < 1e-4);
+    }
+
+    /// Number of times the orbit cameras were marked as changed
+    #[derive(Resource, Default)]
+    struct ChangedCameras(usize);
+
+    #[allow(clippy::type_complexity)]
+    fn count_changed_cameras_system(
+        mut changed_cameras: ResMut<ChangedCameras>,
+        cameras: Query<
+            (),
+            (
+                With<OrbitCameraController>,
+                Or<(Changed<Transform>, Changed<OrbitCameraController>)>,
+            ),
+        >,
+    ) {
+        changed_cameras.0 += cameras.iter().count();
+    }
+
+    /// Spawn 8 orbit cameras in 8 viewports of 200x300 pixels, the first
+    /// one being the active camera
+    fn spawn_8_viewports(app: &mut App) -> Vec<Entity> {
+        let cameras = (0..8)
+            .map(|index| {
+                app.world_mut()
+                    .spawn((
+                        Camera {
+                            viewport: Some(Viewport {
+                                physical_position: UVec2::new(
+                                    index % 4 * 200,
+                                    index / 4 * 300,
+                                ),
+                                physical_size: UVec2::new(200, 300),
+                                ..default()
+                            }),
+                            ..default()
+                        },
+                        Projection::default(),
+                        Transform::from_xyz(index as f32, 2.0, 5.0)
+                            .looking_at(Vec3::ZERO, Vec3::Y),
+                        OrbitCameraController::default(),
+                    ))
+                    .id()
+            })
+            .collect::<Vec<_>>();
+        set_active_camera(app.world_mut(), cameras[0], Vec2::new(200.0, 300.0));
+        app.update();
+        cameras
+    }
+
+    #[test]
+    fn idle_cameras_are_not_updated() {
+        let mut app = test_app();
+        app.init_resource::<ChangedCameras>()
+            .add_systems(Last, count_changed_cameras_system);
+        let cameras = spawn_8_viewports(&mut app);
+        // The controllers are initialized as soon as they are added
+        for &camera in &cameras {
+            assert!(controller(&app, camera).is_initialized);
+        }
+        app.update();
+        let need_update = app
+            .world_mut()
+            .register_system(orbit_controllers_need_update);
+        app.world_mut().run_system(need_update).unwrap();
+        app.world_mut().resource_mut::<ChangedCameras>().0 = 0;
+        for _ in 0..10 {
+            app.update();
+            assert!(!app.world_mut().run_system(need_update).unwrap());
+        }
+        assert_eq!(app.world().resource::<ChangedCameras>().0, 0);
+        // The active camera still handles the input
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .orbit(Vec2::new(20.0, 0.0));
+        app.update();
+        assert_eq!(app.world().resource::<ChangedCameras>().0, 1);
+        assert!(app.world_mut().run_system(need_update).unwrap());
+        // A forced update is applied to its camera only
+        app.world_mut().resource_mut::<ChangedCameras>().0 = 0;
+        app.world_mut()
+            .get_mut::<OrbitCameraController>(cameras[3])
+            .unwrap()
+            .force_update = true;
+        app.update();
+        assert!(!controller(&app, cameras[3]).force_update);
+        assert_eq!(app.world().resource::<ChangedCameras>().0, 1);
+    }
+
+    #[test]
+    fn idle_skip_lets_the_forced_updates_through() {
+        let mut app = test_app();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            Duration::from_millis(100),
+        ));
+        let cameras = spawn_8_viewports(&mut app);
+        app.update();
+        let transforms = |app: &App| {
+            cameras
+                .iter()
+                .map(|&camera| *app.world().get::<Transform>(camera).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let start = transforms(&app);
+
+        // A transition of an inactive camera
+        app.world_mut().send_event(ViewpointEvent {
+            camera_entity: Some(cameras[1]),
+            viewpoint: Viewpoint::Right,
+            duration: Some(Duration::from_millis(300)),
+            relative_to: None,
+        });
+        // The automatic rotation of another one
+        app.world_mut()
+            .get_mut::<OrbitCameraController>(cameras[2])
+            .unwrap()
+            .auto_rotate = Some(AutoRotate {
+            idle_delay: Duration::ZERO,
+            while_inactive: true,
+            ..default()
+        });
+        // A value set directly
+        let mut forced = app
+            .world_mut()
+            .get_mut::<OrbitCameraController>(cameras[3])
+            .unwrap();
+        forced.yaw = Some(1.0);
+        forced.force_update = true;
+        app.update();
+        app.update();
+        let moving = transforms(&app);
+        app.update();
+        let moved = transforms(&app);
+        for index in 1..=2 {
+            assert_ne!(moving[index], start[index]);
+            assert_ne!(moved[index], moving[index]);
+        }
+        assert_ne!(moved[3], start[3]);
+        assert_eq!(controller(&app, cameras[3]).yaw, Some(1.0));
+        // The others stay idle
+        for index in [0, 4, 5, 6, 7] {
+            assert_eq!(moved[index], start[index]);
+        }
+        for _ in 0..3 {
+            app.update();
+        }
+        let (yaw, pitch) = Viewpoint::Right.to_yaw_pitch();
+        let controller = controller(&app, cameras[1]);
+        assert_eq!(
+            (controller.yaw, controller.pitch),
+            (Some(yaw), Some(pitch))
+        );
+    }
+
+    #[test]
+    fn locked_viewpoint_ignores_the_orbit_but_pans_and_zooms() {
+        let mut app = test_app();
+        let camera = spawn_camera(&mut app, Transform::from_xyz(0.0, 0.0, 5.0));
+        app.world_mut()
+            .get_mut::<OrbitCameraController>(camera)
+            .unwrap()
+            .locked_viewpoint = Some(Viewpoint::Right);
+        app.update();
+        let (yaw, pitch) = Viewpoint::Right.to_yaw_pitch();
+        let locked = |app: &App| {
+            let controller = controller(app, camera);
+            controller.yaw == Some(yaw) && controller.pitch == Some(pitch)
+        };
+        assert!(locked(&app));
+        let start = *app.world().get::<Transform>(camera).unwrap();
+
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .orbit(Vec2::new(100.0, 50.0));
+        app.update();
+        assert!(locked(&app));
+        assert_eq!(*app.world().get::<Transform>(camera).unwrap(), start);
+
+        let focus = controller(&app, camera).focus;
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .pan(Vec2::new(100.0, 50.0));
+        app.update();
+        assert!(locked(&app));
+        let panned_focus = controller(&app, camera).focus;
+        assert_ne!(panned_focus, focus);
+        // Along the view plane of the right view
+        assert!((panned_focus.x - focus.x).abs() < 1e-4);
+
+        let radius = controller(&app, camera).radius.unwrap();
+        app.world_mut().resource_mut::<SimulatedInput>().scroll(1.0);
+        app.update();
+        assert!(locked(&app));
+        assert!(controller(&app, camera).radius.unwrap() < radius);
+
+        // Or pan with the orbit input
+        app.world_mut()
+            .get_mut::<OrbitCameraController>(camera)
+            .unwrap()
+            .pan_on_locked_orbit = true;
+        let focus = controller(&app, camera).focus;
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .orbit(Vec2::new(100.0, 50.0));
+        app.update();
+        assert!(locked(&app));
+        assert_ne!(controller(&app, camera).focus, focus);
+    }
+
+    /// Pan translation of a focus at the origin, limited by the geometry
+    fn collided_pan_translation(app: &mut App, translation: Vec3) -> Vec3 {
+        app.world_mut()
+            .run_system_once(
+                move |mut ray_cast: MeshRayCast,
+                      raycast_filter: RaycastFilter| {
+                    collide_pan(
+                        Vec3::ZERO,
+                        translation,
+                        0.1,
+                        &mut ray_cast,
+                        &raycast_filter,
+                    )
+                },
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn off_axis_pan_slides_along_the_geometry() {
+        let mut app = test_app();
+        spawn_wall(&mut app, -5.0);
+        // Toward the wall at 53 degrees off its normal, hitting it after 6.25
+        let translation = Vec3::new(6.0, 0.0, -8.0);
+        let collided = collided_pan_translation(&mut app, translation);
+        // Stops 0.1 before the hit along the motion, then slides
+        let allowed = 6.25 - 0.1;
+        assert!(
+            (collided.z - translation.normalize().z * allowed).abs() < 1e-4
+        );
+        assert!(collided.z > -5.0);
+        assert!((collided.x - translation.x).abs() < 1e-4);
+        assert_eq!(collided.y, 0.0);
+        // Short of the wall or parallel to it, the pan is not limited
+        for translation in [Vec3::new(3.0, 0.0, -4.0), Vec3::new(6.0, 8.0, 0.0)]
+        {
+            assert_eq!(
+                collided_pan_translation(&mut app, translation),
+                translation
+            );
+        }
+    }
+
+    #[test]
+    fn zoom_to_a_grazing_cursor_ray_keeps_the_focus_close() {
+        let radius = 5.0;
+        let transform = Transform::from_xyz(0.0, 0.0, radius)
+            .looking_at(Vec3::ZERO, Vec3::Y);
+        // 85 degrees off axis, the focus plane is 57 units away along it
+        let direction =
+            Quat::from_rotation_y(85f32.to_radians()) * transform.forward();
+        let pivot =
+            focus_plane_pivot(&transform, direction, radius, Vec3::ZERO);
+        assert_eq!(pivot, Vec3::ZERO);
+        // Zoom in by 20%, the pivot being on the focus plane or a hit
+        // further along the ray
+        for pivot in [pivot, transform.translation + direction * 100.0] {
+            let new_radius = radius * 0.8;
+            let new_focus = zoom_to_pivot_focus(
+                &transform,
+                pivot,
+                new_radius - radius,
+                new_radius,
+            )
+            .unwrap_or(Vec3::ZERO);
+            assert!(new_focus.length() <= 0.5 * radius);
+        }
+        // Within 75 degrees, the focus moves toward the cursor
+        let direction =
+            Quat::from_rotation_y(60f32.to_radians()) * transform.forward();
+        let pivot =
+            focus_plane_pivot(&transform, direction, radius, Vec3::ZERO);
+        assert!(pivot.z.abs() < 1e-4);
+        assert!(pivot.x < -1.0);
+        let new_focus =
+            zoom_to_pivot_focus(&transform, pivot, -1.0, radius - 1.0).unwrap();
+        assert!(new_focus.x < 0.0);
+        assert!(new_focus.length() <= 0.5 * radius);
+    }
+
+    #[test]
+    fn orthographic_pan_keeps_the_point_under_the_cursor() {
+        let mut app = test_app();
+        let viewport_size = Vec2::new(640.0, 360.0);
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera {
+                    viewport: Some(Viewport {
+                        physical_position: UVec2::new(100, 50),
+                        physical_size: viewport_size.as_uvec2(),
+                        ..default()
+                    }),
+                    ..default()
+                },
+                Projection::Orthographic(OrthographicProjection {
+                    scaling_mode: ScalingMode::FixedVertical {
+                        viewport_height: 2.0,
+                    },
+                    ..OrthographicProjection::default_3d()
+                }),
+                Transform::from_xyz(3.0, 4.0, 5.0)
+                    .looking_at(Vec3::ZERO, Vec3::Y),
+                OrbitCameraController::default(),
+            ))
+            .id();
+        set_active_camera(app.world_mut(), camera, viewport_size);
+        app.update();
+        // The view is 2 times the scale high
+        let scale = controller(&app, camera).radius.unwrap();
+        let world_per_pixel = 2.0 * scale / viewport_size.y;
+        let transform = *app.world().get::<Transform>(camera).unwrap();
+        let point_under = |focus: Vec3, cursor: Vec2| {
+            let offset = (cursor - viewport_size / 2.0) * world_per_pixel;
+            focus + transform.right() * offset.x - transform.up() * offset.y
+        };
+        let cursor = Vec2::new(100.0, 80.0);
+        let point = point_under(controller(&app, camera).focus, cursor);
+        let drag = Vec2::new(100.0, 40.0);
+        app.world_mut().resource_mut::<SimulatedInput>().pan(drag);
+        app.update();
+        let focus = controller(&app, camera).focus;
+        assert!(point_under(focus, cursor + drag).abs_diff_eq(point, 1e-4));
+        assert_eq!(controller(&app, camera).radius, Some(scale));
+    }
+
+    /// Spawn an 800x600 primary window with the cursor at `cursor`, used by
+    /// the active camera for the raycasts of the cursor
+    fn spawn_window(app: &mut App, cursor: Vec2) -> Entity {
+        app.init_asset::<Image>()
+            .init_resource::<ManualTextureViews>();
+        let mut window = Window::default();
+        window.set_cursor_position(Some(cursor));
+        let window = app.world_mut().spawn((window, PrimaryWindow)).id();
+        app.world_mut()
+            .run_system_once(camera_system::<Projection>)
+            .unwrap();
+        app.world_mut()
+            .resource_mut::<ActiveCameraData>()
+            .window_entity = Some(window);
+        window
+    }
+
+    /// Spawn a 100 units wide wall facing +Z at `z`, hit by the raycasts
+    fn spawn_wall(app: &mut App, z: f32) -> Entity {
+        let mesh = Rectangle::new(100.0, 100.0).mesh().build();
+        let aabb = Aabb::from_min_max(
+            Vec3::new(-50.0, -50.0, 0.0),
+            Vec3::new(50.0, 50.0, 0.0),
+        );
+        let mesh = app.world_mut().resource_mut::<Assets<Mesh>>().add(mesh);
+        // The headless test app does not compute the visibility
+        let mut view_visibility = ViewVisibility::default();
+        view_visibility.set();
+        let wall = app
+            .world_mut()
+            .spawn((
+                Mesh3d(mesh),
+                aabb,
+                Transform::from_xyz(0.0, 0.0, z),
+                InheritedVisibility::VISIBLE,
+                view_visibility,
+            ))
+            .id();
+        app.update();
+        wall
+    }
+
+    /// Translation of the focus of a camera looking at the origin from 10
+    /// units away, after panning 100 pixels to the right with the cursor at
+    /// the center of the view and a wall at `wall_z`, if any
+    fn pan_translation(auto_depth_pan: bool, wall_z: Option<f32>) -> Vec3 {
+        let mut app = test_app();
+        let camera = spawn_camera(
+            &mut app,
+            Transform::from_xyz(0.0, 0.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+        );
+        {
+            let mut controller = app
+                .world_mut()
+                .get_mut::<OrbitCameraController>(camera)
+                .unwrap();
+            controller.auto_depth_pan = auto_depth_pan;
+            // Keep the radius, which is the fallback depth
+            controller.auto_depth = false;
+        }
+        spawn_window(&mut app, Vec2::new(400.0, 300.0));
+        if let Some(z) = wall_z {
+            spawn_wall(&mut app, z);
+        }
+        let start = controller(&app, camera).focus;
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_button(MouseButton::Middle)
+            .press_key(KeyCode::ShiftLeft)
+            .pan(Vec2::new(100.0, 0.0));
+        app.update();
+        assert_eq!(controller(&app, camera).radius, Some(10.0));
+        controller(&app, camera).focus - start
+    }
+
+    #[test]
+    fn auto_depth_pan_uses_the_depth_under_the_cursor() {
+        let reference = pan_translation(false, None);
+        assert!(reference.x < -0.1);
+        assert!(reference.yz().abs_diff_eq(Vec2::ZERO, 1e-5));
+        // Geometry in front of and behind the focus
+        for wall_z in [8.0, 5.0, -5.0] {
+            let depth = 10.0 - wall_z;
+            let translation = pan_translation(true, Some(wall_z));
+            assert!(
+                translation.abs_diff_eq(reference * depth / 10.0, 1e-4),
+                "{translation} for a depth of {depth}",
+            );
+            // Unchanged without the option
+            let translation = pan_translation(false, Some(wall_z));
+            assert!(translation.abs_diff_eq(reference, 1e-5));
+        }
+        // Falls back to the radius when there is nothing under the cursor
+        let translation = pan_translation(true, None);
+        assert!(translation.abs_diff_eq(reference, 1e-5));
+    }
+
+    /// Camera looking at the origin from 10 units away, with the cursor to
+    /// the right of the center of the view, above a wall at `z = 5` if
+    /// `has_wall`
+    fn spawn_pivot_camera(
+        app: &mut App,
+        has_wall: bool,
+        pivot_override: Option<Vec3>,
+    ) -> Entity {
+        let camera = spawn_camera(
+            app,
+            Transform::from_xyz(0.0, 0.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+        );
+        if let Some(point) = pivot_override {
+            app.world_mut()
+                .entity_mut(camera)
+                .insert(OrbitPivotOverride {
+                    point,
+                    affects_zoom: false,
+                });
+        }
+        spawn_window(app, Vec2::new(550.0, 250.0));
+        if has_wall {
+            spawn_wall(app, 5.0);
+        }
+        camera
+    }
+
+    /// Point under the cursor at depth `z`
+    fn point_under_cursor(app: &mut App, camera: Entity, z: f32) -> Vec3 {
+        let world = app.world_mut();
+        let cursor = world
+            .query::<&Window>()
+            .single(world)
+            .cursor_position()
+            .unwrap();
+        let ray = world
+            .get::<Camera>(camera)
+            .unwrap()
+            .viewport_to_world(
+                world.get::<GlobalTransform>(camera).unwrap(),
+                cursor,
+            )
+            .unwrap();
+        ray.get_point(
+            ray.intersect_plane(
+                Vec3::new(0.0, 0.0, z),
+                InfinitePlane3d::new(Vec3::Z),
+            )
+            .unwrap(),
+        )
+    }
+
+    /// Orbit the camera with a drag of `delta`, pressing the orbit button
+    /// first if `is_start`
+    fn drag_orbit(app: &mut App, delta: Vec2, is_start: bool) {
+        let mut input = app.world_mut().resource_mut::<SimulatedInput>();
+        if is_start {
+            input.press_button(MouseButton::Middle);
+        }
+        input.orbit(delta);
+        app.update();
+    }
+
+    /// Position of `point` relative to the camera, constant when orbiting
+    /// around it
+    fn view_position(app: &App, camera: Entity, point: Vec3) -> Vec3 {
+        app.world()
+            .get::<Transform>(camera)
+            .unwrap()
+            .compute_matrix()
+            .inverse()
+            .transform_point3(point)
+    }
+
+    #[test]
+    fn orbit_pivot_override_takes_precedence_over_the_hit_and_the_focus() {
+        let delta = Vec2::new(60.0, 30.0);
+        let pivot_override = Vec3::new(-2.0, 1.0, 0.0);
+        // Override, then geometry under the cursor, then the focus plane
+        for (has_wall, override_opt, pivot_z) in [
+            (true, Some(pivot_override), None),
+            (true, None, Some(5.0)),
+            (false, None, Some(0.0)),
+        ] {
+            let mut app = test_app();
+            let camera = spawn_pivot_camera(&mut app, has_wall, override_opt);
+            let pivot = match pivot_z {
+                Some(z) => point_under_cursor(&mut app, camera, z),
+                None => pivot_override,
+            };
+            let others = [
+                pivot_override,
+                point_under_cursor(&mut app, camera, 5.0),
+                point_under_cursor(&mut app, camera, 0.0),
+            ]
+            .into_iter()
+            .filter(|point| *point != pivot)
+            .map(|point| (point, view_position(&app, camera, point)))
+            .collect::<Vec<_>>();
+            let view_pivot = view_position(&app, camera, pivot);
+            drag_orbit(&mut app, delta, true);
+            assert!(
+                view_position(&app, camera, pivot)
+                    .abs_diff_eq(view_pivot, 1e-3),
+                "the camera did not orbit around {pivot}",
+            );
+            for (point, view_point) in others {
+                assert!(!view_position(&app, camera, point)
+                    .abs_diff_eq(view_point, 1e-2));
+            }
+        }
+    }
+
+    #[test]
+    fn clearing_the_pivot_override_during_a_drag_does_not_jump() {
+        let pivot_override = Vec3::new(-2.0, 1.0, 0.0);
+        let mut transforms = Vec::new();
+        for is_cleared in [false, true] {
+            let mut app = test_app();
+            let camera =
+                spawn_pivot_camera(&mut app, true, Some(pivot_override));
+            drag_orbit(&mut app, Vec2::new(60.0, 30.0), true);
+            if is_cleared {
+                app.world_mut()
+                    .entity_mut(camera)
+                    .remove::<OrbitPivotOverride>();
+            }
+            let view_pivot = view_position(&app, camera, pivot_override);
+            drag_orbit(&mut app, Vec2::new(-20.0, 40.0), false);
+            // Still orbiting around the captured override
+            assert!(view_position(&app, camera, pivot_override)
+                .abs_diff_eq(view_pivot, 1e-3));
+            transforms.push(*app.world().get::<Transform>(camera).unwrap());
+        }
+        assert_transform_approx_eq(&transforms[1], &transforms[0], 1e-5);
+    }
+
+    #[test]
+    fn camera_in_a_rig_orbits_in_world_space() {
+        let mut app = test_app();
+        let rig_transform = Transform::from_xyz(10.0, 2.0, -3.0)
+            .with_rotation(Quat::from_rotation_y(30f32.to_radians()));
+        // Looking at the origin of the rig
+        let local =
+            Transform::from_xyz(0.0, 3.0, 8.0).looking_at(Vec3::ZERO, Vec3::Y);
+        let focus = rig_transform.translation;
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                local,
+                OrbitCameraController {
+                    focus,
+                    // Orbit around the focus, there is no cursor
+                    auto_depth: false,
+                    ..default()
+                },
+            ))
+            .id();
+        app.world_mut().spawn(rig_transform).add_child(camera);
+        set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+        app.update();
+        let global_transform = |app: &App| {
+            app.world()
+                .get::<GlobalTransform>(camera)
+                .unwrap()
+                .compute_transform()
+        };
+        let start = rig_transform.mul_transform(local);
+        assert_transform_approx_eq(&global_transform(&app), &start, 1e-4);
+        let radius = controller(&app, camera).radius.unwrap();
+        assert!((radius - local.translation.length()).abs() < 1e-4);
+
+        // A full turn across the width of the window
+        let delta_yaw = 20.0 / 800.0 * 2.0 * PI;
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .orbit(Vec2::new(20.0, 0.0));
+        app.update();
+        let transform = global_transform(&app);
+        let controller = controller(&app, camera);
+        assert_eq!(controller.focus, focus);
+        assert_eq!(controller.radius, Some(radius));
+        assert_transform_approx_eq(
+            &transform,
+            &math::camera_transform_from_orbit(
+                controller.yaw.unwrap(),
+                controller.pitch.unwrap(),
+                radius,
+                focus,
+            ),
+            1e-4,
+        );
+        // Moved along the orbit only
+        assert!((transform.translation.distance(focus) - radius).abs() < 1e-4);
+        let distance = transform.translation.distance(start.translation);
+        assert!(distance > 0.5 * radius * delta_yaw);
+        assert!(distance <= radius * delta_yaw);
     }
 }
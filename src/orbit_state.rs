@@ -0,0 +1,277 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    orbit::OrbitCameraController,
+    transition::{OrbitState, ViewTransition},
+    BlendyCamerasDisabled,
+};
+
+/// Event to set the focus, radius, yaw or pitch of an
+/// [`OrbitCameraController`], the fields that are `None` are kept. The radius
+/// is the scale of an orthographic projection and is clamped to the
+/// [`OrbitCameraController::zoom_lower_limit`]. A [`SetOrbitStateFailed`]
+/// event is sent if the values could not be set.
+#[derive(Event, Debug, Clone, Reflect)]
+pub struct SetOrbitStateEvent {
+    /// The camera to move
+    pub camera_entity: Entity,
+    /// The point to look at and orbit around
+    pub focus: Option<Vec3>,
+    /// Distance to the focus
+    pub radius: Option<f32>,
+    /// Yaw in radian, with the conventions of
+    /// [`math::yaw_pitch_radius_from_translation`](crate::math::yaw_pitch_radius_from_translation)
+    pub yaw: Option<f32>,
+    /// Pitch in radian, with the conventions of
+    /// [`math::yaw_pitch_radius_from_translation`](crate::math::yaw_pitch_radius_from_translation)
+    pub pitch: Option<f32>,
+    /// Duration of the animated transition to the new state. The camera is
+    /// moved immediately if `None`
+    pub duration: Option<Duration>,
+}
+
+/// Event sent when a [`SetOrbitStateEvent`] could not be applied
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub struct SetOrbitStateFailed {
+    /// The camera of the event
+    pub camera_entity: Entity,
+    /// Why the event could not be applied
+    pub reason: OrbitStateError,
+}
+
+/// Reason why a [`SetOrbitStateEvent`] could not be applied to a camera
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum OrbitStateError {
+    /// The camera entity does not exist or has no orbit controller
+    CameraNotFound,
+    /// The orbit controller of the camera is disabled
+    ControllerDisabled,
+    /// One of the values is not finite, or the radius is not positive
+    InvalidValue,
+}
+
+pub(crate) fn set_orbit_state_system(
+    mut commands: Commands,
+    mut ev_read: EventReader<SetOrbitStateEvent>,
+    mut cameras_query: Query<
+        (&mut OrbitCameraController, &mut Transform, &mut Projection),
+        Without<BlendyCamerasDisabled>,
+    >,
+    mut failed_ev_write: EventWriter<SetOrbitStateFailed>,
+) {
+    for event in ev_read.read() {
+        let camera_entity = event.camera_entity;
+        let mut fail = |reason| {
+            failed_ev_write.send(SetOrbitStateFailed {
+                camera_entity,
+                reason,
+            });
+        };
+        let Ok((mut controller, mut transform, mut projection)) =
+            cameras_query.get_mut(camera_entity)
+        else {
+            warn!(
+                "Camera {camera_entity} not found or without \
+                OrbitCameraController while trying to set the orbit state"
+            );
+            fail(OrbitStateError::CameraNotFound);
+            continue;
+        };
+        if !controller.is_enabled {
+            warn!(
+                "Orbit controller of camera {camera_entity} disabled while \
+                trying to set the orbit state"
+            );
+            fail(OrbitStateError::ControllerDisabled);
+            continue;
+        }
+        let is_valid = event.focus.is_none_or(Vec3::is_finite)
+            && event
+                .radius
+                .is_none_or(|radius| radius.is_finite() && radius > 0.0)
+            && event.yaw.is_none_or(f32::is_finite)
+            && event.pitch.is_none_or(f32::is_finite);
+        if !is_valid {
+            warn!("Invalid orbit state for camera {camera_entity}: {event:?}");
+            fail(OrbitStateError::InvalidValue);
+            continue;
+        }
+        controller.initialize_if_necessary(&mut transform, &mut projection);
+        let Some(start) = OrbitState::from_controller(&controller) else {
+            continue;
+        };
+        let end = OrbitState {
+            focus: event.focus.unwrap_or(start.focus),
+            yaw: event.yaw.unwrap_or(start.yaw),
+            pitch: event.pitch.unwrap_or(start.pitch),
+            radius: event.radius.map_or(start.radius, |radius| {
+                radius.max(controller.zoom_lower_limit)
+            }),
+        };
+        match event.duration {
+            Some(duration) => {
+                commands
+                    .entity(camera_entity)
+                    .insert(ViewTransition::orbit(start, end, duration));
+            }
+            None => {
+                commands.entity(camera_entity).remove::<ViewTransition>();
+                end.apply(&mut controller);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_transform_approx_eq, test_app};
+
+    fn spawn_camera(
+        app: &mut App,
+        controller: OrbitCameraController,
+    ) -> Entity {
+        app.world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 0.0, 5.0),
+                controller,
+            ))
+            .id()
+    }
+
+    /// Send `event` and update, forgetting the previous failures
+    fn set_state(app: &mut App, event: SetOrbitStateEvent) {
+        app.world_mut()
+            .resource_mut::<Events<SetOrbitStateFailed>>()
+            .clear();
+        app.world_mut().send_event(event);
+        app.update();
+    }
+
+    fn event(camera_entity: Entity) -> SetOrbitStateEvent {
+        SetOrbitStateEvent {
+            camera_entity,
+            focus: None,
+            radius: None,
+            yaw: None,
+            pitch: None,
+            duration: None,
+        }
+    }
+
+    fn controller(app: &App, camera: Entity) -> &OrbitCameraController {
+        app.world().get::<OrbitCameraController>(camera).unwrap()
+    }
+
+    fn failures(app: &App) -> Vec<OrbitStateError> {
+        app.world()
+            .resource::<Events<SetOrbitStateFailed>>()
+            .iter_current_update_events()
+            .map(|failed| failed.reason)
+            .collect()
+    }
+
+    #[test]
+    fn radius_is_clamped_to_the_zoom_lower_limit() {
+        let mut app = test_app();
+        let camera = spawn_camera(
+            &mut app,
+            OrbitCameraController {
+                zoom_lower_limit: 1.0,
+                ..default()
+            },
+        );
+        set_state(
+            &mut app,
+            SetOrbitStateEvent {
+                radius: Some(0.2),
+                ..event(camera)
+            },
+        );
+        assert_eq!(controller(&app, camera).radius, Some(1.0));
+        assert_transform_approx_eq(
+            app.world().get::<Transform>(camera).unwrap(),
+            &Transform::from_xyz(0.0, 0.0, 1.0),
+            1e-4,
+        );
+        assert!(failures(&app).is_empty());
+    }
+
+    #[test]
+    fn uninitialized_controller_keeps_the_values_not_set() {
+        let mut app = test_app();
+        let camera = spawn_camera(&mut app, OrbitCameraController::default());
+        assert!(!controller(&app, camera).is_initialized);
+        set_state(
+            &mut app,
+            SetOrbitStateEvent {
+                focus: Some(Vec3::new(0.0, 1.0, 0.0)),
+                ..event(camera)
+            },
+        );
+        let controller = controller(&app, camera);
+        assert!(controller.is_initialized);
+        assert_eq!(controller.focus, Vec3::new(0.0, 1.0, 0.0));
+        // Computed from the transform, relative to the previous focus
+        assert_eq!(controller.yaw, Some(0.0));
+        assert_eq!(controller.pitch, Some(0.0));
+        assert_eq!(controller.radius, Some(5.0));
+        assert_transform_approx_eq(
+            app.world().get::<Transform>(camera).unwrap(),
+            &Transform::from_xyz(0.0, 1.0, 5.0),
+            1e-4,
+        );
+    }
+
+    #[test]
+    fn failures_are_reported() {
+        let mut app = test_app();
+        let without_controller = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 0.0, 5.0),
+            ))
+            .id();
+        set_state(&mut app, event(without_controller));
+        assert_eq!(failures(&app), [OrbitStateError::CameraNotFound]);
+
+        let disabled = spawn_camera(
+            &mut app,
+            OrbitCameraController {
+                is_enabled: false,
+                ..default()
+            },
+        );
+        set_state(&mut app, event(disabled));
+        assert_eq!(failures(&app), [OrbitStateError::ControllerDisabled]);
+
+        let camera = spawn_camera(&mut app, OrbitCameraController::default());
+        set_state(
+            &mut app,
+            SetOrbitStateEvent {
+                radius: Some(0.0),
+                ..event(camera)
+            },
+        );
+        assert_eq!(failures(&app), [OrbitStateError::InvalidValue]);
+        set_state(
+            &mut app,
+            SetOrbitStateEvent {
+                yaw: Some(f32::NAN),
+                ..event(camera)
+            },
+        );
+        assert_eq!(failures(&app), [OrbitStateError::InvalidValue]);
+        assert_transform_approx_eq(
+            app.world().get::<Transform>(camera).unwrap(),
+            &Transform::from_xyz(0.0, 0.0, 5.0),
+            1e-4,
+        );
+    }
+}
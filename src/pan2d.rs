@@ -0,0 +1,287 @@
+use std::borrow::Cow;
+
+use bevy::{prelude::*, render::camera::CameraProjection};
+
+use crate::{
+    input::{
+        self, CameraInputBlocked, CameraInputState, HorizontalScrollAction,
+        InputBinding,
+    },
+    utils, ActiveCameraData, BlendyCamerasDisabled,
+};
+
+/// Component to tag an entity as able to be panned and zoomed in 2D, e.g.
+/// for a node graph or a 2D layout.
+/// The entity must have `Transform` and `OrthographicProjection` components.
+/// Typically you would add `Camera2d` to this entity. The camera is ignored
+/// by the [`SwitchToFlyController`](crate::SwitchToFlyController),
+/// [`SwitchToOrbitController`](crate::SwitchToOrbitController) and
+/// [`SwitchProjection`](crate::SwitchProjection) events.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+#[require(CameraInputState)]
+pub struct Pan2dCameraController {
+    /// Mouse button used to pan the camera. Ignored if `pan_bindings` is
+    /// not empty.
+    pub button_pan: MouseButton,
+    /// Key that must be pressed for the `button_pan` to work. Ignored if
+    /// `pan_bindings` is not empty.
+    pub modifier_pan: Option<KeyCode>,
+    /// Bindings used to pan the camera, any of them works. If empty,
+    /// `button_pan` and `modifier_pan` are used instead.
+    pub pan_bindings: Vec<InputBinding>,
+    /// Key that, while held, keeps the bindings of the controller from
+    /// matching, e.g. while a tool of the application uses the same mouse
+    /// button. Pressing it during a drag ends the drag as if the button was
+    /// released. Defaults to `None`.
+    pub suppress_modifier: Option<KeyCode>,
+    /// What to do with the horizontal scroll of trackpads and tilt wheels.
+    /// Defaults to panning horizontally.
+    pub horizontal_scroll_action: HorizontalScrollAction,
+    /// Sentitivity of the panning motion. With `1.0`, the point under the
+    /// cursor follows the cursor.
+    pub pan_sensitivity: f32,
+    /// Sentitivity of the zooming motion
+    pub zoom_sensitivity: f32,
+    /// Sentitivity of the touch panning gesture
+    pub touch_pan_sensitivity: f32,
+    /// Sentitivity of the touch pinch gesture
+    pub touch_zoom_sensitivity: f32,
+    /// Lower limit on the projection's scale. Should always be >0.
+    /// Defaults to `0.01`.
+    pub zoom_lower_limit: f32,
+    /// Upper limit on the projection's scale, if any
+    pub zoom_upper_limit: Option<f32>,
+    /// Keep the point under the mouse cursor fixed when zooming. Otherwise
+    /// zoom toward the center of the viewport.
+    pub zoom_to_mouse_position: bool,
+    /// World space rectangle the center of the view is kept in, if any
+    pub bounds: Option<Rect>,
+    /// Do not control the camera if `false`
+    pub is_enabled: bool,
+}
+
+impl Default for Pan2dCameraController {
+    fn default() -> Self {
+        Self {
+            button_pan: MouseButton::Middle,
+            modifier_pan: None,
+            pan_bindings: Vec::new(),
+            suppress_modifier: None,
+            horizontal_scroll_action: HorizontalScrollAction::Pan,
+            pan_sensitivity: 1.0,
+            zoom_sensitivity: 1.0,
+            touch_pan_sensitivity: 1.0,
+            touch_zoom_sensitivity: 1.0,
+            zoom_lower_limit: 0.01,
+            zoom_upper_limit: None,
+            zoom_to_mouse_position: true,
+            bounds: None,
+            is_enabled: true,
+        }
+    }
+}
+
+impl Pan2dCameraController {
+    /// The bindings used to pan the camera, either `pan_bindings` or the
+    /// binding made of `button_pan` and `modifier_pan`
+    pub fn pan_bindings(&self) -> Cow<'_, [InputBinding]> {
+        if self.pan_bindings.is_empty() {
+            Cow::Owned(vec![InputBinding::from_button_and_modifiers(
+                self.button_pan,
+                self.modifier_pan,
+                &[],
+            )])
+        } else {
+            Cow::Borrowed(&self.pan_bindings)
+        }
+    }
+
+    /// Whether one of the bindings used to pan the camera is active
+    pub fn is_pan_pressed(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_pressed(
+            &self.pan_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+
+    /// Whether one of the bindings used to pan the camera has just been
+    /// activated
+    pub fn is_pan_just_pressed(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_just_pressed(
+            &self.pan_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+
+    /// Whether one of the bindings used to pan the camera has just been
+    /// deactivated
+    pub fn is_pan_just_released(
+        &self,
+        mouse_input: &ButtonInput<MouseButton>,
+        key_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        input::bindings_just_released(
+            &self.pan_bindings(),
+            self.suppress_modifier,
+            mouse_input,
+            key_input,
+        )
+    }
+}
+
+/// Factor applied to the scale for each line of scroll
+const ZOOM_STEP_FACTOR: f32 = 0.8;
+
+/// Position in the projection space, relative to the camera, of a position
+/// in logical viewport coordinates
+fn viewport_to_projection(
+    projection: &OrthographicProjection,
+    viewport_size: Vec2,
+    position: Vec2,
+) -> Vec2 {
+    let uv = position / viewport_size;
+    let area = projection.area;
+    Vec2::new(
+        area.min.x + uv.x * area.width(),
+        area.max.y - uv.y * area.height(),
+    )
+}
+
+/// Offset in world space of an offset in the projection space
+fn projection_to_world(transform: &Transform, offset: Vec2) -> Vec3 {
+    transform.rotation * (transform.scale * offset.extend(0.0))
+}
+
+/// Run condition of the 2D pan controller system: the active camera has
+/// input. The controller only moves the active camera, so the system is
+/// skipped entirely otherwise.
+pub(crate) fn pan2d_controllers_need_update(
+    active_cam: Res<ActiveCameraData>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    key_input: Res<ButtonInput<KeyCode>>,
+    input_states: Query<&CameraInputState>,
+) -> bool {
+    input::active_camera_has_pending_input(
+        &active_cam,
+        &input_states,
+        &mouse_input,
+        &key_input,
+    )
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pan2d_camera_controller_system(
+    active_cam: Res<ActiveCameraData>,
+    input_blocked: Res<CameraInputBlocked>,
+    mut cameras: Query<
+        (
+            Entity,
+            &Pan2dCameraController,
+            &Camera,
+            &mut Transform,
+            &mut OrthographicProjection,
+            &CameraInputState,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+    windows: Query<&Window>,
+) {
+    for (
+        entity,
+        controller,
+        camera,
+        mut transform,
+        mut projection,
+        input_state,
+    ) in cameras.iter_mut()
+    {
+        if !controller.is_enabled
+            || active_cam.entity != Some(entity)
+            || input_blocked.is_blocked()
+            || !input_state.has_input()
+        {
+            continue;
+        }
+        let Some(viewport_size) =
+            utils::non_empty_size(camera.logical_viewport_size())
+        else {
+            continue;
+        };
+        // Make sure the area matches the current scale
+        projection.update(viewport_size.x, viewport_size.y);
+        let pan = input_state.pan * controller.pan_sensitivity;
+        if pan != Vec2::ZERO {
+            let units_per_pixel = projection.area.size() / viewport_size;
+            let delta = Vec2::new(-pan.x, pan.y) * units_per_pixel;
+            let offset = projection_to_world(&transform, delta);
+            transform.translation += offset;
+        }
+        let scroll = (input_state.scroll_line + input_state.scroll_pixel)
+            * controller.zoom_sensitivity;
+        if scroll != 0.0 {
+            let old_scale = projection.scale;
+            let new_scale = (old_scale * ZOOM_STEP_FACTOR.powf(scroll))
+                .max(controller.zoom_lower_limit)
+                .min(controller.zoom_upper_limit.unwrap_or(f32::INFINITY));
+            let cursor_position = active_cam
+                .window_entity
+                .and_then(|window_entity| windows.get(window_entity).ok())
+                .and_then(|window| {
+                    input_state
+                        .touch_position
+                        .or_else(|| window.cursor_position())
+                })
+                .map(|position| {
+                    let viewport_min = camera
+                        .logical_viewport_rect()
+                        .map(|rect| rect.min)
+                        .unwrap_or_default();
+                    position - viewport_min
+                })
+                .filter(|_| controller.zoom_to_mouse_position);
+            let pre_point = cursor_position.map(|position| {
+                viewport_to_projection(&projection, viewport_size, position)
+            });
+            projection.scale = new_scale;
+            projection.update(viewport_size.x, viewport_size.y);
+            // Keep the point under the cursor at the same world position
+            if let (Some(position), Some(pre_point)) =
+                (cursor_position, pre_point)
+            {
+                let point = viewport_to_projection(
+                    &projection,
+                    viewport_size,
+                    position,
+                );
+                let offset = projection_to_world(&transform, pre_point - point);
+                transform.translation += offset;
+            }
+        }
+        if let Some(bounds) = controller.bounds {
+            let center = transform.translation.truncate();
+            let clamped = center.clamp(bounds.min, bounds.max);
+            if clamped != center {
+                transform.translation.x = clamped.x;
+                transform.translation.y = clamped.y;
+            }
+        }
+    }
+}
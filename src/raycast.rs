@@ -1,4 +1,67 @@
-use bevy::{picking::mesh_picking::ray_cast::RayMeshHit, prelude::*};
+use bevy::{
+    ecs::system::SystemParam, picking::mesh_picking::ray_cast::RayMeshHit,
+    prelude::*, window::PrimaryWindow,
+};
+
+#[cfg(feature = "bevy_egui")]
+use crate::EguiWantsFocus;
+use crate::{get_window_if_cursor_in_camera_viewport, CameraInputBlocked};
+
+/// Marker component excluding an entity from the raycasts of the cameras,
+/// used for the auto-depth, the zoom to the mouse position and the framing
+/// under the cursor. Useful for overlays or skyboxes.
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[reflect(Component, Default)]
+pub struct RaycastExclude {
+    /// Also exclude the descendants of the entity
+    pub descendants: bool,
+}
+
+/// Marker component restricting the raycasts of the cameras to the entities
+/// with this component and their descendants. When no entity has this
+/// component, all the entities are raycasted.
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[reflect(Component, Default)]
+pub struct RaycastIncludeOnly;
+
+/// System parameter deciding which entities are raycasted according to the
+/// [`RaycastExclude`] and [`RaycastIncludeOnly`] markers
+#[derive(SystemParam)]
+pub struct RaycastFilter<'w, 's> {
+    hierarchy: Query<
+        'w,
+        's,
+        (
+            Option<&'static Parent>,
+            Option<&'static RaycastExclude>,
+            Has<RaycastIncludeOnly>,
+        ),
+    >,
+    include_only: Query<'w, 's, (), With<RaycastIncludeOnly>>,
+}
+
+impl RaycastFilter<'_, '_> {
+    /// Whether `entity` is raycasted
+    pub fn is_included(&self, entity: Entity) -> bool {
+        let mut is_included = self.include_only.is_empty();
+        let mut current = Some(entity);
+        while let Some(current_entity) = current {
+            let Ok((parent, exclude, include_only)) =
+                self.hierarchy.get(current_entity)
+            else {
+                break;
+            };
+            if exclude.is_some_and(|exclude| {
+                current_entity == entity || exclude.descendants
+            }) {
+                return false;
+            }
+            is_included |= include_only;
+            current = parent.map(Parent::get);
+        }
+        is_included
+    }
+}
 
 /// Get the ray under the cursor
 pub fn get_cursor_ray(
@@ -7,17 +70,29 @@ pub fn get_cursor_ray(
     window: &Window,
 ) -> Option<Ray3d> {
     window.cursor_position().and_then(|cursor_pos| {
-        let mut viewport_cursor = cursor_pos;
-        if let Some(viewport) = &camera.viewport {
-            viewport_cursor -=
-                viewport.physical_position.as_vec2() / window.scale_factor();
-        }
-        camera
-            .viewport_to_world(global_transform, viewport_cursor)
-            .ok()
+        get_position_ray(camera, global_transform, cursor_pos)
     })
 }
 
+/// Get the ray under a position in logical window coordinates, e.g. a touch
+/// position. The position is converted to the viewport coordinates expected
+/// by [`Camera::viewport_to_world`], taking the offset of the viewport in the
+/// window into account.
+pub fn get_position_ray(
+    camera: &Camera,
+    global_transform: &GlobalTransform,
+    position: Vec2,
+) -> Option<Ray3d> {
+    // Use the scale factor of the render target, like `viewport_to_world`
+    let viewport_offset = camera
+        .logical_viewport_rect()
+        .map(|rect| rect.min)
+        .unwrap_or_default();
+    camera
+        .viewport_to_world(global_transform, position - viewport_offset)
+        .ok()
+}
+
 /// Get the nearest raycast intersection
 pub fn get_nearest_intersection<'a>(
     ray_cast: &'a mut MeshRayCast,
@@ -25,3 +100,213 @@ pub fn get_nearest_intersection<'a>(
 ) -> Option<&'a (Entity, RayMeshHit)> {
     ray_cast.cast_ray(ray, &RayCastSettings::default()).first()
 }
+
+/// Get the nearest raycast intersection with the entities included by
+/// `filter`
+pub fn get_nearest_included_intersection<'a>(
+    ray_cast: &'a mut MeshRayCast,
+    ray: Ray3d,
+    filter: &RaycastFilter,
+) -> Option<&'a (Entity, RayMeshHit)> {
+    let is_included = |entity| filter.is_included(entity);
+    ray_cast
+        .cast_ray(ray, &RayCastSettings::default().with_filter(&is_included))
+        .first()
+}
+
+/// Component with what is under the mouse cursor in the viewport of a
+/// camera, e.g. to place objects where the user points. Add it to a camera
+/// with a controller. It is updated every frame for the hovered camera, the
+/// one with the highest order whose viewport contains the cursor. The
+/// raycast is filtered like the one of the controllers, see
+/// [`RaycastFilter`], and is reused by the orbit controller.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct ViewportCursor {
+    position: Option<Vec2>,
+    ray: Option<Ray3d>,
+    hit: Option<CursorHit>,
+}
+
+impl ViewportCursor {
+    /// Position of the cursor, in logical viewport coordinates. `None` if
+    /// the cursor is outside the viewport, over another camera or if the
+    /// input is blocked, e.g. when egui has the focus
+    pub fn position(&self) -> Option<Vec2> {
+        self.position
+    }
+
+    /// World space ray under the cursor, `None` like
+    /// [`ViewportCursor::position`]
+    pub fn ray(&self) -> Option<Ray3d> {
+        self.ray
+    }
+
+    /// Nearest geometry under the cursor, `None` if there is nothing under
+    /// the cursor or like [`ViewportCursor::position`]
+    pub fn hit(&self) -> Option<CursorHit> {
+        self.hit
+    }
+}
+
+/// Geometry under the cursor, see [`ViewportCursor::hit`]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct CursorHit {
+    /// The entity hit
+    pub entity: Entity,
+    /// World space position of the hit
+    pub position: Vec3,
+    /// World space normal of the surface at the hit
+    pub normal: Vec3,
+    /// Distance from the origin of the ray
+    pub distance: f32,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_viewport_cursor_system(
+    mut cameras_query: Query<(
+        Entity,
+        &Camera,
+        &GlobalTransform,
+        &mut ViewportCursor,
+    )>,
+    primary_window: Query<(Entity, &Window), With<PrimaryWindow>>,
+    other_windows: Query<(Entity, &Window), Without<PrimaryWindow>>,
+    input_blocked: Res<CameraInputBlocked>,
+    #[cfg(feature = "bevy_egui")] egui_wants_focus: Res<EguiWantsFocus>,
+    mut ray_cast: MeshRayCast,
+    raycast_filter: RaycastFilter,
+) {
+    #[allow(unused_mut)]
+    let mut is_blocked = input_blocked.is_blocked();
+    #[cfg(feature = "bevy_egui")]
+    {
+        is_blocked |= egui_wants_focus.prev || egui_wants_focus.curr;
+    }
+    // Like the active camera, the camera with the highest order is hovered
+    // when viewports overlap
+    let hovered = cameras_query
+        .iter()
+        .filter(|_| !is_blocked)
+        .filter_map(|(entity, camera, global_transform, _)| {
+            let (_, window) = get_window_if_cursor_in_camera_viewport(
+                camera,
+                None,
+                &primary_window,
+                &other_windows,
+            )?;
+            let cursor_position = window.cursor_position()?;
+            let ray = get_cursor_ray(camera, global_transform, window)?;
+            let viewport_origin = camera
+                .logical_viewport_rect()
+                .map(|rect| rect.min)
+                .unwrap_or_default();
+            Some((camera.order, entity, cursor_position - viewport_origin, ray))
+        })
+        .max_by_key(|(order, ..)| *order);
+    let hovered = hovered.map(|(_, entity, position, ray)| {
+        let hit = get_nearest_included_intersection(
+            &mut ray_cast,
+            ray,
+            &raycast_filter,
+        )
+        .map(|(entity, hit)| CursorHit {
+            entity: *entity,
+            position: hit.point,
+            normal: hit.normal,
+            distance: hit.distance,
+        });
+        (
+            entity,
+            ViewportCursor {
+                position: Some(position),
+                ray: Some(ray),
+                hit,
+            },
+        )
+    });
+    for (entity, _, _, mut viewport_cursor) in cameras_query.iter_mut() {
+        let new_value = match hovered {
+            Some((hovered_entity, value)) if hovered_entity == entity => value,
+            _ => ViewportCursor::default(),
+        };
+        viewport_cursor.set_if_neq(new_value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::system::RunSystemOnce,
+        render::camera::{camera_system, ManualTextureViews, Viewport},
+        window::WindowResolution,
+    };
+
+    use super::*;
+    use crate::testing::test_app;
+
+    #[test]
+    fn cursor_ray_in_side_by_side_viewports() {
+        let mut app = test_app();
+        app.init_asset::<Image>()
+            .init_resource::<ManualTextureViews>();
+        // Scale factor of 2, so each viewport is 400 logical pixels wide
+        let window = app
+            .world_mut()
+            .spawn((
+                Window {
+                    resolution: WindowResolution::new(1600.0, 1200.0)
+                        .with_scale_factor_override(2.0),
+                    ..default()
+                },
+                PrimaryWindow,
+            ))
+            .id();
+        let cameras = [0, 1].map(|index| {
+            app.world_mut()
+                .spawn((
+                    Camera {
+                        viewport: Some(Viewport {
+                            physical_position: UVec2::new(index * 800, 0),
+                            physical_size: UVec2::new(800, 1200),
+                            ..default()
+                        }),
+                        order: index as isize,
+                        ..default()
+                    },
+                    Projection::default(),
+                    GlobalTransform::from(
+                        Transform::from_xyz(index as f32, 0.0, 5.0)
+                            .looking_at(Vec3::ZERO, Vec3::Y),
+                    ),
+                ))
+                .id()
+        });
+        app.world_mut()
+            .run_system_once(camera_system::<Projection>)
+            .unwrap();
+        let local_cursor = Vec2::new(300.0, 150.0);
+        for (index, camera_entity) in cameras.into_iter().enumerate() {
+            let viewport_offset = Vec2::new(400.0 * index as f32, 0.0);
+            app.world_mut()
+                .get_mut::<Window>(window)
+                .unwrap()
+                .set_cursor_position(Some(local_cursor + viewport_offset));
+            let world = app.world();
+            let camera = world.get::<Camera>(camera_entity).unwrap();
+            let global_transform =
+                world.get::<GlobalTransform>(camera_entity).unwrap();
+            let ray = get_cursor_ray(
+                camera,
+                global_transform,
+                world.get::<Window>(window).unwrap(),
+            )
+            .unwrap();
+            let expected = camera
+                .viewport_to_world(global_transform, local_cursor)
+                .unwrap();
+            assert!(ray.origin.abs_diff_eq(expected.origin, 1e-5));
+            assert!(ray.direction.abs_diff_eq(*expected.direction, 1e-5));
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    control_target::ControlTarget, math, orbit::OrbitCameraController, utils,
+    viewpoints::Viewpoint, ActiveCameraData, BlendyCamerasDisabled,
+    OtherProjection,
+};
+
+/// Component to pan and zoom the orthographic views of a group of cameras
+/// together, like the quad view of Blender. When the focus or the scale of
+/// one of them changes, the others move their focus along their own view
+/// plane and take the same scale. The members with a perspective projection
+/// are not synchronized.
+///
+/// The members must have an [`OrbitCameraController`]. They are updated
+/// immediately when they have no parent and control their own transform,
+/// otherwise during the next frame.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct SynchronizedViews {
+    /// The cameras with the same group are synchronized
+    pub group: u32,
+    /// Viewpoint the rotation of the camera is locked to, like
+    /// [`OrbitCameraController::locked_viewpoint`] which takes precedence.
+    /// Typically set for the orthographic views and `None` for the
+    /// perspective one.
+    pub viewpoint: Option<Viewpoint>,
+}
+
+/// Member of a group of [`SynchronizedViews`] during the synchronization
+struct Member {
+    entity: Entity,
+    focus: Vec3,
+    radius: f32,
+    forward: Vec3,
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn synchronize_views_system(
+    active_cam: Res<ActiveCameraData>,
+    mut cameras_query: Query<
+        (
+            Entity,
+            &SynchronizedViews,
+            &mut OrbitCameraController,
+            &mut Transform,
+            &mut Projection,
+            Option<&mut OtherProjection>,
+            Has<Parent>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+    mut synchronized: Local<HashMap<Entity, (Vec3, f32)>>,
+) {
+    let mut groups = HashMap::<u32, Vec<Member>>::new();
+    for (entity, views, controller, _, projection, _, _) in cameras_query.iter()
+    {
+        let (Some(yaw), Some(pitch), Some(radius)) =
+            (controller.yaw, controller.pitch, controller.radius)
+        else {
+            continue;
+        };
+        if !controller.is_enabled
+            || !matches!(*projection, Projection::Orthographic(_))
+        {
+            continue;
+        }
+        let forward =
+            *math::camera_transform_from_orbit(yaw, pitch, 1.0, Vec3::ZERO)
+                .forward();
+        groups.entry(views.group).or_default().push(Member {
+            entity,
+            focus: controller.focus,
+            radius,
+            forward,
+        });
+    }
+    synchronized.retain(|entity, _| {
+        groups
+            .values()
+            .flatten()
+            .any(|member| member.entity == *entity)
+    });
+
+    for members in groups.values() {
+        // The member that moved since the last synchronization, preferably
+        // the active camera, or a reference for the new members
+        let is_changed = |member: &&Member| {
+            synchronized
+                .get(&member.entity)
+                .is_some_and(|&synced| synced != (member.focus, member.radius))
+        };
+        let reference = members
+            .iter()
+            .filter(is_changed)
+            .find(|member| active_cam.entity == Some(member.entity))
+            .or_else(|| members.iter().find(is_changed))
+            .or_else(|| {
+                members
+                    .iter()
+                    .any(|member| !synchronized.contains_key(&member.entity))
+                    .then(|| {
+                        members
+                            .iter()
+                            .find(|member| {
+                                synchronized.contains_key(&member.entity)
+                            })
+                            .unwrap_or(&members[0])
+                    })
+            });
+        let Some(reference) = reference else {
+            continue;
+        };
+        for member in members {
+            let (focus, radius) = if member.entity == reference.entity {
+                (member.focus, member.radius)
+            } else {
+                // Keep the depth of the focus along the view direction
+                let offset = reference.focus - member.focus;
+                let offset =
+                    offset - member.forward * offset.dot(member.forward);
+                (member.focus + offset, reference.radius)
+            };
+            synchronized.insert(member.entity, (focus, radius));
+            if (focus, radius) == (member.focus, member.radius) {
+                continue;
+            }
+            let Ok((
+                _,
+                _,
+                mut controller,
+                mut transform,
+                mut projection,
+                other_projection_opt,
+                has_parent,
+            )) = cameras_query.get_mut(member.entity)
+            else {
+                continue;
+            };
+            controller.focus = focus;
+            controller.radius = Some(radius);
+            if has_parent
+                || controller.control_target != ControlTarget::SelfEntity
+            {
+                controller.force_update = true;
+                continue;
+            }
+            let (Some(yaw), Some(pitch)) = (controller.yaw, controller.pitch)
+            else {
+                continue;
+            };
+            math::update_orbit_transform(
+                yaw,
+                pitch,
+                radius,
+                focus,
+                &mut transform,
+                &mut projection,
+                controller.ortho_placement,
+            );
+            if controller.auto_clip_planes {
+                utils::update_clip_planes(radius, &mut projection);
+                if let Some(mut other_projection) = other_projection_opt {
+                    utils::update_clip_planes(radius, &mut other_projection.0);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{set_active_camera, test_app, SimulatedInput};
+
+    fn spawn_view(app: &mut App, viewpoint: Viewpoint) -> Entity {
+        app.world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::Orthographic(OrthographicProjection::default_3d()),
+                Transform::from_xyz(1.0, 2.0, 10.0),
+                OrbitCameraController::default(),
+                SynchronizedViews {
+                    group: 0,
+                    viewpoint: Some(viewpoint),
+                },
+            ))
+            .id()
+    }
+
+    fn controller(app: &App, camera: Entity) -> &OrbitCameraController {
+        app.world().get::<OrbitCameraController>(camera).unwrap()
+    }
+
+    #[test]
+    fn pan_and_zoom_move_the_other_views() {
+        let mut app = test_app();
+        let front = spawn_view(&mut app, Viewpoint::Front);
+        let top = spawn_view(&mut app, Viewpoint::Top);
+        set_active_camera(app.world_mut(), front, Vec2::new(800.0, 600.0));
+        app.update();
+        app.update();
+        let front_start = controller(&app, front).focus;
+        let top_start = controller(&app, top).focus;
+        assert_eq!(front_start, top_start);
+
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_button(MouseButton::Middle)
+            .press_key(KeyCode::ShiftLeft)
+            .pan(Vec2::new(100.0, 100.0));
+        app.update();
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .release_button(MouseButton::Middle)
+            .release_key(KeyCode::ShiftLeft)
+            .scroll(2.0);
+        app.update();
+        let front_controller = controller(&app, front);
+        let front_focus = front_controller.focus;
+        let front_radius = front_controller.radius.unwrap();
+        // The front view panned in its own plane, and zoomed
+        assert_ne!(front_focus.x, front_start.x);
+        assert_ne!(front_focus.y, front_start.y);
+        assert!((front_focus.z - front_start.z).abs() < 1e-3);
+        assert_ne!(front_radius, 1.0);
+
+        // The top view follows along X, the depth of its focus is kept
+        let top_controller = controller(&app, top);
+        assert!((top_controller.focus.x - front_focus.x).abs() < 1e-5);
+        assert!((top_controller.focus.y - top_start.y).abs() < 1e-3);
+        assert!((top_controller.focus.z - front_focus.z).abs() < 1e-5);
+        assert_eq!(top_controller.radius, Some(front_radius));
+        let Projection::Orthographic(ref ortho) =
+            *app.world().get::<Projection>(top).unwrap()
+        else {
+            panic!("the projection is not orthographic");
+        };
+        assert_eq!(ortho.scale, front_radius);
+        let transform = app.world().get::<Transform>(top).unwrap();
+        let to_focus = top_controller.focus - transform.translation;
+        assert!(to_focus.normalize().abs_diff_eq(*transform.forward(), 1e-5));
+    }
+}
@@ -0,0 +1,714 @@
+//! Enabled with the `testing` feature. Add the [`SimulatedInputPlugin`] next
+//! to the [`BlendyCamerasPlugin`](crate::BlendyCamerasPlugin), which must run
+//! in its default schedules, make a camera active with [`set_active_camera`],
+//! then queue the input in the [`SimulatedInput`] resource and call
+//! `App::update`. The input is handled by the controllers during the next
+//! update. [`test_app`] returns such an app, without windows.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_blendy_cameras::{testing::*, OrbitCameraController};
+//! let mut app = test_app();
+//! let camera = app
+//!     .world_mut()
+//!     .spawn((
+//!         Camera::default(),
+//!         Projection::default(),
+//!         Transform::from_xyz(0.0, 0.0, 5.0),
+//!         OrbitCameraController::default(),
+//!     ))
+//!     .id();
+//! set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+//! app.update();
+//! // Drag across a quarter of the window width, a quarter turn
+//! app.world_mut()
+//!     .resource_mut::<SimulatedInput>()
+//!     .orbit(Vec2::new(-200.0, 0.0));
+//! app.update();
+//! let transform = app.world().get::<Transform>(camera).unwrap();
+//! assert_transform_approx_eq(
+//!     transform,
+//!     &Transform::from_xyz(5.0, 0.0, 0.0).looking_at(Vec3::ZERO, Vec3::Y),
+//!     1e-4,
+//! );
+//! ```
+
+use bevy::{
+    input::{keyboard::KeyCode, InputPlugin, InputSystem},
+    prelude::*,
+};
+
+use crate::{
+    input::CameraInputState, ActiveCameraData, BlendyCamerasSystemSet,
+};
+
+/// Plugin applying the [`SimulatedInput`] to the active camera
+pub struct SimulatedInputPlugin;
+
+impl Plugin for SimulatedInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SimulatedInput>()
+            .add_systems(PreUpdate, simulated_buttons_system.after(InputSystem))
+            .add_systems(
+                PostUpdate,
+                simulated_input_system
+                    .after(BlendyCamerasSystemSet::ProcessInput)
+                    .before(BlendyCamerasSystemSet::HandleEvents),
+            );
+    }
+}
+
+/// Resource with the input to simulate during the next update, added to the
+/// [`CameraInputState`] of the active camera. The mouse motions are in
+/// logical pixels. The buttons and keys stay pressed until released, like
+/// real ones.
+#[derive(Resource, Default, Debug)]
+pub struct SimulatedInput {
+    state: CameraInputState,
+    pressed_buttons: Vec<MouseButton>,
+    released_buttons: Vec<MouseButton>,
+    pressed_keys: Vec<KeyCode>,
+    released_keys: Vec<KeyCode>,
+}
+
+impl SimulatedInput {
+    /// Mouse motion while orbiting
+    pub fn orbit(&mut self, delta: Vec2) -> &mut Self {
+        self.state.orbit += delta;
+        self
+    }
+
+    /// Mouse motion while panning
+    pub fn pan(&mut self, delta: Vec2) -> &mut Self {
+        self.state.pan += delta;
+        self
+    }
+
+    /// Scroll, in lines, to zoom or change the fly speed
+    pub fn scroll(&mut self, lines: f32) -> &mut Self {
+        self.state.scroll_line += lines;
+        self
+    }
+
+    /// Vertical mouse motion while dragging to zoom
+    pub fn zoom_drag(&mut self, delta: f32) -> &mut Self {
+        self.state.zoom_drag += delta;
+        self
+    }
+
+    /// Mouse motion while rotating in fly mode
+    pub fn rotate(&mut self, delta: Vec2) -> &mut Self {
+        self.state.rotate += delta;
+        self
+    }
+
+    /// Press a mouse button
+    pub fn press_button(&mut self, button: MouseButton) -> &mut Self {
+        self.pressed_buttons.push(button);
+        self
+    }
+
+    /// Release a mouse button
+    pub fn release_button(&mut self, button: MouseButton) -> &mut Self {
+        self.released_buttons.push(button);
+        self
+    }
+
+    /// Press a key
+    pub fn press_key(&mut self, key: KeyCode) -> &mut Self {
+        self.pressed_keys.push(key);
+        self
+    }
+
+    /// Release a key
+    pub fn release_key(&mut self, key: KeyCode) -> &mut Self {
+        self.released_keys.push(key);
+        self
+    }
+}
+
+/// Make `camera_entity` the camera handling the input, with a viewport and a
+/// window of `viewport_size` logical pixels. The plugin no longer updates
+/// the [`ActiveCameraData`] afterward.
+pub fn set_active_camera(
+    world: &mut World,
+    camera_entity: Entity,
+    viewport_size: Vec2,
+) {
+    world.insert_resource(ActiveCameraData {
+        entity: Some(camera_entity),
+        viewport_size: Some(viewport_size),
+        window_size: Some(viewport_size),
+        manual: true,
+        ..default()
+    });
+}
+
+/// Headless app with the [`BlendyCamerasPlugin`](crate::BlendyCamerasPlugin),
+/// the [`SimulatedInputPlugin`] and the minimal plugins they need, including
+/// what the optional features of the crate need
+pub fn test_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        InputPlugin,
+        WindowPlugin {
+            primary_window: None,
+            ..default()
+        },
+        AssetPlugin::default(),
+        crate::BlendyCamerasPlugin::default(),
+        SimulatedInputPlugin,
+    ))
+    .init_asset::<Mesh>();
+    // Needed by the egui focus check, without the rendering of the
+    // `EguiPlugin`
+    #[cfg(feature = "bevy_egui")]
+    app.init_resource::<bevy_egui::EguiUserTextures>();
+    #[cfg(feature = "navigation_gizmo")]
+    app.init_asset::<bevy::render::render_resource::Shader>()
+        .add_plugins(bevy::gizmos::GizmoPlugin);
+    app
+}
+
+/// Whether the translations, rotations and scales of the transforms are
+/// within `epsilon` of each other. The rotations are compared as rotations,
+/// so `q` and `-q` are equal.
+pub fn transforms_approx_eq(
+    a: &Transform,
+    b: &Transform,
+    epsilon: f32,
+) -> bool {
+    a.translation.abs_diff_eq(b.translation, epsilon)
+        && a.scale.abs_diff_eq(b.scale, epsilon)
+        && a.rotation.dot(b.rotation).abs() >= 1.0 - epsilon
+}
+
+/// Panic if the transforms are not within `epsilon` of each other, see
+/// [`transforms_approx_eq`]
+#[track_caller]
+pub fn assert_transform_approx_eq(
+    actual: &Transform,
+    expected: &Transform,
+    epsilon: f32,
+) {
+    assert!(
+        transforms_approx_eq(actual, expected, epsilon),
+        "transforms differ by more than {epsilon}\n  actual: \
+        {actual:?}\nexpected: {expected:?}"
+    );
+}
+
+fn simulated_buttons_system(
+    mut simulated: ResMut<SimulatedInput>,
+    mut mouse_input: ResMut<ButtonInput<MouseButton>>,
+    mut key_input: ResMut<ButtonInput<KeyCode>>,
+) {
+    let simulated = &mut *simulated;
+    for button in simulated.pressed_buttons.drain(..) {
+        mouse_input.press(button);
+    }
+    for button in simulated.released_buttons.drain(..) {
+        mouse_input.release(button);
+    }
+    for key in simulated.pressed_keys.drain(..) {
+        key_input.press(key);
+    }
+    for key in simulated.released_keys.drain(..) {
+        key_input.release(key);
+    }
+}
+
+fn simulated_input_system(
+    mut simulated: ResMut<SimulatedInput>,
+    active_cam: Res<ActiveCameraData>,
+    mut input_states: Query<&mut CameraInputState>,
+) {
+    let state = std::mem::take(&mut simulated.state);
+    let Some(mut input_state) = active_cam
+        .entity
+        .and_then(|entity| input_states.get_mut(entity).ok())
+    else {
+        return;
+    };
+    input_state.orbit += state.orbit;
+    input_state.pan += state.pan;
+    input_state.scroll_line += state.scroll_line;
+    input_state.zoom_drag += state.zoom_drag;
+    input_state.rotate += state.rotate;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use bevy::{
+        ecs::system::RunSystemOnce,
+        input::mouse::MouseMotion,
+        render::camera::{camera_system, ManualTextureViews},
+        window::{CursorGrabMode, PrimaryWindow, WindowFocused},
+    };
+
+    use super::*;
+    #[allow(deprecated)]
+    use crate::{
+        BlendyCamerasConfig, FlyCameraController, MouseKeyTracker,
+        OrbitCameraController, SnapToNearestViewpointEvent, Viewpoint,
+    };
+
+    fn spawn_orbit_camera(app: &mut App, transform: Transform) -> Entity {
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                transform,
+                OrbitCameraController::default(),
+            ))
+            .id();
+        set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+        app.update();
+        camera
+    }
+
+    /// Spawn the primary window, and compute the viewports of the cameras
+    /// rendering to it, which are only computed by the render plugins
+    fn spawn_primary_window(app: &mut App) -> Entity {
+        app.init_asset::<Image>()
+            .init_resource::<ManualTextureViews>();
+        let window = app
+            .world_mut()
+            .spawn((Window::default(), PrimaryWindow))
+            .id();
+        app.world_mut()
+            .run_system_once(camera_system::<Projection>)
+            .unwrap();
+        window
+    }
+
+    fn controller(app: &App, camera: Entity) -> &OrbitCameraController {
+        app.world().get::<OrbitCameraController>(camera).unwrap()
+    }
+
+    fn transform(app: &App, camera: Entity) -> Transform {
+        *app.world().get::<Transform>(camera).unwrap()
+    }
+
+    #[test]
+    fn orbit_quarter_turn() {
+        let mut app = test_app();
+        let camera = spawn_orbit_camera(
+            &mut app,
+            Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        );
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .orbit(Vec2::new(-200.0, 0.0));
+        app.update();
+        assert_transform_approx_eq(
+            &transform(&app, camera),
+            &Transform::from_xyz(5.0, 0.0, 0.0).looking_at(Vec3::ZERO, Vec3::Y),
+            1e-4,
+        );
+        let controller = controller(&app, camera);
+        assert!((controller.yaw.unwrap() - FRAC_PI_2).abs() < 1e-4);
+        assert!(controller.focus.abs_diff_eq(Vec3::ZERO, 1e-4));
+    }
+
+    #[test]
+    fn pan_keeps_focus_plane() {
+        let mut app = test_app();
+        let camera = spawn_orbit_camera(
+            &mut app,
+            Transform::from_xyz(3.0, 4.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        );
+        let start = transform(&app, camera);
+        let start_radius = controller(&app, camera).radius.unwrap();
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .pan(Vec2::new(120.0, -80.0));
+        app.update();
+        let end = transform(&app, camera);
+        let controller = controller(&app, camera);
+        assert!(controller.focus.length() > 0.1);
+        // The focus moves in the view plane and the camera follows it
+        assert!(controller.focus.dot(*start.forward()).abs() < 1e-4);
+        assert!((controller.radius.unwrap() - start_radius).abs() < 1e-4);
+        assert_transform_approx_eq(
+            &end,
+            &Transform {
+                translation: start.translation + controller.focus,
+                ..start
+            },
+            1e-4,
+        );
+    }
+
+    #[test]
+    fn zoom_clamps_at_lower_limit() {
+        let mut app = test_app();
+        let camera = spawn_orbit_camera(
+            &mut app,
+            Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        );
+        app.world_mut()
+            .get_mut::<OrbitCameraController>(camera)
+            .unwrap()
+            .zoom_lower_limit = 1.0;
+        for _ in 0..10 {
+            app.world_mut()
+                .resource_mut::<SimulatedInput>()
+                .scroll(20.0);
+            app.update();
+        }
+        assert_eq!(controller(&app, camera).radius, Some(1.0));
+        assert_transform_approx_eq(
+            &transform(&app, camera),
+            &Transform::from_xyz(0.0, 0.0, 1.0).looking_at(Vec3::ZERO, Vec3::Y),
+            1e-4,
+        );
+    }
+
+    #[test]
+    fn snap_to_nearest_viewpoint() {
+        let mut app = test_app();
+        let camera = spawn_orbit_camera(
+            &mut app,
+            Transform::from_xyz(1.0, 4.0, 0.5).looking_at(Vec3::ZERO, Vec3::Y),
+        );
+        let radius = controller(&app, camera).radius.unwrap();
+        app.world_mut().send_event(SnapToNearestViewpointEvent {
+            camera_entity: Some(camera),
+        });
+        app.update();
+        assert_transform_approx_eq(
+            &transform(&app, camera),
+            &Viewpoint::Top.to_transform(Vec3::ZERO, radius),
+            1e-4,
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn despawning_the_camera_mid_drag_resets_the_active_camera() {
+        let mut app = test_app();
+        let camera = spawn_orbit_camera(
+            &mut app,
+            Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        );
+        app.world_mut()
+            .get_mut::<OrbitCameraController>(camera)
+            .unwrap()
+            .hide_cursor_during_drag = true;
+        let window = spawn_primary_window(&mut app);
+        app.world_mut()
+            .resource_mut::<ActiveCameraData>()
+            .window_entity = Some(window);
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_button(MouseButton::Middle)
+            .orbit(Vec2::new(50.0, 0.0));
+        app.update();
+        let cursor_options =
+            &app.world().get::<Window>(window).unwrap().cursor_options;
+        assert_eq!(cursor_options.grab_mode, CursorGrabMode::Locked);
+        assert!(!cursor_options.visible);
+        app.world_mut().despawn(camera);
+        app.update();
+        assert_eq!(app.world().resource::<ActiveCameraData>().entity, None);
+        assert_eq!(
+            app.world().resource::<MouseKeyTracker>().0,
+            CameraInputState::default()
+        );
+        let cursor_options =
+            &app.world().get::<Window>(window).unwrap().cursor_options;
+        assert_eq!(cursor_options.grab_mode, CursorGrabMode::None);
+        assert!(cursor_options.visible);
+    }
+
+    /// Orbit camera hiding the cursor of the primary window while dragging,
+    /// once the mouse moved more than `threshold` pixels
+    fn spawn_threshold_camera(
+        app: &mut App,
+        threshold: f32,
+    ) -> (Entity, Entity) {
+        let camera = spawn_orbit_camera(
+            app,
+            Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        );
+        {
+            let mut controller = app
+                .world_mut()
+                .get_mut::<OrbitCameraController>(camera)
+                .unwrap();
+            controller.hide_cursor_during_drag = true;
+            controller.drag_start_threshold_px = threshold;
+        }
+        let window = spawn_primary_window(app);
+        app.world_mut()
+            .resource_mut::<ActiveCameraData>()
+            .window_entity = Some(window);
+        (camera, window)
+    }
+
+    /// Update with a real mouse motion of `delta`, and return the orbit
+    /// motion of the camera and whether the cursor is grabbed
+    fn move_mouse(
+        app: &mut App,
+        camera: Entity,
+        window: Entity,
+        delta: Vec2,
+    ) -> (Vec2, bool) {
+        app.world_mut().send_event(MouseMotion { delta });
+        app.update();
+        let world = app.world();
+        let grab_mode = world
+            .get::<Window>(window)
+            .unwrap()
+            .cursor_options
+            .grab_mode;
+        (
+            world.get::<CameraInputState>(camera).unwrap().orbit,
+            grab_mode == CursorGrabMode::Locked,
+        )
+    }
+
+    #[test]
+    fn drag_without_threshold_starts_on_press() {
+        let mut app = test_app();
+        let (camera, window) = spawn_threshold_camera(&mut app, 0.0);
+        let yaw = controller(&app, camera).yaw.unwrap();
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_button(MouseButton::Middle);
+        let delta = Vec2::new(20.0, 0.0);
+        assert_eq!(move_mouse(&mut app, camera, window, delta), (delta, true));
+        // A full turn across the width of the window
+        let delta_yaw = yaw - controller(&app, camera).yaw.unwrap();
+        assert!((delta_yaw - 20.0 / 800.0 * 2.0 * PI).abs() < 1e-5);
+        assert_eq!(move_mouse(&mut app, camera, window, delta), (delta, true));
+    }
+
+    #[test]
+    fn drag_starts_with_the_whole_motion_past_the_threshold() {
+        let mut app = test_app();
+        let (camera, window) = spawn_threshold_camera(&mut app, 10.0);
+        let start = transform(&app, camera);
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_button(MouseButton::Middle);
+        for delta in [Vec2::new(3.0, 0.0), Vec2::new(3.0, 4.0)] {
+            assert_eq!(
+                move_mouse(&mut app, camera, window, delta),
+                (Vec2::ZERO, false)
+            );
+            assert_eq!(transform(&app, camera), start);
+        }
+        assert_eq!(
+            move_mouse(&mut app, camera, window, Vec2::new(4.0, 0.0)),
+            (Vec2::new(10.0, 4.0), true)
+        );
+        assert_ne!(transform(&app, camera), start);
+        let delta = Vec2::new(1.0, 0.0);
+        assert_eq!(move_mouse(&mut app, camera, window, delta), (delta, true));
+    }
+
+    #[test]
+    fn click_below_the_threshold_never_grabs_the_cursor() {
+        let mut app = test_app();
+        let (camera, window) = spawn_threshold_camera(&mut app, 10.0);
+        let start = transform(&app, camera);
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_button(MouseButton::Middle);
+        for _ in 0..3 {
+            assert_eq!(
+                move_mouse(&mut app, camera, window, Vec2::ZERO),
+                (Vec2::ZERO, false)
+            );
+        }
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .release_button(MouseButton::Middle);
+        assert_eq!(
+            move_mouse(&mut app, camera, window, Vec2::ZERO),
+            (Vec2::ZERO, false)
+        );
+        let cursor_options =
+            &app.world().get::<Window>(window).unwrap().cursor_options;
+        assert!(cursor_options.visible);
+        assert_eq!(transform(&app, camera), start);
+    }
+
+    /// Ways the drag can be interrupted while the button is still pressed
+    enum Interruption {
+        FocusLost,
+        ControllerRemoved,
+        PluginDisabled,
+    }
+
+    #[test]
+    fn interrupted_drag_releases_the_cursor() {
+        for interruption in [
+            Interruption::FocusLost,
+            Interruption::ControllerRemoved,
+            Interruption::PluginDisabled,
+        ] {
+            let mut app = test_app();
+            let camera = spawn_orbit_camera(
+                &mut app,
+                Transform::from_xyz(0.0, 0.0, 5.0)
+                    .looking_at(Vec3::ZERO, Vec3::Y),
+            );
+            app.world_mut()
+                .get_mut::<OrbitCameraController>(camera)
+                .unwrap()
+                .hide_cursor_during_drag = true;
+            let window = spawn_primary_window(&mut app);
+            app.world_mut()
+                .resource_mut::<ActiveCameraData>()
+                .window_entity = Some(window);
+            app.world_mut()
+                .resource_mut::<SimulatedInput>()
+                .press_button(MouseButton::Middle)
+                .orbit(Vec2::new(50.0, 0.0));
+            // Grabbed for the whole drag
+            for _ in 0..2 {
+                app.update();
+                let cursor_options =
+                    &app.world().get::<Window>(window).unwrap().cursor_options;
+                assert_eq!(cursor_options.grab_mode, CursorGrabMode::Locked);
+                assert!(!cursor_options.visible);
+                app.world_mut()
+                    .resource_mut::<SimulatedInput>()
+                    .orbit(Vec2::new(10.0, 0.0));
+            }
+            match interruption {
+                Interruption::FocusLost => {
+                    app.world_mut().send_event(WindowFocused {
+                        window,
+                        focused: false,
+                    });
+                }
+                Interruption::ControllerRemoved => {
+                    app.world_mut()
+                        .entity_mut(camera)
+                        .remove::<OrbitCameraController>();
+                }
+                Interruption::PluginDisabled => {
+                    app.world_mut()
+                        .resource_mut::<BlendyCamerasConfig>()
+                        .enabled = false;
+                }
+            }
+            // The button is still pressed, the cursor is not grabbed again
+            for _ in 0..2 {
+                app.world_mut()
+                    .resource_mut::<SimulatedInput>()
+                    .orbit(Vec2::new(10.0, 0.0));
+                app.update();
+                let cursor_options =
+                    &app.world().get::<Window>(window).unwrap().cursor_options;
+                assert_eq!(cursor_options.grab_mode, CursorGrabMode::None);
+                assert!(cursor_options.visible);
+            }
+        }
+    }
+
+    #[test]
+    fn suppress_modifier_ends_and_restarts_the_drag() {
+        let mut app = test_app();
+        let (camera, window) = spawn_threshold_camera(&mut app, 0.0);
+        app.world_mut()
+            .get_mut::<OrbitCameraController>(camera)
+            .unwrap()
+            .suppress_modifier = Some(KeyCode::KeyM);
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_button(MouseButton::Middle);
+        let delta = Vec2::new(20.0, 0.0);
+        assert_eq!(move_mouse(&mut app, camera, window, delta), (delta, true));
+        // Holding the key mid-drag ends it as if the button was released
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_key(KeyCode::KeyM);
+        for _ in 0..2 {
+            let suppressed = transform(&app, camera);
+            assert_eq!(
+                move_mouse(&mut app, camera, window, delta),
+                (Vec2::ZERO, false)
+            );
+            assert_eq!(transform(&app, camera), suppressed);
+            assert_eq!(
+                app.world().resource::<ActiveCameraData>().interaction,
+                None
+            );
+            let cursor_options =
+                &app.world().get::<Window>(window).unwrap().cursor_options;
+            assert!(cursor_options.visible);
+        }
+        // Releasing it while the button is still held starts a new drag
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .release_key(KeyCode::KeyM);
+        let resumed = transform(&app, camera);
+        assert_eq!(move_mouse(&mut app, camera, window, delta), (delta, true));
+        assert_ne!(transform(&app, camera), resumed);
+        // And the key ends it again
+        app.world_mut()
+            .resource_mut::<SimulatedInput>()
+            .press_key(KeyCode::KeyM);
+        assert_eq!(
+            move_mouse(&mut app, camera, window, delta),
+            (Vec2::ZERO, false)
+        );
+    }
+
+    #[test]
+    fn zero_viewport_size_keeps_the_cameras_finite() {
+        let mut app = test_app();
+        let camera = spawn_orbit_camera(
+            &mut app,
+            Transform::from_xyz(3.0, 4.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        );
+        set_active_camera(app.world_mut(), camera, Vec2::ZERO);
+        for _ in 0..3 {
+            app.world_mut()
+                .resource_mut::<SimulatedInput>()
+                .orbit(Vec2::new(40.0, -25.0))
+                .pan(Vec2::new(-30.0, 60.0))
+                .scroll(2.0);
+            app.update();
+        }
+        assert!(transform(&app, camera).is_finite());
+        let controller = controller(&app, camera);
+        assert!(controller.yaw.is_some_and(f32::is_finite));
+        assert!(controller.pitch.is_some_and(f32::is_finite));
+        assert!(controller.radius.is_some_and(f32::is_finite));
+        assert!(controller.focus.is_finite());
+
+        let fly_camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 1.0, 5.0),
+                FlyCameraController::default(),
+            ))
+            .id();
+        set_active_camera(app.world_mut(), fly_camera, Vec2::ZERO);
+        app.update();
+        for _ in 0..3 {
+            app.world_mut()
+                .resource_mut::<SimulatedInput>()
+                .rotate(Vec2::new(40.0, -25.0))
+                .scroll(2.0);
+            app.update();
+        }
+        assert!(transform(&app, fly_camera).is_finite());
+        let fly_controller =
+            app.world().get::<FlyCameraController>(fly_camera).unwrap();
+        assert!(fly_controller.speed.is_finite());
+    }
+}
@@ -0,0 +1,264 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    dolly_zoom::{dolly_zoom_radius, DollyZoomCompleted},
+    fly::FlyCameraController,
+    input::{self, CameraInputBlocked, CameraInputState},
+    orbit::OrbitCameraController,
+    utils, ActiveCameraData, BlendyCamerasDisabled,
+};
+
+/// State of an [`OrbitCameraController`] that can be interpolated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct OrbitState {
+    pub focus: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub radius: f32,
+}
+
+impl OrbitState {
+    /// Return `None` if the controller is not initialized
+    pub(crate) fn from_controller(
+        controller: &OrbitCameraController,
+    ) -> Option<Self> {
+        Some(Self {
+            focus: controller.focus,
+            yaw: controller.yaw?,
+            pitch: controller.pitch?,
+            radius: controller.radius?,
+        })
+    }
+
+    /// Set the controller values, the transform is updated by the
+    /// controller system
+    pub(crate) fn apply(&self, controller: &mut OrbitCameraController) {
+        controller.focus = self.focus;
+        controller.yaw = Some(self.yaw);
+        controller.pitch = Some(self.pitch);
+        controller.radius = Some(self.radius);
+        controller.force_update = true;
+    }
+
+    fn lerp(&self, end: &Self, t: f32) -> Self {
+        Self {
+            focus: self.focus.lerp(end.focus, t),
+            yaw: utils::lerp_angle(self.yaw, end.yaw, t),
+            pitch: utils::lerp_angle(self.pitch, end.pitch, t),
+            radius: self.radius + (end.radius - self.radius) * t,
+        }
+    }
+}
+
+enum ViewTransitionKind {
+    Orbit {
+        start: OrbitState,
+        end: OrbitState,
+    },
+    Fly {
+        start: Transform,
+        end: Transform,
+    },
+    DollyZoom {
+        start: OrbitState,
+        start_fov: f32,
+        end_fov: f32,
+    },
+}
+
+/// Component added to a camera while it is animated toward a new view.
+/// Removed when the animation is finished or cancelled by user input.
+#[derive(Component)]
+pub(crate) struct ViewTransition {
+    timer: Timer,
+    kind: ViewTransitionKind,
+}
+
+impl ViewTransition {
+    pub(crate) fn orbit(
+        start: OrbitState,
+        end: OrbitState,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            timer: Timer::new(duration, TimerMode::Once),
+            kind: ViewTransitionKind::Orbit { start, end },
+        }
+    }
+
+    pub(crate) fn fly(
+        start: Transform,
+        end: Transform,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            timer: Timer::new(duration, TimerMode::Once),
+            kind: ViewTransitionKind::Fly { start, end },
+        }
+    }
+
+    pub(crate) fn dolly_zoom(
+        start: OrbitState,
+        start_fov: f32,
+        end_fov: f32,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            timer: Timer::new(duration, TimerMode::Once),
+            kind: ViewTransitionKind::DollyZoom {
+                start,
+                start_fov,
+                end_fov,
+            },
+        }
+    }
+
+    fn is_dolly_zoom(&self) -> bool {
+        matches!(self.kind, ViewTransitionKind::DollyZoom { .. })
+    }
+}
+
+/// Animate the cameras with a [`ViewTransition`], cancelling the animation as
+/// soon as the user starts moving the camera
+#[allow(clippy::type_complexity)]
+pub(crate) fn view_transition_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    active_cam: Res<ActiveCameraData>,
+    key_input: Res<ButtonInput<KeyCode>>,
+    input_blocked: Res<CameraInputBlocked>,
+    mut cameras_query: Query<
+        (
+            Entity,
+            &mut ViewTransition,
+            &mut Transform,
+            Option<&mut OrbitCameraController>,
+            Option<&FlyCameraController>,
+            Option<&CameraInputState>,
+            Option<&mut Projection>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+    mut dolly_zoom_ev_write: EventWriter<DollyZoomCompleted>,
+) {
+    for (
+        entity,
+        mut transition,
+        mut transform,
+        orbit_controller_opt,
+        fly_controller_opt,
+        input_state_opt,
+        projection_opt,
+    ) in cameras_query.iter_mut()
+    {
+        let fly_moving = fly_controller_opt.is_some_and(|controller| {
+            controller.is_enabled && input::move_pressed(controller, &key_input)
+        });
+        let orbit_moving =
+            orbit_controller_opt.as_ref().is_some_and(|controller| {
+                controller.is_enabled
+                    && (input::key_pan_direction(controller, &key_input)
+                        != Vec2::ZERO
+                        || input::key_zoom_direction(controller, &key_input)
+                            != 0.0)
+            });
+        if active_cam.entity == Some(entity)
+            && !input_blocked.is_blocked()
+            && (input_state_opt.is_some_and(CameraInputState::has_input)
+                || fly_moving
+                || orbit_moving)
+        {
+            commands.entity(entity).remove::<ViewTransition>();
+            if transition.is_dolly_zoom() {
+                dolly_zoom_ev_write.send(DollyZoomCompleted {
+                    camera_entity: entity,
+                    is_cancelled: true,
+                });
+            }
+            continue;
+        }
+        transition.timer.tick(time.delta());
+        let finished = transition.timer.finished();
+        // Smoothstep easing
+        let t = transition.timer.fraction();
+        let t = t * t * (3.0 - 2.0 * t);
+        match transition.kind {
+            ViewTransitionKind::Orbit { start, end } => {
+                let Some(mut controller) = orbit_controller_opt
+                    .filter(|controller| controller.is_enabled)
+                else {
+                    commands.entity(entity).remove::<ViewTransition>();
+                    continue;
+                };
+                // Make sure the final state is exactly the requested one
+                let state = if finished { end } else { start.lerp(&end, t) };
+                state.apply(&mut controller);
+            }
+            ViewTransitionKind::Fly { start, end } => {
+                if !fly_controller_opt
+                    .is_some_and(|controller| controller.is_enabled)
+                {
+                    commands.entity(entity).remove::<ViewTransition>();
+                    continue;
+                }
+                *transform = if finished {
+                    end
+                } else {
+                    Transform {
+                        translation: start.translation.lerp(end.translation, t),
+                        rotation: start.rotation.slerp(end.rotation, t),
+                        scale: end.scale,
+                    }
+                };
+            }
+            ViewTransitionKind::DollyZoom {
+                start,
+                start_fov,
+                end_fov,
+            } => {
+                let (Some(mut controller), Some(mut projection)) = (
+                    orbit_controller_opt
+                        .filter(|controller| controller.is_enabled),
+                    projection_opt,
+                ) else {
+                    commands.entity(entity).remove::<ViewTransition>();
+                    dolly_zoom_ev_write.send(DollyZoomCompleted {
+                        camera_entity: entity,
+                        is_cancelled: true,
+                    });
+                    continue;
+                };
+                let Projection::Perspective(ref mut p) = *projection else {
+                    commands.entity(entity).remove::<ViewTransition>();
+                    dolly_zoom_ev_write.send(DollyZoomCompleted {
+                        camera_entity: entity,
+                        is_cancelled: true,
+                    });
+                    continue;
+                };
+                let fov = if finished {
+                    end_fov
+                } else {
+                    start_fov + (end_fov - start_fov) * t
+                };
+                p.fov = fov;
+                let state = OrbitState {
+                    radius: dolly_zoom_radius(start.radius, start_fov, fov),
+                    ..start
+                };
+                state.apply(&mut controller);
+                if finished {
+                    dolly_zoom_ev_write.send(DollyZoomCompleted {
+                        camera_entity: entity,
+                        is_cancelled: false,
+                    });
+                }
+            }
+        }
+        if finished {
+            commands.entity(entity).remove::<ViewTransition>();
+        }
+    }
+}
@@ -1,56 +1,220 @@
-use bevy::prelude::*;
-
-pub fn calculate_from_translation_and_focus(
-    translation: Vec3,
-    focus: Vec3,
-) -> (f32, f32, f32) {
-    let comp_vec = translation - focus;
-    // let mut radius = comp_vec.length();
-    // if radius == 0.0 {
-    //     radius = 0.05; // Radius 0 causes problems
-    // }
-    let radius = comp_vec.length().max(0.05);
-    let yaw = if comp_vec.x == 0.0 && comp_vec.z >= 0.0 {
-        0.0
+use std::f32::consts::{PI, TAU};
+
+use bevy::{prelude::*, render::camera::CameraProjection};
+
+use crate::orbit::OrthoPlacement;
+
+/// Part of the projection changed by zooming: the scale if orthographic, the
+/// field of view if perspective
+pub fn projection_zoom(projection: &Projection) -> f32 {
+    match projection {
+        Projection::Orthographic(p) => p.scale,
+        Projection::Perspective(p) => p.fov,
+    }
+}
+
+/// Size of the view of an orthographic projection with `scale` in a viewport
+/// of `viewport_size` logical pixels, in world units. Unlike the `area` of the
+/// projection, which is only updated before rendering, this is always in
+/// sync with the scale and the viewport.
+pub fn orthographic_view_size(
+    projection: &OrthographicProjection,
+    scale: f32,
+    viewport_size: Option<Vec2>,
+) -> Vec2 {
+    let viewport_size = non_empty_size(viewport_size).unwrap_or(Vec2::ONE);
+    let mut projection = projection.clone();
+    projection.scale = scale;
+    projection.update(viewport_size.x, viewport_size.y);
+    projection.area.size()
+}
+
+/// Height of the view of an orthographic projection with a scale of `1.0`,
+/// in world units
+fn orthographic_unit_height(
+    projection: &OrthographicProjection,
+    viewport_size: Option<Vec2>,
+) -> f32 {
+    orthographic_view_size(projection, 1.0, viewport_size).y
+}
+
+/// Height of the view at the focus, in world units, for the orbit `radius`,
+/// which is the scale if the projection is orthographic
+fn focus_view_height(
+    projection: &Projection,
+    radius: f32,
+    viewport_size: Option<Vec2>,
+) -> f32 {
+    match projection {
+        Projection::Perspective(p) => 2.0 * radius * (p.fov / 2.0).tan(),
+        Projection::Orthographic(p) => {
+            radius * orthographic_unit_height(p, viewport_size)
+        }
+    }
+}
+
+/// Orbit radius, or scale if orthographic, for `next_projection` to show
+/// the same height at the focus as `radius` with `projection`. Converting
+/// back and forth gives the initial radius, so switching the projection
+/// repeatedly does not drift the framing.
+pub fn switched_projection_radius(
+    projection: &Projection,
+    next_projection: &Projection,
+    radius: f32,
+    viewport_size: Option<Vec2>,
+) -> f32 {
+    let height = focus_view_height(projection, radius, viewport_size);
+    let unit_height = focus_view_height(next_projection, 1.0, viewport_size);
+    let next_radius = height / unit_height;
+    if next_radius.is_finite() && next_radius > 0.0 {
+        next_radius
     } else {
-        (comp_vec.z / (comp_vec.x.powi(2) + comp_vec.z.powi(2)).sqrt()).acos()
-    };
-    let pitch = (comp_vec.y / radius).asin();
-    (yaw, pitch, radius)
+        radius
+    }
 }
 
-/// Update `transform` based on yaw, pitch, and the camera's focus and radius
-pub fn update_orbit_transform(
-    yaw: f32,
-    pitch: f32,
-    mut radius: f32,
-    focus: Vec3,
-    transform: &mut Transform,
+pub fn approx_equal(a: f32, b: f32, epsilon: f32) -> bool {
+    (a - b).abs() < epsilon
+    // (a - b).abs() < 1000.0 * f32::EPSILON
+}
+
+/// Same as [`approx_equal`] but for angles in radian, taking the wrap around
+/// at PI into account
+pub fn approx_equal_angle(a: f32, b: f32, epsilon: f32) -> bool {
+    approx_equal(wrap_angle(a - b), 0.0, epsilon)
+}
+
+/// `size` if it is finite and not empty, to avoid dividing by a zero sized
+/// viewport or window
+pub fn non_empty_size(size: Option<Vec2>) -> Option<Vec2> {
+    size.filter(|size| size.is_finite() && size.x > 0.0 && size.y > 0.0)
+}
+
+/// Interpolate between two angles in radian following the shortest path
+pub fn lerp_angle(start: f32, end: f32, t: f32) -> f32 {
+    let delta = (end - start + PI).rem_euclid(TAU) - PI;
+    start + delta * t
+}
+
+/// Wrap an angle in radian to the range `[-PI, PI)`
+pub fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+/// Round an angle in radian to the nearest multiple of `step`
+pub fn snap_angle(angle: f32, step: f32) -> f32 {
+    (angle / step).round() * step
+}
+
+/// Distance from the camera to the far plane never goes under this value
+/// when the clipping planes are automatically adjusted
+const AUTO_CLIP_MIN_FAR: f32 = 1000.0;
+
+/// Extend the near and far planes of an orthographic `projection` so that a
+/// sphere of `radius` around the focus is not clipped, the camera being at
+/// the distance given by `placement` from the focus. The planes are never
+/// moved closer to each other and perspective projections are not changed.
+pub fn fit_orthographic_clip_planes(
+    radius: f32,
+    placement: OrthoPlacement,
     projection: &mut Projection,
 ) {
-    if let Projection::Orthographic(ref mut p) = *projection {
-        p.scale = radius;
-        // (near + far) / 2.0 ensures that objects near `focus` are not clipped
-        radius = (p.near + p.far) / 2.0;
+    let Projection::Orthographic(p) = projection else {
+        return;
+    };
+    if !radius.is_finite() || radius <= 0.0 {
+        return;
+    }
+    match placement {
+        // The camera moves with the planes, keep it in the middle of a
+        // depth range covering the sphere
+        OrthoPlacement::ClipPlanesMiddle => {
+            p.far = p.far.max(p.near + 2.0 * radius);
+        }
+        // The near plane may be behind the camera with an orthographic
+        // projection
+        _ => {
+            let distance = placement.distance(p);
+            p.near = p.near.min(distance - radius);
+            p.far = p.far.max(distance + radius);
+        }
     }
-    *transform = camera_transform_form_orbit(yaw, pitch, radius, focus);
 }
 
-pub fn camera_transform_form_orbit(
-    yaw: f32,
-    pitch: f32,
-    radius: f32,
-    focus: Vec3,
-) -> Transform {
-    let mut transform = Transform::IDENTITY;
-    transform.rotation =
-        Quat::from_rotation_y(yaw) * Quat::from_rotation_x(-pitch);
-    transform.translation = focus + transform.back() * radius;
-    transform
-}
-
-const EPSILON: f32 = 0.001;
-pub fn approx_equal(a: f32, b: f32) -> bool {
-    (a - b).abs() < EPSILON
-    // (a - b).abs() < 1000.0 * f32::EPSILON
+/// Set the near and far planes of `projection` to values suited to a camera
+/// at `distance` from the geometry of interest, i.e. the orbit radius. The
+/// planes are only changed when they are off by more than a factor of 2, so
+/// that small zoom changes do not update the projection every frame.
+pub fn update_clip_planes(distance: f32, projection: &mut Projection) {
+    let (near, far, current_near, current_far) = match projection {
+        Projection::Perspective(p) => (
+            (distance / 1000.0).clamp(0.0001, 1.0),
+            (distance * 10.0).max(AUTO_CLIP_MIN_FAR),
+            p.near,
+            p.far,
+        ),
+        // By default, the camera is placed at (near + far) / 2 from the focus
+        Projection::Orthographic(p) => (
+            p.near,
+            (distance * 20.0).max(AUTO_CLIP_MIN_FAR),
+            p.near,
+            p.far,
+        ),
+    };
+    let is_off = |current: f32, target: f32| {
+        current <= 0.0 || !(0.5..=2.0).contains(&(target / current))
+    };
+    if (near > 0.0 && is_off(current_near, near)) || is_off(current_far, far) {
+        match projection {
+            Projection::Perspective(p) => {
+                p.near = near;
+                p.far = far;
+            }
+            Projection::Orthographic(p) => {
+                p.far = far;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approx_equal_angle_wraps_around() {
+        assert!(approx_equal_angle(PI, -PI, 1e-3));
+        assert!(approx_equal_angle(PI - 1e-4, -PI + 1e-4, 1e-3));
+        assert!(approx_equal_angle(0.0, TAU, 1e-3));
+        assert!(!approx_equal_angle(PI, 0.0, 1e-3));
+        assert!(!approx_equal_angle(-PI, -PI / 2.0, 1e-3));
+        assert!(!approx_equal_angle(0.1, 0.0, 1e-3));
+    }
+
+    #[test]
+    fn switching_projection_repeatedly_keeps_the_view_height() {
+        let viewport_size = Some(Vec2::new(1280.0, 720.0));
+        let mut projection = Projection::default();
+        let mut other_projection =
+            Projection::Orthographic(OrthographicProjection::default_3d());
+        let start_radius = 7.0;
+        let mut radius = start_radius;
+        for index in 0..10 {
+            // Zoom in, then zoom back out after the next switch
+            radius *= if index % 2 == 0 { 0.8 } else { 1.25 };
+            let height = focus_view_height(&projection, radius, viewport_size);
+            radius = switched_projection_radius(
+                &projection,
+                &other_projection,
+                radius,
+                viewport_size,
+            );
+            std::mem::swap(&mut projection, &mut other_projection);
+            let switched_height =
+                focus_view_height(&projection, radius, viewport_size);
+            assert!((switched_height - height).abs() < 1e-4);
+        }
+        assert!(matches!(projection, Projection::Perspective(_)));
+        assert!((radius - start_radius).abs() < 1e-4);
+    }
 }
@@ -0,0 +1,563 @@
+use std::collections::BTreeMap;
+
+use bevy::{prelude::*, render::camera::ScalingMode};
+
+use crate::{
+    fly::FlyCameraController, math, orbit::OrbitCameraController,
+    transition::ViewTransition, BlendyCamerasDisabled, OtherProjection,
+    DEFAULT_ORBIT_RADIUS,
+};
+
+/// Full view state of a camera, to save it across app restarts, taken with
+/// [`OrbitCameraController::snapshot`] and restored with
+/// [`OrbitCameraController::apply_snapshot`]. Requires the `serialize` cargo
+/// feature.
+#[derive(
+    Debug, Clone, PartialEq, Reflect, serde::Serialize, serde::Deserialize,
+)]
+#[reflect(Serialize, Deserialize)]
+pub struct CameraViewSnapshot {
+    /// The point the camera looks at
+    pub focus: Vec3,
+    /// Yaw in radian, with the conventions of
+    /// [`math::yaw_pitch_radius_from_translation`]
+    pub yaw: f32,
+    /// Pitch in radian, with the conventions of
+    /// [`math::yaw_pitch_radius_from_translation`]
+    pub pitch: f32,
+    /// The distance between the camera and the `focus`, or the scale of the
+    /// projection if orthographic
+    pub radius: f32,
+    /// Whether the [`FlyCameraController`] is enabled instead of the
+    /// [`OrbitCameraController`]
+    pub is_fly_enabled: bool,
+    /// The camera projection
+    pub projection: ProjectionSnapshot,
+    /// The projection used on the next projection switch, see
+    /// [`OtherProjection`]
+    pub other_projection: Option<ProjectionSnapshot>,
+}
+
+/// Serializable parameters of a camera [`Projection`], see
+/// [`CameraViewSnapshot`]
+#[derive(Debug, Clone, Copy, Reflect, serde::Serialize, serde::Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub enum ProjectionSnapshot {
+    /// A [`PerspectiveProjection`]
+    Perspective {
+        /// Vertical field of view in radian
+        fov: f32,
+        /// Near clipping plane
+        near: f32,
+        /// Far clipping plane
+        far: f32,
+    },
+    /// An [`OrthographicProjection`]
+    Orthographic {
+        /// Near clipping plane
+        near: f32,
+        /// Far clipping plane
+        far: f32,
+        /// Where the origin of the viewport is, see
+        /// [`OrthographicProjection::viewport_origin`]
+        viewport_origin: Vec2,
+        /// How the projection scales with the viewport
+        scaling_mode: ScalingMode,
+        /// Scale of the projection
+        scale: f32,
+    },
+}
+
+// `ScalingMode` does not implement `PartialEq`
+impl PartialEq for ProjectionSnapshot {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::Perspective { fov, near, far },
+                Self::Perspective {
+                    fov: other_fov,
+                    near: other_near,
+                    far: other_far,
+                },
+            ) => (fov, near, far) == (other_fov, other_near, other_far),
+            (
+                Self::Orthographic {
+                    near,
+                    far,
+                    viewport_origin,
+                    scaling_mode,
+                    scale,
+                },
+                Self::Orthographic {
+                    near: other_near,
+                    far: other_far,
+                    viewport_origin: other_viewport_origin,
+                    scaling_mode: other_scaling_mode,
+                    scale: other_scale,
+                },
+            ) => {
+                (near, far, viewport_origin, scale)
+                    == (
+                        other_near,
+                        other_far,
+                        other_viewport_origin,
+                        other_scale,
+                    )
+                    && scaling_mode_eq(scaling_mode, other_scaling_mode)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn scaling_mode_eq(a: &ScalingMode, b: &ScalingMode) -> bool {
+    match (*a, *b) {
+        (
+            ScalingMode::Fixed { width, height },
+            ScalingMode::Fixed {
+                width: other_width,
+                height: other_height,
+            },
+        ) => (width, height) == (other_width, other_height),
+        (ScalingMode::WindowSize, ScalingMode::WindowSize) => true,
+        (
+            ScalingMode::AutoMin {
+                min_width,
+                min_height,
+            },
+            ScalingMode::AutoMin {
+                min_width: other_width,
+                min_height: other_height,
+            },
+        ) => (min_width, min_height) == (other_width, other_height),
+        (
+            ScalingMode::AutoMax {
+                max_width,
+                max_height,
+            },
+            ScalingMode::AutoMax {
+                max_width: other_width,
+                max_height: other_height,
+            },
+        ) => (max_width, max_height) == (other_width, other_height),
+        (
+            ScalingMode::FixedVertical { viewport_height },
+            ScalingMode::FixedVertical {
+                viewport_height: other_height,
+            },
+        ) => viewport_height == other_height,
+        (
+            ScalingMode::FixedHorizontal { viewport_width },
+            ScalingMode::FixedHorizontal {
+                viewport_width: other_width,
+            },
+        ) => viewport_width == other_width,
+        _ => false,
+    }
+}
+
+impl ProjectionSnapshot {
+    /// Parameters of `projection`
+    pub fn from_projection(projection: &Projection) -> Self {
+        match projection {
+            Projection::Perspective(p) => Self::Perspective {
+                fov: p.fov,
+                near: p.near,
+                far: p.far,
+            },
+            Projection::Orthographic(p) => Self::Orthographic {
+                near: p.near,
+                far: p.far,
+                viewport_origin: p.viewport_origin,
+                scaling_mode: p.scaling_mode,
+                scale: p.scale,
+            },
+        }
+    }
+
+    /// Projection with these parameters. The aspect ratio and area are
+    /// updated by Bevy from the viewport.
+    pub fn to_projection(&self) -> Projection {
+        match *self {
+            Self::Perspective { fov, near, far } => {
+                Projection::Perspective(PerspectiveProjection {
+                    fov,
+                    near,
+                    far,
+                    ..default()
+                })
+            }
+            Self::Orthographic {
+                near,
+                far,
+                viewport_origin,
+                scaling_mode,
+                scale,
+            } => Projection::Orthographic(OrthographicProjection {
+                near,
+                far,
+                viewport_origin,
+                scaling_mode,
+                scale,
+                ..OrthographicProjection::default_3d()
+            }),
+        }
+    }
+}
+
+impl OrbitCameraController {
+    /// Current view of the camera. When the controller is disabled or not
+    /// initialized, the view is computed from the transform and the
+    /// controller is assumed to be disabled in favor of the
+    /// [`FlyCameraController`]. The `other_projection` is `None`, set it from
+    /// the [`OtherProjection`] of the camera if needed.
+    pub fn snapshot(
+        &self,
+        transform: &Transform,
+        projection: &Projection,
+    ) -> CameraViewSnapshot {
+        let state = match (self.is_enabled, self.yaw, self.pitch, self.radius) {
+            (true, Some(yaw), Some(pitch), Some(radius)) => {
+                (self.focus, yaw, pitch, radius)
+            }
+            _ => {
+                let radius = match projection {
+                    Projection::Orthographic(p) => p.scale,
+                    Projection::Perspective(_) => {
+                        self.radius.unwrap_or(DEFAULT_ORBIT_RADIUS)
+                    }
+                };
+                let (yaw, pitch, _roll) =
+                    transform.rotation.to_euler(EulerRot::YXZ);
+                let focus =
+                    transform.translation + transform.forward() * radius;
+                (focus, yaw, -pitch, radius)
+            }
+        };
+        let (focus, yaw, pitch, radius) = state;
+        CameraViewSnapshot {
+            focus,
+            yaw,
+            pitch,
+            radius,
+            is_fly_enabled: !self.is_enabled,
+            projection: ProjectionSnapshot::from_projection(projection),
+            other_projection: None,
+        }
+    }
+
+    /// Restore a view taken with [`Self::snapshot`]. The controller is
+    /// initialized and the transform and projection are updated immediately,
+    /// so that the next rendered frame already shows the view. The
+    /// `other_projection` is updated if given, and the controller is
+    /// disabled if the snapshot has the [`FlyCameraController`] enabled,
+    /// which is left to the caller to enable.
+    pub fn apply_snapshot(
+        &mut self,
+        snapshot: &CameraViewSnapshot,
+        transform: &mut Transform,
+        projection: &mut Projection,
+        other_projection: Option<&mut OtherProjection>,
+    ) {
+        *projection = snapshot.projection.to_projection();
+        if let (Some(other_projection), Some(other_snapshot)) =
+            (other_projection, snapshot.other_projection)
+        {
+            other_projection.set(other_snapshot.to_projection());
+        }
+        self.focus = snapshot.focus;
+        self.yaw = Some(snapshot.yaw);
+        self.pitch = Some(snapshot.pitch);
+        self.radius = Some(snapshot.radius);
+        self.is_enabled = !snapshot.is_fly_enabled;
+        self.is_initialized = true;
+        self.force_update = true;
+        math::update_orbit_transform(
+            snapshot.yaw,
+            snapshot.pitch,
+            snapshot.radius,
+            snapshot.focus,
+            transform,
+            projection,
+            self.ortho_placement,
+        );
+    }
+}
+
+/// Component to save the view of the camera in the [`PersistedCameraViews`]
+/// resource whenever it changes, and to restore the view saved with the
+/// same key when the component is added. The camera must have an
+/// [`OrbitCameraController`].
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct PersistCameraView {
+    /// Key of the view in the [`PersistedCameraViews`], stable across app
+    /// restarts unlike the camera entity
+    pub key: String,
+}
+
+/// Resource with the views of the cameras with a [`PersistCameraView`]. It
+/// is not added by the plugin: insert it, e.g. loaded from a file, to
+/// restore and save the views, and write it back to the file when it
+/// changes.
+#[derive(
+    Resource,
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Reflect,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[reflect(Resource, Default, Serialize, Deserialize)]
+pub struct PersistedCameraViews {
+    /// The saved views, by key
+    pub views: BTreeMap<String, CameraViewSnapshot>,
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn restore_persisted_views_system(
+    mut commands: Commands,
+    persisted: Option<Res<PersistedCameraViews>>,
+    mut cameras_query: Query<
+        (
+            Entity,
+            &PersistCameraView,
+            &mut OrbitCameraController,
+            Option<&mut FlyCameraController>,
+            &mut Transform,
+            &mut Projection,
+            Option<&mut OtherProjection>,
+        ),
+        (Added<PersistCameraView>, Without<BlendyCamerasDisabled>),
+    >,
+) {
+    let Some(persisted) = persisted else {
+        return;
+    };
+    for (
+        entity,
+        persist,
+        mut orbit_controller,
+        fly_controller_opt,
+        mut transform,
+        mut projection,
+        other_projection_opt,
+    ) in cameras_query.iter_mut()
+    {
+        let Some(snapshot) = persisted.views.get(&persist.key) else {
+            continue;
+        };
+        let has_other_projection = other_projection_opt.is_some();
+        orbit_controller.apply_snapshot(
+            snapshot,
+            &mut transform,
+            &mut projection,
+            other_projection_opt.map(Mut::into_inner),
+        );
+        match fly_controller_opt {
+            Some(mut fly_controller) => {
+                fly_controller.is_enabled = snapshot.is_fly_enabled;
+            }
+            None => orbit_controller.is_enabled = true,
+        }
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.remove::<ViewTransition>();
+        if let (false, Some(other_snapshot)) =
+            (has_other_projection, snapshot.other_projection)
+        {
+            entity_commands
+                .insert(OtherProjection(other_snapshot.to_projection()));
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn persist_views_system(
+    persisted: Option<ResMut<PersistedCameraViews>>,
+    cameras_query: Query<
+        (
+            &PersistCameraView,
+            &OrbitCameraController,
+            Option<&FlyCameraController>,
+            &Transform,
+            &Projection,
+            Option<&OtherProjection>,
+        ),
+        Without<BlendyCamerasDisabled>,
+    >,
+) {
+    let Some(mut persisted) = persisted else {
+        return;
+    };
+    for (
+        persist,
+        orbit_controller,
+        fly_controller_opt,
+        transform,
+        projection,
+        other_projection_opt,
+    ) in cameras_query.iter()
+    {
+        let mut snapshot = orbit_controller.snapshot(transform, projection);
+        snapshot.is_fly_enabled = fly_controller_opt
+            .is_some_and(|fly_controller| fly_controller.is_enabled);
+        snapshot.other_projection = other_projection_opt
+            .map(|other| ProjectionSnapshot::from_projection(other.get()));
+        // Only mark the resource as changed when a view changed
+        if persisted.views.get(&persist.key) != Some(&snapshot) {
+            persisted.views.insert(persist.key.clone(), snapshot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{assert_transform_approx_eq, test_app},
+        SwitchProjection,
+    };
+
+    fn perspective_snapshot() -> CameraViewSnapshot {
+        CameraViewSnapshot {
+            focus: Vec3::new(1.0, 2.0, 3.0),
+            yaw: 0.5,
+            pitch: 0.25,
+            radius: 8.0,
+            is_fly_enabled: false,
+            projection: ProjectionSnapshot::Perspective {
+                fov: 0.6,
+                near: 0.2,
+                far: 500.0,
+            },
+            other_projection: Some(ProjectionSnapshot::Orthographic {
+                near: -10.0,
+                far: 10.0,
+                viewport_origin: Vec2::new(0.5, 0.5),
+                scaling_mode: ScalingMode::FixedVertical {
+                    viewport_height: 2.0,
+                },
+                scale: 4.0,
+            }),
+        }
+    }
+
+    /// Orthographic projection, with the perspective one as the other. The
+    /// radius is the scale of the projection.
+    fn orthographic_snapshot() -> CameraViewSnapshot {
+        let snapshot = perspective_snapshot();
+        CameraViewSnapshot {
+            radius: 4.0,
+            projection: snapshot.other_projection.unwrap(),
+            other_projection: Some(snapshot.projection),
+            ..snapshot
+        }
+    }
+
+    /// Spawn a perspective camera restoring the view saved with `snapshot`
+    fn spawn_persisted_camera(
+        app: &mut App,
+        snapshot: CameraViewSnapshot,
+    ) -> Entity {
+        app.insert_resource(PersistedCameraViews {
+            views: BTreeMap::from([("main".to_string(), snapshot)]),
+        });
+        app.world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 0.0, 5.0),
+                OrbitCameraController::default(),
+                PersistCameraView {
+                    key: "main".to_string(),
+                },
+            ))
+            .id()
+    }
+
+    #[test]
+    fn snapshot_survives_a_serde_round_trip() {
+        for snapshot in [perspective_snapshot(), orthographic_snapshot()] {
+            let serialized = ron::to_string(&snapshot).unwrap();
+            let deserialized: CameraViewSnapshot =
+                ron::from_str(&serialized).unwrap();
+            assert_eq!(deserialized, snapshot);
+        }
+        let views = PersistedCameraViews {
+            views: BTreeMap::from([
+                ("perspective".to_string(), perspective_snapshot()),
+                ("orthographic".to_string(), orthographic_snapshot()),
+            ]),
+        };
+        let serialized = ron::to_string(&views).unwrap();
+        let deserialized: PersistedCameraViews =
+            ron::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, views);
+    }
+
+    #[test]
+    fn restored_view_is_final_on_the_first_update() {
+        let snapshot = perspective_snapshot();
+        let mut expected = Transform::default();
+        math::update_orbit_transform(
+            snapshot.yaw,
+            snapshot.pitch,
+            snapshot.radius,
+            snapshot.focus,
+            &mut expected,
+            &mut snapshot.projection.to_projection(),
+            default(),
+        );
+        let mut app = test_app();
+        let camera = spawn_persisted_camera(&mut app, snapshot.clone());
+        for _ in 0..3 {
+            app.update();
+            assert_transform_approx_eq(
+                app.world().get::<Transform>(camera).unwrap(),
+                &expected,
+                1e-4,
+            );
+        }
+        let controller =
+            app.world().get::<OrbitCameraController>(camera).unwrap();
+        let transform = app.world().get::<Transform>(camera).unwrap();
+        let projection = app.world().get::<Projection>(camera).unwrap();
+        let taken = controller.snapshot(transform, projection);
+        assert_eq!(taken.focus, snapshot.focus);
+        assert!((taken.yaw - snapshot.yaw).abs() < 1e-5);
+        assert!((taken.pitch - snapshot.pitch).abs() < 1e-5);
+        assert!((taken.radius - snapshot.radius).abs() < 1e-5);
+        assert_eq!(taken.projection, snapshot.projection);
+    }
+
+    #[test]
+    fn restoring_another_projection_kind_updates_the_other_projection() {
+        let snapshot = orthographic_snapshot();
+        let mut app = test_app();
+        let camera = spawn_persisted_camera(&mut app, snapshot.clone());
+        app.update();
+        let projection = app.world().get::<Projection>(camera).unwrap();
+        assert_eq!(
+            ProjectionSnapshot::from_projection(projection),
+            snapshot.projection
+        );
+        let other_projection =
+            app.world().get::<OtherProjection>(camera).unwrap();
+        assert_eq!(
+            Some(ProjectionSnapshot::from_projection(other_projection.get())),
+            snapshot.other_projection
+        );
+        // The restored projections are used when switching
+        app.world_mut().send_event(SwitchProjection {
+            camera_entity: camera,
+        });
+        app.update();
+        let projection = app.world().get::<Projection>(camera).unwrap();
+        let Projection::Perspective(perspective) = projection else {
+            panic!("the projection is not perspective");
+        };
+        assert_eq!(perspective.fov, 0.6);
+    }
+}
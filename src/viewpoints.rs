@@ -1,16 +1,127 @@
-use std::f32::consts::{FRAC_PI_2, PI};
+use std::{
+    f32::consts::{FRAC_PI_2, FRAC_PI_4, PI},
+    time::Duration,
+};
 
-use bevy::prelude::*;
+use bevy::{prelude::*, window::PrimaryWindow};
 
 use crate::{
     // ActiveCameraData,
+    bookmarks::ViewBookmark,
     fly::FlyCameraController,
+    get_window_if_cursor_in_camera_viewport,
+    math,
     orbit::OrbitCameraController,
+    raycast::{
+        get_cursor_ray, get_nearest_included_intersection, RaycastFilter,
+        ViewportCursor,
+    },
+    switch_camera_projection,
+    synchronized_views::SynchronizedViews,
+    transition::{OrbitState, ViewTransition},
     utils,
+    AutoOrthographic,
+    BlendyCamerasDisabled,
+    CameraViewChanged,
+    OtherProjection,
+    ProjectionSwitched,
+    ViewChangeCause,
 };
 
+/// Pitch of an isometric view, the angle between the horizontal plane and the
+/// diagonal of a cube: `atan(1 / sqrt(2))`, about 35.264 degrees
+const ISOMETRIC_PITCH: f32 = 0.615_479_7;
+
+/// Default tolerance in radian when detecting viewpoints
+const DEFAULT_EPSILON: f32 = 0.001;
+
+/// Resource with the tolerances used to detect the viewpoint of a camera
+#[derive(Resource, Debug, Clone, Copy, Reflect)]
+#[reflect(Resource, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ViewpointDetectionConfig {
+    /// Tolerance in radian on yaw and pitch to detect a predefined viewpoint.
+    /// Defaults to `0.001`.
+    pub epsilon: f32,
+    /// Tolerance in radian on yaw and pitch to keep reporting a previously
+    /// detected viewpoint, see [`Viewpoint::from_yaw_pitch_with_hysteresis`].
+    /// Should be larger than `epsilon`. Defaults to `0.01`.
+    pub exit_epsilon: f32,
+}
+
+impl Default for ViewpointDetectionConfig {
+    fn default() -> Self {
+        Self {
+            epsilon: DEFAULT_EPSILON,
+            exit_epsilon: 0.01,
+        }
+    }
+}
+
+/// Corner of the scene from which an isometric view looks
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Reflect)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum IsoCorner {
+    /// View from the front, right and top
+    FrontRightTop,
+    /// View from the front, left and top
+    FrontLeftTop,
+    /// View from the back, right and top
+    BackRightTop,
+    /// View from the back, left and top
+    BackLeftTop,
+    /// View from the front, right and bottom
+    FrontRightBottom,
+    /// View from the front, left and bottom
+    FrontLeftBottom,
+    /// View from the back, right and bottom
+    BackRightBottom,
+    /// View from the back, left and bottom
+    BackLeftBottom,
+}
+
+impl IsoCorner {
+    /// All the corners
+    pub const ALL: &'static [IsoCorner] = &[
+        Self::FrontRightTop,
+        Self::FrontLeftTop,
+        Self::BackRightTop,
+        Self::BackLeftTop,
+        Self::FrontRightBottom,
+        Self::FrontLeftBottom,
+        Self::BackRightBottom,
+        Self::BackLeftBottom,
+    ];
+
+    fn to_yaw_pitch(self) -> (f32, f32) {
+        match self {
+            Self::FrontRightTop => (FRAC_PI_4, ISOMETRIC_PITCH),
+            Self::FrontLeftTop => (-FRAC_PI_4, ISOMETRIC_PITCH),
+            Self::BackRightTop => (3.0 * FRAC_PI_4, ISOMETRIC_PITCH),
+            Self::BackLeftTop => (-3.0 * FRAC_PI_4, ISOMETRIC_PITCH),
+            Self::FrontRightBottom => (FRAC_PI_4, -ISOMETRIC_PITCH),
+            Self::FrontLeftBottom => (-FRAC_PI_4, -ISOMETRIC_PITCH),
+            Self::BackRightBottom => (3.0 * FRAC_PI_4, -ISOMETRIC_PITCH),
+            Self::BackLeftBottom => (-3.0 * FRAC_PI_4, -ISOMETRIC_PITCH),
+        }
+    }
+}
+
 /// Point of view of a camera, looking in the oposite direction
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
 pub enum Viewpoint {
     /// Custom user viewpoint
     User {
@@ -31,9 +142,49 @@ pub enum Viewpoint {
     Left,
     /// View from right
     Right,
+    /// Isometric view from a corner
+    Isometric {
+        /// The corner to view from
+        corner: IsoCorner,
+    },
 }
 
 impl Viewpoint {
+    /// All the predefined viewpoints, i.e. all but [`Viewpoint::User`]. Useful
+    /// to list them in menus.
+    pub const ALL: &'static [Viewpoint] = &[
+        Self::Top,
+        Self::Bottom,
+        Self::Front,
+        Self::Back,
+        Self::Left,
+        Self::Right,
+        Self::Isometric {
+            corner: IsoCorner::FrontRightTop,
+        },
+        Self::Isometric {
+            corner: IsoCorner::FrontLeftTop,
+        },
+        Self::Isometric {
+            corner: IsoCorner::BackRightTop,
+        },
+        Self::Isometric {
+            corner: IsoCorner::BackLeftTop,
+        },
+        Self::Isometric {
+            corner: IsoCorner::FrontRightBottom,
+        },
+        Self::Isometric {
+            corner: IsoCorner::FrontLeftBottom,
+        },
+        Self::Isometric {
+            corner: IsoCorner::BackRightBottom,
+        },
+        Self::Isometric {
+            corner: IsoCorner::BackLeftBottom,
+        },
+    ];
+
     // Not used at the moment
     // pub fn to_view_direction(self) -> Vec3 {
     //     match self {
@@ -55,118 +206,1169 @@ impl Viewpoint {
             Self::Back => (PI, 0.0),
             Self::Left => (-FRAC_PI_2, 0.0),
             Self::Right => (FRAC_PI_2, 0.0),
+            Self::Isometric { corner } => corner.to_yaw_pitch(),
         }
     }
 
-    fn from_yaw_pitch(yaw: f32, pitch: f32) -> Self {
-        // println!("{yaw} {pitch}");
-        if utils::approx_equal(yaw, 0.0)
-            && utils::approx_equal(pitch, FRAC_PI_2)
-        {
+    /// Transform of a camera orbiting around `focus` at `radius` from this
+    /// viewpoint, looking at the focus without roll, like the one the
+    /// [`OrbitCameraController`] moves to with a [`ViewpointEvent`]. Useful to
+    /// preview a viewpoint without moving the camera.
+    pub fn to_transform(self, focus: Vec3, radius: f32) -> Transform {
+        let (yaw, pitch) = self.to_yaw_pitch();
+        math::camera_transform_from_orbit(yaw, pitch, radius, focus)
+    }
+
+    /// The viewpoint looking from the opposite side, e.g. `Bottom` for `Top`
+    /// or the opposite corner for an isometric view
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::User { yaw, pitch } => Self::User {
+                yaw: utils::wrap_angle(yaw + PI),
+                pitch: -pitch,
+            },
+            Self::Top => Self::Bottom,
+            Self::Bottom => Self::Top,
+            Self::Front => Self::Back,
+            Self::Back => Self::Front,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+            Self::Isometric { corner } => Self::Isometric {
+                corner: match corner {
+                    IsoCorner::FrontRightTop => IsoCorner::BackLeftBottom,
+                    IsoCorner::FrontLeftTop => IsoCorner::BackRightBottom,
+                    IsoCorner::BackRightTop => IsoCorner::FrontLeftBottom,
+                    IsoCorner::BackLeftTop => IsoCorner::FrontRightBottom,
+                    IsoCorner::FrontRightBottom => IsoCorner::BackLeftTop,
+                    IsoCorner::FrontLeftBottom => IsoCorner::BackRightTop,
+                    IsoCorner::BackRightBottom => IsoCorner::FrontLeftTop,
+                    IsoCorner::BackLeftBottom => IsoCorner::FrontRightTop,
+                },
+            },
+        }
+    }
+
+    /// The viewpoint a quarter turn clockwise around the vertical axis, seen
+    /// from above, e.g. `Left` for `Front`. `Top` and `Bottom` are unchanged.
+    pub fn rotated_cw(self) -> Self {
+        self.rotated_yaw(-FRAC_PI_2)
+    }
+
+    /// The viewpoint a quarter turn counterclockwise around the vertical
+    /// axis, seen from above, e.g. `Right` for `Front`. `Top` and `Bottom` are
+    /// unchanged.
+    pub fn rotated_ccw(self) -> Self {
+        self.rotated_yaw(FRAC_PI_2)
+    }
+
+    fn rotated_yaw(self, delta_yaw: f32) -> Self {
+        match self {
+            Self::Top | Self::Bottom => self,
+            Self::User { yaw, pitch } => Self::User {
+                yaw: utils::wrap_angle(yaw + delta_yaw),
+                pitch,
+            },
+            _ => {
+                // Pick the nearest predefined viewpoint rather than detecting
+                // it within a tolerance, the rotated angles are exact up to
+                // rounding errors
+                let (yaw, pitch) = self.to_yaw_pitch();
+                let yaw = yaw + delta_yaw;
+                Self::ALL
+                    .iter()
+                    .copied()
+                    .min_by(|a, b| {
+                        a.distance_to(yaw, pitch)
+                            .total_cmp(&b.distance_to(yaw, pitch))
+                    })
+                    .unwrap_or(self)
+            }
+        }
+    }
+
+    /// Sum of the yaw and pitch differences in radian to this viewpoint
+    fn distance_to(self, yaw: f32, pitch: f32) -> f32 {
+        let (viewpoint_yaw, viewpoint_pitch) = self.to_yaw_pitch();
+        utils::wrap_angle(yaw - viewpoint_yaw).abs()
+            + (pitch - viewpoint_pitch).abs()
+    }
+
+    /// Whether the yaw and pitch in radian are within `epsilon` of this
+    /// viewpoint
+    fn is_near(self, yaw: f32, pitch: f32, epsilon: f32) -> bool {
+        let (viewpoint_yaw, viewpoint_pitch) = self.to_yaw_pitch();
+        utils::approx_equal_angle(yaw, viewpoint_yaw, epsilon)
+            && utils::approx_equal(pitch, viewpoint_pitch, epsilon)
+    }
+
+    /// Calculate [`Viewpoint`] from yaw and pitch in radian. Returns
+    /// [`Viewpoint::User`] if they are not within `epsilon` of any of the
+    /// predefined viewpoints.
+    pub fn from_yaw_pitch(yaw: f32, pitch: f32, epsilon: f32) -> Self {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|viewpoint| viewpoint.is_near(yaw, pitch, epsilon))
+            .unwrap_or(Self::User { yaw, pitch })
+    }
+
+    /// Same as [`Viewpoint::from_yaw_pitch`] but keeps reporting the
+    /// `previous` predefined viewpoint until the yaw or pitch moves further
+    /// than [`ViewpointDetectionConfig::exit_epsilon`] from it. This avoids
+    /// flickering between a predefined viewpoint and [`Viewpoint::User`]
+    /// when the orientation is slightly off, e.g. after an animation.
+    pub fn from_yaw_pitch_with_hysteresis(
+        previous: Option<Viewpoint>,
+        yaw: f32,
+        pitch: f32,
+        config: &ViewpointDetectionConfig,
+    ) -> Self {
+        match previous {
+            Some(previous)
+                if !matches!(previous, Self::User { .. })
+                    && previous.is_near(yaw, pitch, config.exit_epsilon) =>
+            {
+                previous
+            }
+            _ => Self::from_yaw_pitch(yaw, pitch, config.epsilon),
+        }
+    }
+
+    /// Whether the viewpoint looks along one of the world axes
+    pub fn is_axis(self) -> bool {
+        matches!(
+            self,
             Self::Top
-        } else if utils::approx_equal(yaw, 0.0)
-            && utils::approx_equal(pitch, -FRAC_PI_2)
-        {
-            Self::Bottom
-        } else if utils::approx_equal(yaw, 0.0)
-            && utils::approx_equal(pitch, 0.0)
-        {
+                | Self::Bottom
+                | Self::Front
+                | Self::Back
+                | Self::Left
+                | Self::Right
+        )
+    }
+
+    /// Find the axis viewpoint (top, bottom, front, back, left or right)
+    /// nearest to the given yaw and pitch in radian
+    pub fn nearest_to_yaw_pitch(yaw: f32, pitch: f32) -> Self {
+        // Compare view directions rather than angles so that the wrap around
+        // at PI and out of range pitch values are handled
+        let direction = Vec3::new(
+            yaw.sin() * pitch.cos(),
+            pitch.sin(),
+            yaw.cos() * pitch.cos(),
+        );
+        let abs = direction.abs();
+        if abs.y >= abs.x && abs.y >= abs.z {
+            if direction.y > 0.0 {
+                Self::Top
+            } else {
+                Self::Bottom
+            }
+        } else if abs.x >= abs.z {
+            if direction.x > 0.0 {
+                Self::Right
+            } else {
+                Self::Left
+            }
+        } else if direction.z > 0.0 {
             Self::Front
-        } else if (utils::approx_equal(yaw, PI)
-            || utils::approx_equal(yaw, -PI))
-            && utils::approx_equal(pitch, 0.0)
-        {
-            Self::Back
-        } else if utils::approx_equal(yaw, -FRAC_PI_2)
-            && utils::approx_equal(pitch, 0.0)
-        {
-            Self::Left
-        } else if utils::approx_equal(yaw, FRAC_PI_2)
-            && utils::approx_equal(pitch, 0.0)
-        {
-            Self::Right
         } else {
-            Self::User { yaw, pitch }
+            Self::Back
         }
     }
 
-    /// Calculate [`Viewpoint`] from camera [`Transform`]
-    pub fn from_transform(transform: &Transform) -> Self {
+    /// Calculate [`Viewpoint`] from camera [`Transform`]. Returns
+    /// [`Viewpoint::User`] with the camera yaw and pitch if they are not
+    /// within `epsilon` of any of the predefined viewpoints, usually
+    /// [`ViewpointDetectionConfig::epsilon`].
+    pub fn from_transform(transform: &Transform, epsilon: f32) -> Self {
+        let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+        Self::from_yaw_pitch(yaw, -pitch, epsilon)
+    }
+
+    /// Same as [`Viewpoint::from_transform`] but using the tolerances of
+    /// `config` and the hysteresis of
+    /// [`Viewpoint::from_yaw_pitch_with_hysteresis`]
+    pub fn from_transform_with_hysteresis(
+        previous: Option<Viewpoint>,
+        transform: &Transform,
+        config: &ViewpointDetectionConfig,
+    ) -> Self {
         let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
-        Self::from_yaw_pitch(yaw, -pitch)
+        Self::from_yaw_pitch_with_hysteresis(previous, yaw, -pitch, config)
+    }
+
+    /// Calculate [`Viewpoint`] from camera [`Transform`], relative to the
+    /// local axes of a reference entity with the given rotation, with the
+    /// same tolerance as [`Viewpoint::from_transform`]
+    pub fn from_transform_relative(
+        transform: &Transform,
+        reference_rotation: Quat,
+        epsilon: f32,
+    ) -> Self {
+        let rotation = reference_rotation.inverse() * transform.rotation;
+        let (yaw, pitch, _) = rotation.to_euler(EulerRot::YXZ);
+        Self::from_yaw_pitch(yaw, -pitch, epsilon)
     }
 }
 
 /// Event used to set the camera point of view
-#[derive(Event)]
+#[derive(Event, Reflect)]
 pub struct ViewpointEvent {
-    /// The camera for wich to change viewpoint
-    pub camera_entity: Entity,
+    /// The camera for wich to change viewpoint. If `None`, the viewpoint is
+    /// applied to all the cameras with an enabled controller.
+    pub camera_entity: Option<Entity>,
     /// The viewpoint to apply to the camera
     pub viewpoint: Viewpoint,
+    /// Duration of the animated transition to the viewpoint. The camera
+    /// is moved immediately if `None`
+    pub duration: Option<Duration>,
+    /// Entity whose local axes the viewpoint is relative to, e.g. `Front`
+    /// looks at the front of this entity. The viewpoint is relative to the
+    /// world axes if `None`. The scale of the entity is ignored, as well as
+    /// any rotation that would roll the camera.
+    pub relative_to: Option<Entity>,
+}
+
+/// Express yaw and pitch relative to a reference rotation in world space
+fn yaw_pitch_relative_to(
+    yaw: f32,
+    pitch: f32,
+    reference_rotation: Quat,
+) -> (f32, f32) {
+    let rotation =
+        reference_rotation * Quat::from_euler(EulerRot::YXZ, yaw, -pitch, 0.0);
+    let (yaw, pitch, _) = rotation.to_euler(EulerRot::YXZ);
+    (yaw, -pitch)
+}
+
+/// Move the viewpoint the camera is locked to, if any, to `viewpoint`
+fn update_locked_viewpoint(
+    orbit_controller_opt: Option<&mut OrbitCameraController>,
+    synchronized_views_opt: Option<Mut<SynchronizedViews>>,
+    viewpoint: Viewpoint,
+) {
+    if let Some(controller) = orbit_controller_opt {
+        if controller.locked_viewpoint.is_some() {
+            controller.locked_viewpoint = Some(viewpoint);
+        }
+    }
+    if let Some(mut views) = synchronized_views_opt {
+        if views.viewpoint.is_some() {
+            views.viewpoint = Some(viewpoint);
+        }
+    }
+}
+
+/// Event used to snap the camera to the axis viewpoint nearest to its current
+/// orientation
+#[derive(Event, Reflect)]
+pub struct SnapToNearestViewpointEvent {
+    /// The camera to snap. If `None`, all the cameras with an enabled
+    /// controller are snapped.
+    pub camera_entity: Option<Entity>,
+}
+
+/// Move the camera to the given yaw and pitch, immediately or through an
+/// animated transition
+#[allow(clippy::too_many_arguments)]
+fn set_camera_yaw_pitch(
+    commands: &mut Commands,
+    entity: Entity,
+    yaw: f32,
+    pitch: f32,
+    duration: Option<Duration>,
+    transform: &mut Transform,
+    projection: &mut Projection,
+    orbit_controller_opt: Option<&mut OrbitCameraController>,
+    fly_controller_opt: Option<&FlyCameraController>,
+) {
+    if let Some(controller) = orbit_controller_opt {
+        // NOTE: Checking if viewport is active does not work if
+        // no manual manipulation of the camera is done a priory.
+
+        // if controller.is_enabled && active_cam.entity == Some(entity) {
+        if controller.is_enabled {
+            if let Some(duration) = duration {
+                controller.initialize_if_necessary(transform, projection);
+                let Some(start) = OrbitState::from_controller(controller)
+                else {
+                    return;
+                };
+                let end = OrbitState {
+                    yaw,
+                    pitch,
+                    ..start
+                };
+                commands
+                    .entity(entity)
+                    .insert(ViewTransition::orbit(start, end, duration));
+                return;
+            }
+            commands.entity(entity).remove::<ViewTransition>();
+            controller.set_yaw_pitch(yaw, pitch);
+            controller.initialize_if_necessary(transform, projection);
+            // Skip cameras whose controller could not be initialized
+            // instead of panicking
+            let Some(radius) = controller.radius else {
+                return;
+            };
+            math::update_orbit_transform(
+                yaw,
+                pitch,
+                radius,
+                controller.focus,
+                transform,
+                projection,
+                controller.ortho_placement,
+            );
+        }
+    }
+    if let Some(controller) = fly_controller_opt {
+        // if controller.is_enabled && active_cam.entity == Some(entity) {
+        if controller.is_enabled {
+            let rotation = Quat::from_euler(EulerRot::YXZ, yaw, -pitch, 0.0);
+            if let Some(duration) = duration {
+                let end = Transform {
+                    rotation,
+                    ..*transform
+                };
+                commands
+                    .entity(entity)
+                    .insert(ViewTransition::fly(*transform, end, duration));
+            } else {
+                commands.entity(entity).remove::<ViewTransition>();
+                transform.rotation = rotation;
+            }
+        }
+    }
+}
+
+/// Switch to the orthographic projection when reaching an axis viewpoint, and
+/// back to perspective when leaving it, if the switch was automatic
+#[allow(clippy::too_many_arguments)]
+fn update_auto_orthographic(
+    commands: &mut Commands,
+    entity: Entity,
+    is_axis: bool,
+    controller: &mut OrbitCameraController,
+    viewport_size: Option<Vec2>,
+    transform: &mut Transform,
+    projection: &mut Projection,
+    other_projection_opt: Option<Mut<OtherProjection>>,
+    is_auto_orthographic: bool,
+) {
+    let Some(mut other_projection) = other_projection_opt else {
+        return;
+    };
+    if !controller.is_enabled {
+        return;
+    }
+    controller.initialize_if_necessary(transform, projection);
+    if !controller.is_initialized {
+        return;
+    }
+    match *projection {
+        Projection::Perspective(_)
+            if is_axis && controller.auto_ortho_on_viewpoint =>
+        {
+            switch_camera_projection(
+                controller,
+                viewport_size,
+                transform,
+                &mut other_projection.0,
+                projection,
+            );
+            commands.entity(entity).insert(AutoOrthographic);
+            commands.send_event(ProjectionSwitched::new(entity, projection));
+        }
+        Projection::Orthographic(_) if !is_axis && is_auto_orthographic => {
+            switch_camera_projection(
+                controller,
+                viewport_size,
+                transform,
+                &mut other_projection.0,
+                projection,
+            );
+            commands.entity(entity).remove::<AutoOrthographic>();
+            commands.send_event(ProjectionSwitched::new(entity, projection));
+        }
+        _ => {}
+    }
 }
 
 #[allow(clippy::type_complexity)]
 pub(crate) fn viewpoint_system(
+    mut commands: Commands,
     mut ev_read: EventReader<ViewpointEvent>,
+    mut snap_ev_read: EventReader<SnapToNearestViewpointEvent>,
+    references_query: Query<&GlobalTransform>,
     // active_cam: Res<ActiveCameraData>,
     mut cameras_query: Query<
         (
-            // Entity,
+            Entity,
             &mut Transform,
             Option<&mut OrbitCameraController>,
-            Option<&mut FlyCameraController>,
+            Option<&FlyCameraController>,
+            Option<&Camera>,
             &mut Projection,
+            Option<&mut OtherProjection>,
+            Has<AutoOrthographic>,
+            Option<&mut SynchronizedViews>,
+        ),
+        (
+            Or<(With<OrbitCameraController>, With<FlyCameraController>)>,
+            Without<BlendyCamerasDisabled>,
         ),
-        Or<(With<OrbitCameraController>, With<FlyCameraController>)>,
     >,
+    mut changed_ev_write: EventWriter<CameraViewChanged>,
 ) {
     for ViewpointEvent {
         camera_entity,
         viewpoint,
+        duration,
+        relative_to,
     } in ev_read.read()
     {
-        if let Ok((
-            // entity,
+        if let Some(camera_entity) = camera_entity {
+            if !cameras_query.contains(*camera_entity) {
+                warn!("Camera not found while trying to set viewpoint");
+                continue;
+            }
+        }
+        let (yaw, pitch) = viewpoint.to_yaw_pitch();
+        let (yaw, pitch) = match relative_to {
+            Some(reference_entity) => {
+                let Ok(reference_transform) =
+                    references_query.get(*reference_entity)
+                else {
+                    warn!("Reference entity not found while setting viewpoint");
+                    continue;
+                };
+                // Ignore scale, which may be non-uniform
+                let (_, reference_rotation, _) =
+                    reference_transform.to_scale_rotation_translation();
+                yaw_pitch_relative_to(yaw, pitch, reference_rotation)
+            }
+            None => (yaw, pitch),
+        };
+        let locked_viewpoint = match relative_to {
+            Some(_) => Viewpoint::User { yaw, pitch },
+            None => *viewpoint,
+        };
+        for (
+            entity,
             mut transform,
-            orbit_controller_opt,
+            mut orbit_controller_opt,
             fly_controller_opt,
+            camera_opt,
             mut projection,
-        )) = cameras_query.get_mut(*camera_entity)
+            other_projection_opt,
+            is_auto_orthographic,
+            synchronized_views_opt,
+        ) in cameras_query.iter_mut()
         {
-            let (yaw, pitch) = viewpoint.to_yaw_pitch();
-            if let Some(mut controller) = orbit_controller_opt {
-                // NOTE: Checking if viewport is active does not work if
-                // no manual manipulation of the camera is done a priory.
-
-                // if controller.is_enabled && active_cam.entity == Some(entity) {
-                if controller.is_enabled {
-                    controller.yaw = Some(yaw);
-                    controller.pitch = Some(pitch);
-                    controller.initialize_if_necessary(
-                        &mut transform,
-                        &mut projection,
-                    );
-                    utils::update_orbit_transform(
-                        controller.yaw.unwrap(),
-                        controller.pitch.unwrap(),
-                        controller.radius.unwrap(),
-                        controller.focus,
+            if camera_entity
+                .is_some_and(|camera_entity| camera_entity != entity)
+            {
+                continue;
+            }
+            let pre_view = ViewBookmark::from_camera(
+                &transform,
+                orbit_controller_opt.as_deref(),
+                fly_controller_opt,
+                &projection,
+            );
+            // Switch the projection first, so that the transition starts
+            // from the radius converted for the new projection
+            if let Some(ref mut controller) = orbit_controller_opt {
+                update_auto_orthographic(
+                    &mut commands,
+                    entity,
+                    viewpoint.is_axis(),
+                    controller,
+                    camera_opt.and_then(Camera::logical_viewport_size),
+                    &mut transform,
+                    &mut projection,
+                    other_projection_opt,
+                    is_auto_orthographic,
+                );
+            }
+            set_camera_yaw_pitch(
+                &mut commands,
+                entity,
+                yaw,
+                pitch,
+                *duration,
+                &mut transform,
+                &mut projection,
+                orbit_controller_opt.as_deref_mut(),
+                fly_controller_opt,
+            );
+            update_locked_viewpoint(
+                orbit_controller_opt.as_deref_mut(),
+                synchronized_views_opt,
+                locked_viewpoint,
+            );
+            let view = ViewBookmark::from_camera(
+                &transform,
+                orbit_controller_opt.as_deref(),
+                fly_controller_opt,
+                &projection,
+            );
+            if let Some(event) = CameraViewChanged::between(
+                entity,
+                ViewChangeCause::ViewpointEvent,
+                pre_view,
+                view,
+            ) {
+                changed_ev_write.send(event);
+            }
+        }
+    }
+    for SnapToNearestViewpointEvent { camera_entity } in snap_ev_read.read() {
+        if let Some(camera_entity) = camera_entity {
+            if !cameras_query.contains(*camera_entity) {
+                warn!("Camera not found while trying to snap viewpoint");
+                continue;
+            }
+        }
+        for (
+            entity,
+            mut transform,
+            mut orbit_controller_opt,
+            fly_controller_opt,
+            camera_opt,
+            mut projection,
+            other_projection_opt,
+            is_auto_orthographic,
+            synchronized_views_opt,
+        ) in cameras_query.iter_mut()
+        {
+            if camera_entity
+                .is_some_and(|camera_entity| camera_entity != entity)
+            {
+                continue;
+            }
+            let pre_view = ViewBookmark::from_camera(
+                &transform,
+                orbit_controller_opt.as_deref(),
+                fly_controller_opt,
+                &projection,
+            );
+            let (current_yaw, current_pitch) = if let Some(ref mut controller) =
+                orbit_controller_opt
+            {
+                controller
+                    .initialize_if_necessary(&mut transform, &mut projection);
+                let (Some(yaw), Some(pitch)) =
+                    (controller.yaw, controller.pitch)
+                else {
+                    continue;
+                };
+                (yaw, pitch)
+            } else {
+                let (yaw, pitch, _) =
+                    transform.rotation.to_euler(EulerRot::YXZ);
+                (yaw, -pitch)
+            };
+            let nearest_viewpoint =
+                Viewpoint::nearest_to_yaw_pitch(current_yaw, current_pitch);
+            let (yaw, pitch) = nearest_viewpoint.to_yaw_pitch();
+            // Keep the yaw continuous so the camera does not turn the long
+            // way around
+            let yaw = utils::lerp_angle(current_yaw, yaw, 1.0);
+            // Switch the projection first, like for the viewpoint events
+            if let Some(ref mut controller) = orbit_controller_opt {
+                update_auto_orthographic(
+                    &mut commands,
+                    entity,
+                    true,
+                    controller,
+                    camera_opt.and_then(Camera::logical_viewport_size),
+                    &mut transform,
+                    &mut projection,
+                    other_projection_opt,
+                    is_auto_orthographic,
+                );
+            }
+            set_camera_yaw_pitch(
+                &mut commands,
+                entity,
+                yaw,
+                pitch,
+                None,
+                &mut transform,
+                &mut projection,
+                orbit_controller_opt.as_deref_mut(),
+                fly_controller_opt,
+            );
+            update_locked_viewpoint(
+                orbit_controller_opt.as_deref_mut(),
+                synchronized_views_opt,
+                nearest_viewpoint,
+            );
+            let view = ViewBookmark::from_camera(
+                &transform,
+                orbit_controller_opt.as_deref(),
+                fly_controller_opt,
+                &projection,
+            );
+            if let Some(event) = CameraViewChanged::between(
+                entity,
+                ViewChangeCause::ViewpointEvent,
+                pre_view,
+                view,
+            ) {
+                changed_ev_write.send(event);
+            }
+        }
+    }
+}
+
+/// Event to look straight at the surface under the mouse cursor, in the
+/// viewport of the camera, like "Align View to Active" in Blender. The
+/// point under the cursor becomes the focus and the radius is kept. An
+/// [`AlignViewToSurfaceFailed`] event is sent if there is nothing under the
+/// cursor.
+#[derive(Event, Debug, Clone, Reflect)]
+pub struct AlignViewToSurfaceEvent {
+    /// The camera to move
+    pub camera_entity: Entity,
+    /// Duration of the animated transition to the new view. The camera is
+    /// moved immediately if `None`
+    pub duration: Option<Duration>,
+}
+
+/// Event sent when an [`AlignViewToSurfaceEvent`] could not be applied
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub struct AlignViewToSurfaceFailed {
+    /// The camera of the event
+    pub camera_entity: Entity,
+    /// Why the view could not be aligned
+    pub reason: AlignViewError,
+}
+
+/// Reason why an [`AlignViewToSurfaceEvent`] could not be applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum AlignViewError {
+    /// The camera entity does not exist or has no controller
+    CameraNotFound,
+    /// The cursor is not in the viewport of the camera, or there is no
+    /// geometry under it
+    NothingUnderCursor,
+    /// The controllers of the camera are disabled
+    NoEnabledController,
+}
+
+/// Yaw and pitch of a camera looking along `-normal`. The yaw is undefined
+/// when looking straight up or down, `current_yaw` is kept then so that the
+/// camera does not spin.
+fn yaw_pitch_facing_normal(normal: Vec3, current_yaw: f32) -> (f32, f32) {
+    let pitch = normal.y.clamp(-1.0, 1.0).asin();
+    let yaw = if normal.xz().length() < 1e-4 {
+        current_yaw
+    } else {
+        // Keep the yaw continuous so the camera does not turn the long way
+        // around
+        utils::lerp_angle(current_yaw, normal.x.atan2(normal.z), 1.0)
+    };
+    (yaw, pitch)
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn align_view_to_surface_system(
+    mut commands: Commands,
+    mut ev_read: EventReader<AlignViewToSurfaceEvent>,
+    mut cameras_query: Query<
+        (
+            &Camera,
+            &GlobalTransform,
+            &mut Transform,
+            Option<&mut OrbitCameraController>,
+            Option<&FlyCameraController>,
+            &mut Projection,
+            Option<&ViewportCursor>,
+            Option<&mut SynchronizedViews>,
+        ),
+        (
+            Or<(With<OrbitCameraController>, With<FlyCameraController>)>,
+            Without<BlendyCamerasDisabled>,
+        ),
+    >,
+    primary_window: Query<(Entity, &Window), With<PrimaryWindow>>,
+    other_windows: Query<(Entity, &Window), Without<PrimaryWindow>>,
+    mut ray_cast: MeshRayCast,
+    raycast_filter: RaycastFilter,
+    mut changed_ev_write: EventWriter<CameraViewChanged>,
+    mut failed_ev_write: EventWriter<AlignViewToSurfaceFailed>,
+) {
+    for AlignViewToSurfaceEvent {
+        camera_entity,
+        duration,
+    } in ev_read.read()
+    {
+        let camera_entity = *camera_entity;
+        let mut fail = |reason| {
+            failed_ev_write.send(AlignViewToSurfaceFailed {
+                camera_entity,
+                reason,
+            });
+        };
+        let Ok((
+            camera,
+            global_transform,
+            mut transform,
+            mut orbit_controller_opt,
+            fly_controller_opt,
+            mut projection,
+            viewport_cursor_opt,
+            synchronized_views_opt,
+        )) = cameras_query.get_mut(camera_entity)
+        else {
+            warn!("Camera not found while trying to align the view");
+            fail(AlignViewError::CameraNotFound);
+            continue;
+        };
+        let is_orbit_enabled = orbit_controller_opt
+            .as_ref()
+            .is_some_and(|controller| controller.is_enabled);
+        let is_fly_enabled =
+            fly_controller_opt.is_some_and(|controller| controller.is_enabled);
+        if !is_orbit_enabled && !is_fly_enabled {
+            warn!(
+                "Controllers of camera {camera_entity} disabled while trying \
+                to align the view"
+            );
+            fail(AlignViewError::NoEnabledController);
+            continue;
+        }
+        // Reuse the raycast of the cursor made this frame, if any
+        let hit = match viewport_cursor_opt
+            .filter(|cursor| cursor.position().is_some())
+        {
+            Some(cursor) => cursor.hit().map(|hit| (hit.position, hit.normal)),
+            None => get_window_if_cursor_in_camera_viewport(
+                camera,
+                None,
+                &primary_window,
+                &other_windows,
+            )
+            .and_then(|(_window_entity, window)| {
+                get_cursor_ray(camera, global_transform, window)
+            })
+            .and_then(|ray| {
+                get_nearest_included_intersection(
+                    &mut ray_cast,
+                    ray,
+                    &raycast_filter,
+                )
+            })
+            .map(|(_entity, hit)| (hit.point, hit.normal)),
+        };
+        let Some((point, normal)) = hit
+            .and_then(|(point, normal)| Some((point, normal.try_normalize()?)))
+        else {
+            warn!("Nothing under the cursor while trying to align the view");
+            fail(AlignViewError::NothingUnderCursor);
+            continue;
+        };
+        let pre_view = ViewBookmark::from_camera(
+            &transform,
+            orbit_controller_opt.as_deref(),
+            fly_controller_opt,
+            &projection,
+        );
+        if let (Some(controller), true) =
+            (orbit_controller_opt.as_deref_mut(), is_orbit_enabled)
+        {
+            controller.initialize_if_necessary(&mut transform, &mut projection);
+            if let Some(start) = OrbitState::from_controller(controller) {
+                let (yaw, pitch) = yaw_pitch_facing_normal(normal, start.yaw);
+                let end = OrbitState {
+                    focus: point,
+                    yaw,
+                    pitch,
+                    ..start
+                };
+                if let Some(duration) = duration {
+                    commands
+                        .entity(camera_entity)
+                        .insert(ViewTransition::orbit(start, end, *duration));
+                } else {
+                    commands.entity(camera_entity).remove::<ViewTransition>();
+                    end.apply(controller);
+                    math::update_orbit_transform(
+                        end.yaw,
+                        end.pitch,
+                        end.radius,
+                        end.focus,
                         &mut transform,
                         &mut projection,
+                        controller.ortho_placement,
                     );
                 }
+                update_locked_viewpoint(
+                    Some(controller),
+                    synchronized_views_opt,
+                    Viewpoint::User { yaw, pitch },
+                );
+            }
+        } else if is_fly_enabled {
+            // Keep the distance to the surface
+            let (current_yaw, _, _) =
+                transform.rotation.to_euler(EulerRot::YXZ);
+            let (yaw, pitch) = yaw_pitch_facing_normal(normal, current_yaw);
+            let end = Transform {
+                translation: point
+                    + normal * transform.translation.distance(point),
+                rotation: Quat::from_euler(EulerRot::YXZ, yaw, -pitch, 0.0),
+                ..*transform
+            };
+            if let Some(duration) = duration {
+                commands
+                    .entity(camera_entity)
+                    .insert(ViewTransition::fly(*transform, end, *duration));
+            } else {
+                commands.entity(camera_entity).remove::<ViewTransition>();
+                *transform = end;
             }
-            if let Some(controller) = fly_controller_opt {
-                // if controller.is_enabled && active_cam.entity == Some(entity) {
-                if controller.is_enabled {
-                    let rotation =
-                        Quat::from_euler(EulerRot::YXZ, yaw, -pitch, 0.0);
-                    transform.rotation = rotation;
+        }
+        let view = ViewBookmark::from_camera(
+            &transform,
+            orbit_controller_opt.as_deref(),
+            fly_controller_opt,
+            &projection,
+        );
+        if let Some(event) = CameraViewChanged::between(
+            camera_entity,
+            ViewChangeCause::ViewpointEvent,
+            pre_view,
+            view,
+        ) {
+            changed_ev_write.send(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::system::RunSystemOnce,
+        render::{
+            camera::{camera_system, ManualTextureViews},
+            primitives::Aabb,
+        },
+    };
+
+    use super::*;
+    use crate::testing::{
+        assert_transform_approx_eq, set_active_camera, test_app,
+    };
+
+    #[test]
+    fn from_yaw_pitch_round_trip() {
+        for &viewpoint in Viewpoint::ALL {
+            let (yaw, pitch) = viewpoint.to_yaw_pitch();
+            assert_eq!(
+                Viewpoint::from_yaw_pitch(yaw, pitch, DEFAULT_EPSILON),
+                viewpoint
+            );
+        }
+    }
+
+    #[test]
+    fn from_transform_round_trip() {
+        let focus = Vec3::new(1.0, -2.0, 3.0);
+        let users = [
+            Viewpoint::User {
+                yaw: 0.7,
+                pitch: 0.3,
+            },
+            Viewpoint::User {
+                yaw: -2.5,
+                pitch: -1.2,
+            },
+        ];
+        for &viewpoint in Viewpoint::ALL.iter().chain(&users) {
+            let transform = viewpoint.to_transform(focus, 5.0);
+            let round_trip =
+                Viewpoint::from_transform(&transform, DEFAULT_EPSILON);
+            match (viewpoint, round_trip) {
+                (
+                    Viewpoint::User { yaw, pitch },
+                    Viewpoint::User {
+                        yaw: round_trip_yaw,
+                        pitch: round_trip_pitch,
+                    },
+                ) => {
+                    assert!((yaw - round_trip_yaw).abs() < 1e-5);
+                    assert!((pitch - round_trip_pitch).abs() < 1e-5);
                 }
+                _ => assert_eq!(round_trip, viewpoint),
             }
-        } else {
-            warn!("Camera not found while trying to set viewpoint");
         }
     }
+
+    #[test]
+    fn opposite_is_an_involution() {
+        let users = [
+            Viewpoint::User {
+                yaw: 0.7,
+                pitch: 0.3,
+            },
+            Viewpoint::User {
+                yaw: -2.5,
+                pitch: -1.2,
+            },
+        ];
+        for &viewpoint in Viewpoint::ALL.iter().chain(&users) {
+            let opposite = viewpoint.opposite();
+            assert_ne!(opposite, viewpoint);
+            match (viewpoint, opposite.opposite()) {
+                (
+                    Viewpoint::User { yaw, pitch },
+                    Viewpoint::User {
+                        yaw: back_yaw,
+                        pitch: back_pitch,
+                    },
+                ) => {
+                    assert!(utils::approx_equal_angle(yaw, back_yaw, 1e-5));
+                    assert_eq!(pitch, back_pitch);
+                }
+                (_, back) => assert_eq!(back, viewpoint),
+            }
+            // Looking from the other side of the focus
+            let forward = viewpoint.to_transform(Vec3::ZERO, 1.0).forward();
+            let opposite_forward =
+                opposite.to_transform(Vec3::ZERO, 1.0).forward();
+            assert!(forward.dot(*opposite_forward) < -1.0 + 1e-5);
+        }
+    }
+
+    #[test]
+    fn from_yaw_pitch_back_on_both_sides_of_pi() {
+        for yaw in [PI, -PI, PI - 1e-4, -PI + 1e-4] {
+            assert_eq!(
+                Viewpoint::from_yaw_pitch(yaw, 0.0, DEFAULT_EPSILON),
+                Viewpoint::Back
+            );
+        }
+    }
+
+    #[test]
+    fn from_transform_uses_the_given_epsilon() {
+        let transform = Viewpoint::Front.to_transform(Vec3::ZERO, 5.0);
+        let transform = Transform {
+            rotation: Quat::from_rotation_y(0.005) * transform.rotation,
+            ..transform
+        };
+        assert!(matches!(
+            Viewpoint::from_transform(&transform, DEFAULT_EPSILON),
+            Viewpoint::User { .. }
+        ));
+        assert_eq!(
+            Viewpoint::from_transform(&transform, 0.01),
+            Viewpoint::Front
+        );
+        assert_eq!(
+            Viewpoint::from_transform_relative(
+                &transform,
+                Quat::from_rotation_y(0.005),
+                DEFAULT_EPSILON
+            ),
+            Viewpoint::Front
+        );
+    }
+
+    #[test]
+    fn hysteresis_keeps_back_across_pi() {
+        let config = ViewpointDetectionConfig::default();
+        for yaw in [PI - 0.005, -PI + 0.005] {
+            assert!(matches!(
+                Viewpoint::from_yaw_pitch_with_hysteresis(
+                    None, yaw, 0.0, &config
+                ),
+                Viewpoint::User { .. }
+            ));
+            assert_eq!(
+                Viewpoint::from_yaw_pitch_with_hysteresis(
+                    Some(Viewpoint::Back),
+                    yaw,
+                    0.0,
+                    &config
+                ),
+                Viewpoint::Back
+            );
+        }
+        assert!(matches!(
+            Viewpoint::from_yaw_pitch_with_hysteresis(
+                Some(Viewpoint::Back),
+                PI - 0.05,
+                0.0,
+                &config
+            ),
+            Viewpoint::User { .. }
+        ));
+    }
+
+    #[test]
+    fn rotations_cycle_through_the_viewpoints() {
+        assert_eq!(Viewpoint::Front.rotated_ccw(), Viewpoint::Right);
+        assert_eq!(Viewpoint::Right.rotated_ccw(), Viewpoint::Back);
+        assert_eq!(Viewpoint::Back.rotated_ccw(), Viewpoint::Left);
+        assert_eq!(Viewpoint::Front.rotated_cw(), Viewpoint::Left);
+        assert_eq!(Viewpoint::Top.rotated_cw(), Viewpoint::Top);
+        for &viewpoint in Viewpoint::ALL {
+            assert_eq!(viewpoint.rotated_ccw().rotated_cw(), viewpoint);
+            let full_turn = viewpoint
+                .rotated_ccw()
+                .rotated_ccw()
+                .rotated_ccw()
+                .rotated_ccw();
+            assert_eq!(full_turn, viewpoint);
+        }
+    }
+
+    #[test]
+    fn snap_switches_to_orthographic_before_moving() {
+        let mut app = test_app();
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.5, 0.3, 5.0)
+                    .looking_at(Vec3::ZERO, Vec3::Y),
+                OrbitCameraController {
+                    auto_ortho_on_viewpoint: true,
+                    ..default()
+                },
+            ))
+            .id();
+        set_active_camera(app.world_mut(), camera, Vec2::new(800.0, 600.0));
+        app.update();
+        app.world_mut().send_event(SnapToNearestViewpointEvent {
+            camera_entity: Some(camera),
+        });
+        app.update();
+        let world = app.world();
+        let Projection::Orthographic(ref projection) =
+            *world.get::<Projection>(camera).unwrap()
+        else {
+            panic!("the projection is not orthographic after snapping");
+        };
+        let controller = world.get::<OrbitCameraController>(camera).unwrap();
+        assert_eq!(controller.radius, Some(projection.scale));
+        let snapped = *world.get::<Transform>(camera).unwrap();
+        assert_eq!(
+            Viewpoint::from_transform(&snapped, DEFAULT_EPSILON),
+            Viewpoint::Front
+        );
+        // The view does not change on the next frame
+        app.update();
+        assert_transform_approx_eq(
+            app.world().get::<Transform>(camera).unwrap(),
+            &snapped,
+            1e-5,
+        );
+    }
+
+    /// Spawn a 100 units wide plane with the `transform`, hit by the
+    /// raycasts, and return its normal
+    fn spawn_plane(app: &mut App, transform: Transform) -> Vec3 {
+        let mesh = Rectangle::new(100.0, 100.0).mesh().build();
+        let aabb = Aabb::from_min_max(
+            Vec3::new(-50.0, -50.0, 0.0),
+            Vec3::new(50.0, 50.0, 0.0),
+        );
+        let mesh = app.world_mut().resource_mut::<Assets<Mesh>>().add(mesh);
+        // The headless test app does not compute the visibility
+        let mut view_visibility = ViewVisibility::default();
+        view_visibility.set();
+        app.world_mut().spawn((
+            Mesh3d(mesh),
+            aabb,
+            transform,
+            InheritedVisibility::VISIBLE,
+            view_visibility,
+        ));
+        app.update();
+        transform.rotation * Vec3::Z
+    }
+
+    fn align_view(app: &mut App, camera: Entity) -> Vec<AlignViewError> {
+        app.world_mut()
+            .resource_mut::<Events<AlignViewToSurfaceFailed>>()
+            .clear();
+        app.world_mut().send_event(AlignViewToSurfaceEvent {
+            camera_entity: camera,
+            duration: None,
+        });
+        app.update();
+        app.world()
+            .resource::<Events<AlignViewToSurfaceFailed>>()
+            .iter_current_update_events()
+            .map(|event| event.reason)
+            .collect()
+    }
+
+    #[test]
+    fn view_is_aligned_to_the_tilted_surface_under_the_cursor() {
+        let mut app = test_app();
+        app.init_asset::<Image>()
+            .init_resource::<ManualTextureViews>();
+        let window = app
+            .world_mut()
+            .spawn((Window::default(), PrimaryWindow))
+            .id();
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                Projection::default(),
+                Transform::from_xyz(0.0, 0.0, 10.0)
+                    .looking_at(Vec3::ZERO, Vec3::Y),
+                OrbitCameraController::default(),
+            ))
+            .id();
+        // Compute the viewport, which is only computed by the render plugins
+        app.world_mut()
+            .run_system_once(camera_system::<Projection>)
+            .unwrap();
+        let normal = spawn_plane(
+            &mut app,
+            Transform::from_xyz(0.0, 0.0, -2.0)
+                .with_rotation(Quat::from_euler(EulerRot::YXZ, 0.5, -0.3, 0.0)),
+        );
+        let start = *app.world().get::<Transform>(camera).unwrap();
+        assert_eq!(
+            align_view(&mut app, camera),
+            [AlignViewError::NothingUnderCursor]
+        );
+        assert_eq!(*app.world().get::<Transform>(camera).unwrap(), start);
+
+        let mut window = app.world_mut().get_mut::<Window>(window).unwrap();
+        let center = window.size() / 2.0;
+        window.set_cursor_position(Some(center));
+        assert_eq!(align_view(&mut app, camera), []);
+        let world = app.world();
+        let controller = world.get::<OrbitCameraController>(camera).unwrap();
+        assert!(controller
+            .focus
+            .abs_diff_eq(Vec3::new(0.0, 0.0, -2.0), 1e-4));
+        assert_eq!(controller.radius, Some(10.0));
+        let transform = *world.get::<Transform>(camera).unwrap();
+        assert!(transform.forward().abs_diff_eq(-normal, 1e-4));
+        assert!(transform
+            .translation
+            .abs_diff_eq(controller.focus + normal * 10.0, 1e-4));
+        // The view does not change on the next frame
+        app.update();
+        assert_transform_approx_eq(
+            app.world().get::<Transform>(camera).unwrap(),
+            &transform,
+            1e-5,
+        );
+    }
 }